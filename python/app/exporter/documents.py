@@ -1,85 +1,211 @@
 from __future__ import annotations
 
 from pathlib import Path
+from typing import List, Mapping, Optional, Sequence, Tuple
 
 from docx import Document
+from docx.enum.text import WD_ALIGN_PARAGRAPH
 from reportlab.lib.pagesizes import A4
 from reportlab.pdfgen import canvas
 
-from app.models import AdaptationResult, CandidateProfile, ExportPayload
+from app.models import AdaptationResult, CandidateProfile, CoverLetter, ExportPayload, ResumeDocument
+from app.models.entities import Inline
 
+# Ligne de PDF : segments (texte, gras).
+Line = List[Tuple[str, bool]]
 
-def ensure_output_dir() -> Path:
-    base = Path.home() / ".cvgen" / "exports"
+
+def ensure_output_dir(output_dir: Optional[str] = None) -> Path:
+    base = Path(output_dir).expanduser() if output_dir else Path.home() / ".cvgen" / "exports"
     base.mkdir(parents=True, exist_ok=True)
     return base
 
 
+def add_cover_letter(doc, letter: CoverLetter, labels: Mapping[str, str]) -> None:
+    """Mise en page classique : expéditeur à gauche, destinataire et date à droite."""
+    if letter.sender:
+        doc.add_paragraph("\n".join(letter.sender))
+    if letter.recipient:
+        doc.add_paragraph("\n".join(letter.recipient)).alignment = WD_ALIGN_PARAGRAPH.RIGHT
+    if letter.date:
+        doc.add_paragraph(letter.date).alignment = WD_ALIGN_PARAGRAPH.RIGHT
+    if letter.subject:
+        subject = doc.add_paragraph()
+        subject.add_run(f"{labels['subject']} : {letter.subject}").bold = True
+    if letter.salutation:
+        doc.add_paragraph(letter.salutation)
+    for paragraph in letter.paragraphs:
+        doc.add_paragraph(paragraph)
+    if letter.closing:
+        doc.add_paragraph(letter.closing)
+    if letter.signature:
+        doc.add_paragraph(letter.signature)
+
+
+def add_rich_text(paragraph, text: Sequence[Inline]) -> None:
+    for inline in text:
+        run = paragraph.add_run(inline.text)
+        if inline.emphasis:
+            run.bold = True
+
+
+def add_items(paragraph, items: Sequence[Sequence[Inline]]) -> None:
+    for index, item in enumerate(items):
+        if index:
+            paragraph.add_run(", ")
+        add_rich_text(paragraph, item)
+
+
+def add_resume_document(doc, document: ResumeDocument) -> None:
+    """Sections en titres, entrées avec période et puces, mots-clés en gras."""
+    doc.add_heading(document.name, level=2)
+    for section in document.sections:
+        if section.heading:
+            doc.add_heading(section.heading, level=2)
+        for block in section.blocks:
+            if block.type == "paragraph":
+                add_rich_text(doc.add_paragraph(), block.text)
+            elif block.type == "tags":
+                add_items(doc.add_paragraph(), block.items)
+            elif block.type == "list":
+                for item in block.items:
+                    add_rich_text(doc.add_paragraph(style="List Bullet"), item)
+            elif block.entry is not None:
+                entry = block.entry
+                add_rich_text(doc.add_heading(level=3), entry.title)
+                if entry.period:
+                    doc.add_paragraph().add_run(entry.period).italic = True
+                for bullet in entry.bullets:
+                    add_rich_text(doc.add_paragraph(style="List Bullet"), bullet)
+                if entry.tags:
+                    tags = doc.add_paragraph()
+                    tags.add_run(f"{entry.tags.label} : ").bold = True
+                    add_items(tags, entry.tags.items)
+
+
+def resume_lines(document: ResumeDocument) -> List[Line]:
+    """Mise en page du CV pour le PDF, ligne par ligne, comme sa forme texte."""
+
+    def rich(text: Sequence[Inline], prefix: str = "") -> Line:
+        return [(prefix, False), *((inline.text, inline.emphasis) for inline in text)]
+
+    def items(values: Sequence[Sequence[Inline]], prefix: str = "") -> Line:
+        line: Line = [(prefix, False)]
+        for index, item in enumerate(values):
+            line.extend(rich(item, ", " if index else ""))
+        return line
+
+    lines: List[Line] = [[(document.name, True)]]
+    for section in document.sections:
+        lines.append([])
+        if section.heading:
+            lines.append([(section.heading, True)])
+        for block in section.blocks:
+            if block.type == "paragraph":
+                lines.append(rich(block.text))
+            elif block.type == "tags":
+                lines.append(items(block.items))
+            elif block.type == "list":
+                lines.extend(rich(item, "• ") for item in block.items)
+            elif block.entry is not None:
+                entry = block.entry
+                period = f" ({entry.period})" if entry.period else ""
+                lines.append([*rich(entry.title, "- "), (period, False)])
+                lines.extend(rich(bullet, "    • ") for bullet in entry.bullets)
+                if entry.tags:
+                    lines.append(items(entry.tags.items, f"    {entry.tags.label} : "))
+    return lines
+
+
 def export_docx(
     profile: CandidateProfile,
     adaptation: AdaptationResult,
     output_dir: Path,
+    labels: Mapping[str, str],
 ) -> tuple[Path, Path]:
     doc = Document()
     doc.add_heading(profile.full_name, level=1)
     if profile.summary:
         doc.add_paragraph(profile.summary)
 
-    doc.add_heading("Compétences clés", level=2)
+    doc.add_heading(labels["skills"], level=2)
     doc.add_paragraph(", ".join(profile.skills[:12]))
 
-    doc.add_heading("Expériences", level=2)
+    doc.add_heading(labels["experiences"], level=2)
     for experience in profile.experiences:
         doc.add_heading(f"{experience.role} – {experience.company}", level=3)
-        doc.add_paragraph(f"{experience.start_date} - {experience.end_date or 'Présent'}")
+        doc.add_paragraph(f"{experience.start_date} - {experience.end_date or labels['present']}")
         for achievement in experience.achievements:
             doc.add_paragraph(achievement, style="List Bullet")
 
     doc.add_page_break()
-    doc.add_heading("CV adapté", level=1)
-    for line in adaptation.adapted_resume.splitlines():
-        doc.add_paragraph(line)
+    doc.add_heading(labels["adaptedResume"], level=1)
+    if adaptation.resume_document:
+        add_resume_document(doc, adaptation.resume_document)
+    else:
+        for line in adaptation.adapted_resume.splitlines():
+            doc.add_paragraph(line)
 
     doc.add_page_break()
-    doc.add_heading("Lettre de motivation", level=1)
-    for line in adaptation.adapted_cover_letter.splitlines():
-        doc.add_paragraph(line)
+    doc.add_heading(labels["coverLetter"], level=1)
+    add_cover_letter(doc, adaptation.adapted_cover_letter, labels)
 
     resume_doc = output_dir / "cvgen_resume.docx"
     doc.save(resume_doc)
 
     letter_doc = Document()
-    letter_doc.add_heading("Lettre de motivation", level=1)
-    for paragraph in adaptation.adapted_cover_letter.split("\n\n"):
-        letter_doc.add_paragraph(paragraph)
+    add_cover_letter(letter_doc, adaptation.adapted_cover_letter, labels)
     letter_path = output_dir / "cvgen_letter.docx"
     letter_doc.save(letter_path)
 
     return resume_doc, letter_path
 
 
-def export_pdf(adaptation: AdaptationResult, output_dir: Path) -> Path:
+def export_pdf(adaptation: AdaptationResult, output_dir: Path, labels: Mapping[str, str]) -> Path:
     output_path = output_dir / "cvgen_resume.pdf"
     pdf = canvas.Canvas(str(output_path), pagesize=A4)
     width, height = A4
 
     text_object = pdf.beginText(50, height - 50)
     text_object.setFont("Helvetica-Bold", 16)
-    text_object.textLine("CV adapté")
+    text_object.textLine(labels["adaptedResume"])
     text_object.moveCursor(0, 20)
 
-    text_object.setFont("Helvetica", 11)
-    for line in adaptation.adapted_resume.splitlines():
-        text_object.textLine(line)
+    if adaptation.resume_document:
+        lines = resume_lines(adaptation.resume_document)
+    else:
+        lines = [[(line, False)] for line in adaptation.adapted_resume.splitlines()]
+    for line in lines:
+        for text, bold in line:
+            text_object.setFont("Helvetica-Bold" if bold else "Helvetica", 11)
+            text_object.textOut(text)
+        text_object.textLine("")
     text_object.moveCursor(0, 20)
 
     text_object.setFont("Helvetica-Bold", 16)
-    text_object.textLine("Lettre de motivation")
+    text_object.textLine(labels["coverLetter"])
     text_object.moveCursor(0, 20)
 
+    letter = adaptation.adapted_cover_letter
     text_object.setFont("Helvetica", 11)
-    for line in adaptation.adapted_cover_letter.splitlines():
+    pdf.setFont("Helvetica", 11)
+    for line in letter.sender:
         text_object.textLine(line)
+    for line in [*letter.recipient, letter.date or ""]:
+        if line:
+            pdf.drawRightString(width - 50, text_object.getY(), line)
+            text_object.textLine("")
+    text_object.moveCursor(0, 10)
+    if letter.subject:
+        text_object.setFont("Helvetica-Bold", 11)
+        text_object.textLine(f"{labels['subject']} : {letter.subject}")
+        text_object.setFont("Helvetica", 11)
+        text_object.moveCursor(0, 10)
+    for block in [letter.salutation, *letter.paragraphs, letter.closing]:
+        for line in block.splitlines():
+            text_object.textLine(line)
+        text_object.moveCursor(0, 6)
+    text_object.textLine(letter.signature)
 
     pdf.drawText(text_object)
     pdf.showPage()
@@ -87,10 +213,16 @@ def export_pdf(adaptation: AdaptationResult, output_dir: Path) -> Path:
     return output_path
 
 
-def export_documents(profile: CandidateProfile, adaptation: AdaptationResult, fmt: str) -> ExportPayload:
-    output_dir = ensure_output_dir()
-    resume_docx, letter_docx = export_docx(profile, adaptation, output_dir)
-    resume_pdf = export_pdf(adaptation, output_dir)
+def export_documents(
+    profile: CandidateProfile,
+    adaptation: AdaptationResult,
+    fmt: str,
+    labels: Mapping[str, str],
+    output_dir: Optional[str] = None,
+) -> ExportPayload:
+    output_dir = ensure_output_dir(output_dir)
+    resume_docx, letter_docx = export_docx(profile, adaptation, output_dir, labels)
+    resume_pdf = export_pdf(adaptation, output_dir, labels)
 
     resume_path = resume_pdf if fmt == "pdf" else resume_docx
 
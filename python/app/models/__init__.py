@@ -1,6 +1,7 @@
 from .entities import (
     AdaptationResult,
     CandidateProfile,
+    CoverLetter,
     Education,
     Experience,
     ExtractionPayload,
@@ -9,11 +10,13 @@ from .entities import (
     JobOffer,
     LanguageLevel,
     Project,
+    ResumeDocument,
 )
 
 __all__ = [
     "AdaptationResult",
     "CandidateProfile",
+    "CoverLetter",
     "Education",
     "Experience",
     "ExtractionPayload",
@@ -22,4 +25,5 @@ __all__ = [
     "JobOffer",
     "LanguageLevel",
     "Project",
+    "ResumeDocument",
 ]
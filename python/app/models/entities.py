@@ -162,11 +162,126 @@ class HighlightSpan:
         )
 
 
+@dataclass
+class CoverLetter:
+    salutation: str = ""
+    paragraphs: List[str] = field(default_factory=list)
+    closing: str = ""
+    signature: str = ""
+    sender: List[str] = field(default_factory=list)
+    recipient: List[str] = field(default_factory=list)
+    date: Optional[str] = None
+    subject: Optional[str] = None
+
+    @classmethod
+    def from_dict(cls, data) -> "CoverLetter":
+        # Anciennes adaptations : la lettre est un simple texte.
+        if isinstance(data, str):
+            return cls(paragraphs=[block.strip() for block in data.split("\n\n") if block.strip()])
+        return cls(
+            salutation=data.get("salutation", ""),
+            paragraphs=data.get("paragraphs", []),
+            closing=data.get("closing", ""),
+            signature=data.get("signature", ""),
+            sender=data.get("sender", []),
+            recipient=data.get("recipient", []),
+            date=data.get("date"),
+            subject=data.get("subject"),
+        )
+
+
+@dataclass
+class Inline:
+    text: str
+    emphasis: bool = False
+
+
+def rich_text_from(data: Optional[list]) -> List[Inline]:
+    return [
+        Inline(text=item.get("text", ""), emphasis=item.get("emphasis", False)) for item in data or []
+    ]
+
+
+@dataclass
+class TagLine:
+    label: str
+    items: List[List[Inline]] = field(default_factory=list)
+
+
+@dataclass
+class ResumeEntry:
+    title: List[Inline]
+    period: Optional[str] = None
+    bullets: List[List[Inline]] = field(default_factory=list)
+    tags: Optional[TagLine] = None
+
+
+@dataclass
+class ResumeBlock:
+    """`paragraph` (text), `tags` et `list` (items) ou `entry`."""
+
+    type: str
+    text: List[Inline] = field(default_factory=list)
+    items: List[List[Inline]] = field(default_factory=list)
+    entry: Optional[ResumeEntry] = None
+
+    @classmethod
+    def from_dict(cls, data: dict) -> "ResumeBlock":
+        items = [rich_text_from(item) for item in data.get("items", [])]
+        entry = None
+        if data.get("type") == "entry":
+            tags = data.get("tags")
+            entry = ResumeEntry(
+                title=rich_text_from(data.get("title")),
+                period=data.get("period"),
+                bullets=[rich_text_from(item) for item in data.get("bullets", [])],
+                tags=TagLine(
+                    label=tags.get("label", ""),
+                    items=[rich_text_from(item) for item in tags.get("items", [])],
+                )
+                if tags
+                else None,
+            )
+        return cls(
+            type=data.get("type", "paragraph"),
+            text=rich_text_from(data.get("text")),
+            items=items,
+            entry=entry,
+        )
+
+
+@dataclass
+class ResumeSection:
+    heading: Optional[str] = None
+    blocks: List[ResumeBlock] = field(default_factory=list)
+
+
+@dataclass
+class ResumeDocument:
+    name: str = ""
+    sections: List[ResumeSection] = field(default_factory=list)
+
+    @classmethod
+    def from_dict(cls, data: dict) -> "ResumeDocument":
+        return cls(
+            name=data.get("name", ""),
+            sections=[
+                ResumeSection(
+                    heading=section.get("heading"),
+                    blocks=[ResumeBlock.from_dict(block) for block in section.get("blocks", [])],
+                )
+                for section in data.get("sections", [])
+            ],
+        )
+
+
 @dataclass
 class AdaptationResult:
     adapted_resume: str
-    adapted_cover_letter: str
+    adapted_cover_letter: CoverLetter
     highlights: List[HighlightSpan]
+    # Fourni par Rust à l'export ; le moteur Python ne produit que le texte.
+    resume_document: Optional[ResumeDocument] = None
 
     def to_dict(self) -> dict:
         return dataclass_to_camel(self)
@@ -174,11 +289,14 @@ class AdaptationResult:
     @classmethod
     def from_dict(cls, data: dict) -> "AdaptationResult":
         highlights = [HighlightSpan.from_dict(item) for item in data.get("highlights", [])]
+        document = data.get("resumeDocument")
         return cls(
             adapted_resume=data.get("adaptedResume") or data.get("adapted_resume", ""),
-            adapted_cover_letter=data.get("adaptedCoverLetter")
-            or data.get("adapted_cover_letter", ""),
+            adapted_cover_letter=CoverLetter.from_dict(
+                data.get("adaptedCoverLetter") or data.get("adapted_cover_letter", "")
+            ),
             highlights=highlights,
+            resume_document=ResumeDocument.from_dict(document) if document else None,
         )
 
 
@@ -2,12 +2,12 @@ from __future__ import annotations
 
 import re
 from functools import lru_cache
-from typing import Iterable, List, Sequence
+from typing import Iterable, List, Mapping, Sequence
 from uuid import uuid4
 
 from sentence_transformers import SentenceTransformer, util
 
-from app.models import AdaptationResult, CandidateProfile, HighlightSpan, JobOffer
+from app.models import AdaptationResult, CandidateProfile, CoverLetter, HighlightSpan, JobOffer
 
 MODEL_NAME = "sentence-transformers/all-MiniLM-L6-v2"
 
@@ -30,61 +30,71 @@ def rank_skills(skills: Sequence[str], keywords: Sequence[str]) -> List[str]:
     return [skill for skill, _ in paired]
 
 
-def build_resume(profile: CandidateProfile, keywords: Sequence[str]) -> tuple[str, List[HighlightSpan]]:
+def build_resume(
+    profile: CandidateProfile,
+    keywords: Sequence[str],
+    labels: Mapping[str, str],
+) -> tuple[str, List[HighlightSpan]]:
     ranked_skills = rank_skills(profile.skills, keywords)
     lines: List[str] = [profile.full_name, ""]
     if profile.summary:
         lines.extend([profile.summary, ""])
 
-    lines.append("Compétences clés : " + ", ".join(ranked_skills[:10]))
+    lines.append(f"{labels['skills']} : " + ", ".join(ranked_skills[:10]))
     lines.append("")
-    lines.append("Expériences professionnelles :")
+    lines.append(f"{labels['experiences']} :")
 
     for experience in profile.experiences[:6]:
-        header = f"- {experience.role} chez {experience.company} ({experience.start_date} - {experience.end_date or 'Présent'})"
+        header = f"- {experience.role} {labels['at']} {experience.company} ({experience.start_date} - {experience.end_date or labels['present']})"
         lines.append(header)
         for achievement in experience.achievements[:4]:
             lines.append(f"  • {achievement}")
         if experience.technologies:
-            lines.append(f"    Technologies : {', '.join(experience.technologies[:6])}")
+            lines.append(f"    {labels['technologies']} : {', '.join(experience.technologies[:6])}")
         lines.append("")
 
     if profile.education:
-        lines.append("Formation :")
+        lines.append(f"{labels['education']} :")
         for edu in profile.education[:4]:
-            lines.append(f"- {edu.degree} - {edu.school} ({edu.start_date} - {edu.end_date or 'Présent'})")
+            lines.append(f"- {edu.degree} - {edu.school} ({edu.start_date} - {edu.end_date or labels['present']})")
 
     if profile.languages:
         lines.append("")
-        lines.append("Langues : " + ", ".join(f"{lang.label} ({lang.level})" for lang in profile.languages))
+        lines.append(f"{labels['languages']} : " + ", ".join(f"{lang.label} ({lang.level})" for lang in profile.languages))
 
     text = "\n".join(lines).strip()
     highlights = build_highlights(text, keywords)
     return text, highlights
 
 
-def build_cover_letter(profile: CandidateProfile, offer: JobOffer, keywords: Sequence[str]) -> str:
-    intro = f"Madame, Monsieur,\n\nJe vous propose ma candidature au poste {offer.title}."
+def build_cover_letter(
+    profile: CandidateProfile,
+    offer: JobOffer,
+    keywords: Sequence[str],
+    template: Mapping[str, str],
+) -> CoverLetter:
     strengths = ", ".join(keywords[:5]) if keywords else ", ".join(profile.skills[:5])
-    body = (
-        "Fort d'expériences significatives, je maîtrise "
-        f"{strengths}. J'ai conduit des projets en autonomie tout en collaborant étroitement avec les équipes métiers."
-    )
+    paragraphs = [
+        template["intro"].format(title=offer.title),
+        template["body"].format(strengths=strengths),
+    ]
     achievements = []
     for experience in profile.experiences[:2]:
         if experience.achievements:
             achievements.append(experience.achievements[0])
     if achievements:
-        body += "\n\nAmong mes réalisations clés : " + "; ".join(achievements)
-
-    closing = (
-        "\n\nJe suis disponible rapidement pour un entretien afin d'échanger sur votre besoin.\n\n"
-        "Cordialement,\n"
-        f"{profile.full_name}"
+        paragraphs.append(template["achievements"] + "; ".join(achievements))
+    paragraphs.append(template["closing"])
+
+    return CoverLetter(
+        salutation=template["salutation"],
+        paragraphs=paragraphs,
+        closing=template["signoff"],
+        signature=profile.full_name,
+        sender=[line for line in (profile.full_name, profile.email, profile.phone) if line],
+        recipient=[line for line in (offer.company, offer.location) if line],
     )
 
-    return "\n".join([intro, body, closing]).strip()
-
 
 def build_highlights(text: str, keywords: Sequence[str]) -> List[HighlightSpan]:
     spans: List[HighlightSpan] = []
@@ -104,10 +114,17 @@ def build_highlights(text: str, keywords: Sequence[str]) -> List[HighlightSpan]:
     return spans
 
 
-def adapt_documents(profile: CandidateProfile, offer: JobOffer) -> AdaptationResult:
+def adapt_documents(
+    profile: CandidateProfile,
+    offer: JobOffer,
+    labels: Mapping[str, str],
+    template: Mapping[str, str],
+) -> AdaptationResult:
+    """`labels` (`sections.*`) et `template` (`coverLetter.*`) viennent des catalogues Rust,
+    résolus dans la langue cible."""
     keywords = offer.keywords or []
-    resume_text, highlights = build_resume(profile, keywords)
-    cover_letter = build_cover_letter(profile, offer, keywords)
+    resume_text, highlights = build_resume(profile, keywords, labels)
+    cover_letter = build_cover_letter(profile, offer, keywords, template)
 
     return AdaptationResult(
         adapted_resume=resume_text,
@@ -46,6 +46,19 @@ def import_cv(input: Path) -> None:  # type: ignore[override]
     output(result.to_dict())
 
 
+@cli.command(name="extract_text")
+def extract_text_cmd(input: Path) -> None:  # type: ignore[override]
+    payload = read_payload(input)
+    file_path = Path(payload["file_path"]).expanduser()
+    try:
+        raw_text, warnings = extract_text(file_path)
+    except ExtractionError as error:
+        typer.secho(f"Erreur d'extraction : {error}", fg=typer.colors.RED, err=True)
+        raise typer.Exit(code=1) from error
+
+    output({"rawText": raw_text, "warnings": warnings})
+
+
 @cli.command(name="analyze_offer")
 def analyze_offer(input: Path) -> None:  # type: ignore[override]
     payload = read_payload(input)
@@ -59,7 +72,12 @@ def adapt_documents_cmd(input: Path) -> None:  # type: ignore[override]
     payload = read_payload(input)
     profile = CandidateProfile.from_dict(payload["profile"])
     offer = JobOffer.from_dict(payload["offer"])
-    result = adapt_documents(profile, offer)
+    result = adapt_documents(
+        profile,
+        offer,
+        labels=payload["labels"],
+        template=payload["coverLetter"],
+    )
     output(result.to_dict())
 
 
@@ -69,7 +87,13 @@ def export_documents_cmd(input: Path) -> None:  # type: ignore[override]
     profile = CandidateProfile.from_dict(payload["profile"])
     adaptation = AdaptationResult.from_dict(payload["adaptation"])
     fmt = payload.get("format", "pdf")
-    result = export_documents(profile, adaptation, fmt)
+    result = export_documents(
+        profile,
+        adaptation,
+        fmt,
+        labels=payload["labels"],
+        output_dir=payload.get("output_dir"),
+    )
     output(result.to_dict())
 
 
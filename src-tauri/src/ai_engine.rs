@@ -1,50 +1,537 @@
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tauri::{command, AppHandle, State};
+use tauri::{command, AppHandle, Manager, State};
+use tracing::instrument;
 
 use crate::{
-    models::{AdaptationResult, JobOffer},
-    python_bridge::call_python,
+    diff::{fill_offsets, keep_user_edits, merge_highlights, rebase_highlights, word_highlights},
+    llm_engine::{LlmEngine, ADAPTATION_PROGRESS_EVENT},
+    locale::{cover_letter_templates, detect_language, section_labels, Language},
+    messages::AppError,
+    models::{
+        AdaptationResult, CandidateProfile, CoverLetter, HighlightType, JobOffer, ResumeDocument,
+    },
+    python_bridge::PythonBridge,
+    rule_engine::RuleBasedEngine,
+    settings::{current_settings, Settings},
     state::{
-        get_adaptation, store_adaptation, store_offer, try_get_offer, try_get_profile, SharedState,
+        adaptation_key, get_adaptation, store_adaptation, store_offer, try_get_offer, SharedState,
     },
+    variants::resolve_profile,
+    vault::VaultState,
 };
 
-#[command]
-pub async fn analyze_offer(
+/// Profil (déjà localisé) et offre à rapprocher, dans la langue cible.
+#[derive(Debug, Clone, Copy)]
+pub struct EngineInput<'a> {
+    pub profile: &'a CandidateProfile,
+    pub offer: &'a JobOffer,
+    pub language: Language,
+}
+
+/// Production du CV et de la lettre adaptés ; la mise en cache reste à la charge d'`adapt`.
+pub trait AdaptationEngine {
+    fn adapt(
+        &self,
+        input: EngineInput<'_>,
+    ) -> impl Future<Output = Result<AdaptationResult, AppError>> + Send;
+}
+
+/// Pipeline Python (sentence-transformers) : verbe `adapt_documents`.
+impl AdaptationEngine for PythonBridge {
+    async fn adapt(&self, input: EngineInput<'_>) -> Result<AdaptationResult, AppError> {
+        let payload = json!({
+            "profile": input.profile,
+            "offer": input.offer,
+            "language": input.language,
+            "labels": section_labels(input.language),
+            "coverLetter": cover_letter_templates(input.language)
+        });
+        self.call("adapt_documents", payload)
+            .await
+            .map_err(AppError::python)
+    }
+}
+
+/// Moteur choisi dans les préférences (`adaptationEngine`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineKind {
+    #[default]
+    Python,
+    /// Règles en Rust : fonctionne sans environnement Python.
+    Rules,
+    /// LLM local via un serveur compatible OpenAI (préférences `llm`).
+    Llm,
+}
+
+/// Moteur résolu à partir des préférences.
+pub enum ConfiguredEngine {
+    Python(PythonBridge),
+    Rules(RuleBasedEngine),
+    Llm(LlmEngine),
+}
+
+impl ConfiguredEngine {
+    /// `bridge` n'est appelé que pour le moteur Python.
+    pub fn new(
+        settings: &Settings,
+        bridge: impl FnOnce() -> Result<PythonBridge, AppError>,
+    ) -> Result<Self, AppError> {
+        Ok(match settings.adaptation_engine {
+            EngineKind::Python => Self::Python(bridge()?),
+            EngineKind::Rules => Self::Rules(RuleBasedEngine),
+            EngineKind::Llm => Self::Llm(LlmEngine::new(settings.llm.clone())),
+        })
+    }
+
+    /// Comme `new`, la génération du LLM étant relayée par `adaptation-progress`.
+    pub fn from_app(app: &AppHandle, settings: &Settings) -> Result<Self, AppError> {
+        let engine = Self::new(settings, || {
+            PythonBridge::from_app(app).map_err(AppError::python)
+        })?;
+        Ok(match engine {
+            Self::Llm(engine) => {
+                let app = app.clone();
+                Self::Llm(engine.with_progress(move |delta| {
+                    let _ = app.emit_all(ADAPTATION_PROGRESS_EVENT, json!({ "delta": delta }));
+                }))
+            }
+            engine => engine,
+        })
+    }
+}
+
+impl AdaptationEngine for ConfiguredEngine {
+    async fn adapt(&self, input: EngineInput<'_>) -> Result<AdaptationResult, AppError> {
+        match self {
+            Self::Python(bridge) => bridge.adapt(input).await,
+            Self::Rules(engine) => engine.adapt(input).await,
+            Self::Llm(engine) => engine.adapt(input).await,
+        }
+    }
+}
+
+/// Paramètres d'une adaptation, communs à la commande Tauri, au CLI et à l'API HTTP.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptRequest {
+    pub profile_id: String,
+    pub offer_id: String,
+    pub variant_id: Option<String>,
+    pub language: Option<Language>,
+    /// Relance le moteur même si une adaptation est déjà en cache.
+    #[serde(default)]
+    pub regenerate: bool,
+    /// Autorise la régénération d'une adaptation retouchée (les retouches sont perdues).
+    #[serde(default)]
+    pub discard_edits: bool,
+}
+
+/// Retouche du CV ou de la lettre d'une adaptation existante.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptationEdit {
+    pub profile_id: String,
+    pub variant_id: Option<String>,
+    pub adapted_resume: Option<String>,
+    pub adapted_cover_letter: Option<CoverLetter>,
+}
+
+/// Enrichit une offre (mots-clés, langue) et l'enregistre.
+pub async fn analyze(
+    bridge: &PythonBridge,
+    state: &SharedState,
     offer: JobOffer,
-    state: State<'_, SharedState>,
-    app: AppHandle,
-) -> Result<JobOffer, String> {
+) -> Result<JobOffer, AppError> {
     let payload = json!({ "offer": offer });
-    let enriched: JobOffer = call_python(&app, "analyze_offer", payload)
+    let mut enriched: JobOffer = bridge
+        .call("analyze_offer", payload)
         .await
-        .map_err(|error| error.to_string())?;
+        .map_err(AppError::python)?;
+    enriched.language = offer
+        .language
+        .or_else(|| detect_language(&enriched.description));
+    // Champs inconnus du script Python : repris de l'offre d'origine.
+    enriched.date_posted = offer.date_posted;
+    enriched.valid_through = offer.valid_through;
+    enriched.salary = offer.salary;
+    enriched.contact = offer.contact;
 
-    store_offer(&state, enriched.clone());
+    store_offer(state, enriched.clone());
     Ok(enriched)
 }
 
+/// Adapte CV et lettre à une offre ; l'adaptation en cache est reprise si elle vise la même
+/// offre dans la même langue. Sauf `discard_edits`, les passages saisis par l'utilisateur sont
+/// reportés sur le nouveau résultat.
+pub async fn adapt(
+    engine: &impl AdaptationEngine,
+    state: &SharedState,
+    settings: &Settings,
+    request: AdaptRequest,
+) -> Result<AdaptationResult, AppError> {
+    let AdaptRequest {
+        profile_id,
+        offer_id,
+        variant_id,
+        language,
+        regenerate,
+        discard_edits,
+    } = request;
+    let offer = try_get_offer(state, &offer_id)
+        .ok_or_else(|| AppError::new("errors.offerNotFound").arg("id", &offer_id))?;
+    let language = language
+        .or(offer.language)
+        .or_else(|| detect_language(&offer.description))
+        .unwrap_or(settings.default_target_language);
+
+    let key = adaptation_key(&profile_id, variant_id.as_deref());
+    let mut previous = None;
+    if let Some(existing) = get_adaptation(state, &key) {
+        // Les adaptations antérieures au multilingue ont été produites en français ; celles
+        // enregistrées sans offre sont reprises pour n'importe laquelle, comme auparavant.
+        let same_offer = existing.offer_id.as_deref().is_none_or(|id| id == offer_id);
+        if !regenerate && same_offer && existing.language.unwrap_or_default() == language {
+            return Ok(existing);
+        }
+        if existing.has_user_edits() && !discard_edits {
+            // Une retouche par simple suppression n'a aucun passage à reporter.
+            let passages = existing
+                .highlights
+                .iter()
+                .chain(&existing.cover_letter_highlights)
+                .any(|span| matches!(span.highlight_type, HighlightType::UserEdit));
+            if !passages {
+                return Err(AppError::new("errors.adaptationEdited"));
+            }
+            previous = Some(existing);
+        }
+    }
+
+    let profile = resolve_profile(state, &profile_id, variant_id.as_deref())?.localized(language);
+
+    let input = EngineInput {
+        profile: &profile,
+        offer: &offer,
+        language,
+    };
+    let mut result = engine.adapt(input).await?;
+    result.language = Some(language);
+    result.offer_id = Some(offer_id);
+    // Avec un arbre, le texte et ses mises en valeur en dérivent ; un texte libre (Python,
+    // LLM) est gardé tel quel, avec les surlignages du moteur. Les ajouts et suppressions mot à
+    // mot ne complètent que les passages où le moteur n'en signale pas.
+    if let Some(document) = &result.resume_document {
+        (result.adapted_resume, result.highlights) = document.render();
+    }
+    let words = word_highlights(&profile, &result.adapted_resume);
+    result.highlights = merge_highlights(std::mem::take(&mut result.highlights), words);
+    fill_offsets(&result.adapted_resume, &mut result.highlights);
+    if let Some(previous) = &previous {
+        carry_user_edits(previous, &mut result);
+    }
+
+    store_adaptation(state, &key, result.clone());
+    Ok(result)
+}
+
+/// Reporte sur le résultat régénéré les passages saisis dans `previous` : là où le moteur les a
+/// réécrits, le texte de l'utilisateur l'emporte et reste marqué `userEdit`.
+fn carry_user_edits(previous: &AdaptationResult, result: &mut AdaptationResult) {
+    let resume = keep_user_edits(
+        &previous.adapted_resume,
+        &previous.highlights,
+        &result.adapted_resume,
+    );
+    if resume != result.adapted_resume {
+        result.highlights = rebase_highlights(&result.highlights, &result.adapted_resume, &resume);
+        result.resume_document = Some(ResumeDocument::from_text(&resume, &result.highlights));
+        result.adapted_resume = resume;
+        result.user_edited = true;
+    }
+
+    let generated = result.adapted_cover_letter.to_text();
+    let letter = keep_user_edits(
+        &previous.adapted_cover_letter.to_text(),
+        &previous.cover_letter_highlights,
+        &generated,
+    );
+    if letter != generated {
+        result.cover_letter_highlights =
+            rebase_highlights(&result.cover_letter_highlights, &generated, &letter);
+        result.adapted_cover_letter = CoverLetter::from_text(&letter);
+        result.user_edited = true;
+    }
+}
+
+/// Enregistre le texte retouché : les surlignages sont reportés, les passages saisis marqués
+/// `userEdit` et l'adaptation, même retouchée par une simple suppression, protégée d'une
+/// régénération.
+pub fn apply_adaptation_edit(
+    state: &SharedState,
+    edit: AdaptationEdit,
+) -> Result<AdaptationResult, AppError> {
+    let key = adaptation_key(&edit.profile_id, edit.variant_id.as_deref());
+    let mut adaptation = get_adaptation(state, &key)
+        .ok_or_else(|| AppError::new("errors.adaptationNotFound").arg("id", &key))?;
+
+    if let Some(resume) = edit.adapted_resume {
+        adaptation.user_edited |= resume != adaptation.adapted_resume;
+        adaptation.highlights =
+            rebase_highlights(&adaptation.highlights, &adaptation.adapted_resume, &resume);
+        // Le texte saisi fait foi ; l'arbre des exporteurs en est relu.
+        adaptation.resume_document =
+            Some(ResumeDocument::from_text(&resume, &adaptation.highlights));
+        adaptation.adapted_resume = resume;
+    }
+    if let Some(letter) = edit.adapted_cover_letter {
+        adaptation.user_edited |= letter != adaptation.adapted_cover_letter;
+        adaptation.cover_letter_highlights = rebase_highlights(
+            &adaptation.cover_letter_highlights,
+            &adaptation.adapted_cover_letter.to_text(),
+            &letter.to_text(),
+        );
+        adaptation.adapted_cover_letter = letter;
+    }
+
+    store_adaptation(state, &key, adaptation.clone());
+    Ok(adaptation)
+}
+
+#[command]
+#[instrument(skip_all, fields(offer_id = %offer.id))]
+pub async fn analyze_offer(
+    offer: JobOffer,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+    app: AppHandle,
+) -> Result<JobOffer, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let bridge = PythonBridge::from_app(&app).map_err(AppError::python)?;
+    analyze(&bridge, &state, offer).await
+}
+
 #[command]
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(state, vault, app))]
 pub async fn adapt_documents(
     profile_id: String,
     offer_id: String,
+    variant_id: Option<String>,
+    language: Option<Language>,
+    regenerate: Option<bool>,
+    discard_edits: Option<bool>,
     state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
     app: AppHandle,
-) -> Result<AdaptationResult, String> {
-    if let Some(existing) = get_adaptation(&state, &profile_id) {
-        return Ok(existing);
+) -> Result<AdaptationResult, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let settings = current_settings(&app);
+    let engine = ConfiguredEngine::from_app(&app, &settings)?;
+    let request = AdaptRequest {
+        profile_id,
+        offer_id,
+        variant_id,
+        language,
+        regenerate: regenerate.unwrap_or_default(),
+        discard_edits: discard_edits.unwrap_or_default(),
+    };
+    adapt(&engine, &state, &settings, request).await
+}
+
+#[command]
+#[instrument(skip(state, vault, adapted_resume, adapted_cover_letter))]
+pub fn update_adaptation(
+    profile_id: String,
+    variant_id: Option<String>,
+    adapted_resume: Option<String>,
+    adapted_cover_letter: Option<CoverLetter>,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+) -> Result<AdaptationResult, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let edit = AdaptationEdit {
+        profile_id,
+        variant_id,
+        adapted_resume,
+        adapted_cover_letter,
+    };
+    apply_adaptation_edit(&state, edit)
+}
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::Mutex;
+    use serde_json::json;
+
+    use super::{
+        adapt, apply_adaptation_edit, AdaptRequest, AdaptationEdit, AdaptationEngine, EngineInput,
+    };
+    use crate::{
+        messages::AppError,
+        models::{AdaptationResult, HighlightType},
+        rule_engine::RuleBasedEngine,
+        settings::Settings,
+        state::{store_offer, store_profile, AppMemory, SharedState},
+    };
+
+    fn request(offer_id: &str, regenerate: bool, discard_edits: bool) -> AdaptRequest {
+        AdaptRequest {
+            profile_id: "p1".into(),
+            offer_id: offer_id.into(),
+            variant_id: None,
+            language: None,
+            regenerate,
+            discard_edits,
+        }
     }
 
-    let profile = try_get_profile(&state, &profile_id)
-        .ok_or_else(|| format!("Profil {profile_id} introuvable"))?;
-    let offer =
-        try_get_offer(&state, &offer_id).ok_or_else(|| format!("Offre {offer_id} introuvable"))?;
+    #[tokio::test]
+    async fn edited_adaptations_are_not_regenerated_silently() {
+        let state = SharedState(Mutex::new(AppMemory::default()));
+        store_profile(
+            &state,
+            serde_json::from_value(json!({
+                "id": "p1", "fullName": "Élise Martin", "email": null, "phone": null,
+                "summary": "Développeuse Rust.", "experiences": [], "skills": ["Rust", "Go"],
+                "education": [], "projects": [], "languages": []
+            }))
+            .unwrap(),
+        );
+        for id in ["o1", "o2"] {
+            store_offer(
+                &state,
+                serde_json::from_value(json!({
+                    "id": id, "title": "Ingénieure Rust", "company": null,
+                    "description": "Poste Rust.", "location": null, "keywords": ["rust"],
+                    "language": "fr"
+                }))
+                .unwrap(),
+            );
+        }
+        let settings = Settings::default();
+        let engine = RuleBasedEngine;
 
-    let payload = json!({ "profile": profile, "offer": offer });
-    let result: AdaptationResult = call_python(&app, "adapt_documents", payload)
-        .await
-        .map_err(|error| error.to_string())?;
+        let first = adapt(&engine, &state, &settings, request("o1", false, false))
+            .await
+            .unwrap();
+        assert_eq!(first.offer_id.as_deref(), Some("o1"));
+        // Une autre offre ne reprend pas l'adaptation en cache.
+        let other = adapt(&engine, &state, &settings, request("o2", false, false))
+            .await
+            .unwrap();
+        assert_eq!(other.offer_id.as_deref(), Some("o2"));
 
-    store_adaptation(&state, &profile_id, result.clone());
-    Ok(result)
+        // Retouche par simple suppression : aucun passage `userEdit`, mais l'adaptation est
+        // protégée.
+        let kept: String = other.adapted_resume.chars().take(5).collect();
+        let edited = apply_adaptation_edit(
+            &state,
+            AdaptationEdit {
+                profile_id: "p1".into(),
+                variant_id: None,
+                adapted_resume: Some(kept),
+                adapted_cover_letter: None,
+            },
+        )
+        .unwrap();
+        assert!(edited.user_edited && edited.has_user_edits());
+
+        for request in [request("o2", true, false), request("o1", false, false)] {
+            let error = adapt(&engine, &state, &settings, request)
+                .await
+                .unwrap_err();
+            assert_eq!(error.key, "errors.adaptationEdited");
+        }
+        let regenerated = adapt(&engine, &state, &settings, request("o1", true, true))
+            .await
+            .unwrap();
+        assert!(!regenerated.user_edited);
+
+        // Les passages saisis survivent à la régénération.
+        let written = format!("{}\nDisponible immédiatement.", regenerated.adapted_resume);
+        apply_adaptation_edit(
+            &state,
+            AdaptationEdit {
+                profile_id: "p1".into(),
+                variant_id: None,
+                adapted_resume: Some(written.clone()),
+                adapted_cover_letter: None,
+            },
+        )
+        .unwrap();
+        let carried = adapt(&engine, &state, &settings, request("o1", true, false))
+            .await
+            .unwrap();
+        assert_eq!(carried.adapted_resume, written);
+        assert!(carried.user_edited);
+        assert!(carried.highlights.iter().any(|span| {
+            matches!(span.highlight_type, HighlightType::UserEdit)
+                && written[span.utf8.unwrap().start..span.utf8.unwrap().end]
+                    == *"Disponible immédiatement."
+        }));
+    }
+
+    /// Moteur à texte libre, comme le LLM : pas d'arbre, ses propres surlignages.
+    struct FreeTextEngine;
+
+    impl AdaptationEngine for FreeTextEngine {
+        async fn adapt(&self, _input: EngineInput<'_>) -> Result<AdaptationResult, AppError> {
+            Ok(serde_json::from_value(json!({
+                "adaptedResume": "PROFIL\n    Développeuse Rust senior.",
+                "adaptedCoverLetter": {},
+                "highlights": [
+                    { "id": "llm-1", "type": "addition", "start": 29, "end": 35 }
+                ]
+            }))
+            .unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn free_text_results_keep_their_text_and_highlights() {
+        let state = SharedState(Mutex::new(AppMemory::default()));
+        store_profile(
+            &state,
+            serde_json::from_value(json!({
+                "id": "p1", "fullName": "Élise Martin", "email": null, "phone": null,
+                "summary": "Développeuse Rust.", "experiences": [], "skills": [],
+                "education": [], "projects": [], "languages": []
+            }))
+            .unwrap(),
+        );
+        store_offer(
+            &state,
+            serde_json::from_value(json!({
+                "id": "o1", "title": "Rust", "company": null, "description": "Rust.",
+                "location": null, "keywords": ["rust"], "language": "fr"
+            }))
+            .unwrap(),
+        );
+
+        let result = adapt(
+            &FreeTextEngine,
+            &state,
+            &Settings::default(),
+            request("o1", false, false),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            result.adapted_resume,
+            "PROFIL\n    Développeuse Rust senior."
+        );
+        assert!(result.resume_document.is_none());
+        let additions: Vec<&str> = result
+            .highlights
+            .iter()
+            .filter(|span| matches!(span.highlight_type, HighlightType::Addition))
+            .map(|span| span.id.as_str())
+            .collect();
+        assert_eq!(additions, ["llm-1"]);
+        assert!(result.highlights[0].utf8.is_some());
+    }
 }
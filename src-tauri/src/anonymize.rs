@@ -0,0 +1,562 @@
+//! Export « à l'aveugle » : variante neutre d'un profil et de son adaptation.
+//!
+//! Les valeurs identifiantes du profil (nom, coordonnées, écoles, entreprises en option) sont
+//! remplacées par des libellés neutres, y compris dans le texte libre (résumé, réalisations,
+//! CV adapté, lettre). Les e-mails, téléphones et mentions d'âge sont aussi détectés par motif.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    diff::fill_offsets,
+    locale::{lookup, Language},
+    models::{AdaptationResult, CandidateProfile, HighlightSpan, Inline, RichText},
+};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnonymizeOptions {
+    /// Remplace aussi les noms d'entreprises (« Entreprise A », « Entreprise B »…).
+    pub companies: bool,
+}
+
+pub(crate) static EMAIL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[\w.+-]+@[\w-]+(\.[\w-]+)+").expect("regex e-mail"));
+pub(crate) static PHONE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[+(]?\d[\d .\-()]{7,}\d").expect("regex téléphone"));
+static AGE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b\d{2}\s*(ans|years?\s+old|jahre(\s+alt)?)\b|\b(âge|age|alter)\s*:?\s*\d{2}\b|\b(née?|born|geboren)\s+(le\s+|on\s+|am\s+)?[\w./-]+(\s+\w+\s+\d{4})?",
+    )
+    .expect("regex âge")
+});
+
+fn placeholder(language: Language, key: &str) -> String {
+    lookup(language, &format!("anonymize.{key}"))
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// Remplacement d'une plage de caractères (indices en points de code).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Edit {
+    start: usize,
+    end: usize,
+    replacement_len: usize,
+}
+
+/// Table des valeurs à masquer, construite à partir du profil d'origine.
+pub struct Redactor {
+    needles: Vec<(Regex, String)>,
+    patterns: Vec<(&'static Regex, String)>,
+}
+
+impl Redactor {
+    pub fn new(profile: &CandidateProfile, options: AnonymizeOptions, language: Language) -> Self {
+        let mut needles: Vec<(String, String)> = Vec::new();
+        let name = placeholder(language, "name");
+
+        needles.push((profile.full_name.clone(), name.clone()));
+        for part in profile.full_name.split_whitespace() {
+            if part.chars().count() > 1 {
+                needles.push((part.to_string(), name.clone()));
+            }
+        }
+        if let Some(email) = &profile.email {
+            needles.push((email.clone(), placeholder(language, "email")));
+        }
+        if let Some(phone) = &profile.phone {
+            needles.push((phone.clone(), placeholder(language, "phone")));
+        }
+        for (index, education) in profile.education.iter().enumerate() {
+            let label = format!("{} {}", placeholder(language, "school"), index + 1);
+            needles.push((education.school.clone(), label));
+        }
+        if options.companies {
+            let mut companies: Vec<&str> = Vec::new();
+            for experience in &profile.experiences {
+                if !companies.contains(&experience.company.as_str()) {
+                    companies.push(&experience.company);
+                }
+            }
+            for (index, company) in companies.into_iter().enumerate() {
+                let label = format!("{} {}", placeholder(language, "company"), letter(index));
+                needles.push((company.to_string(), label));
+            }
+        }
+
+        // Les valeurs les plus longues d'abord : « Jane Doe » avant « Jane ».
+        needles.retain(|(needle, _)| !needle.trim().is_empty());
+        needles.sort_by_key(|(needle, _)| std::cmp::Reverse(needle.chars().count()));
+        let needles = needles
+            .into_iter()
+            .filter_map(|(needle, replacement)| {
+                let needle = needle.trim();
+                // `\b` seulement côté caractère de mot : « +33 6… » n'a pas de frontière avant `+`.
+                let boundary = |c: Option<char>| {
+                    if c.is_some_and(char::is_alphanumeric) {
+                        r"\b"
+                    } else {
+                        ""
+                    }
+                };
+                let pattern = format!(
+                    "(?i){}{}{}",
+                    boundary(needle.chars().next()),
+                    regex::escape(needle),
+                    boundary(needle.chars().last())
+                );
+                Regex::new(&pattern).ok().map(|regex| (regex, replacement))
+            })
+            .collect();
+
+        Self {
+            needles,
+            patterns: vec![
+                (&EMAIL, placeholder(language, "email")),
+                (&PHONE, placeholder(language, "phone")),
+                (&AGE, placeholder(language, "age")),
+            ],
+        }
+    }
+
+    pub fn scrub(&self, text: &str) -> String {
+        self.scrub_with_edits(text).0
+    }
+
+    fn scrub_with_edits(&self, text: &str) -> (String, Vec<Edit>) {
+        // (début, fin) en octets + remplacement ; la première correspondance l'emporte.
+        let mut matches: Vec<(usize, usize, &str)> = Vec::new();
+        let rules = self
+            .needles
+            .iter()
+            .map(|(regex, replacement)| (regex, replacement))
+            .chain(
+                self.patterns
+                    .iter()
+                    .map(|(regex, replacement)| (*regex, replacement)),
+            );
+        for (regex, replacement) in rules {
+            for found in regex.find_iter(text) {
+                if std::ptr::eq(regex, &*PHONE) && !looks_like_phone(found.as_str()) {
+                    continue;
+                }
+                let overlaps = matches
+                    .iter()
+                    .any(|(start, end, _)| found.start() < *end && *start < found.end());
+                if !overlaps {
+                    matches.push((found.start(), found.end(), replacement));
+                }
+            }
+        }
+        matches.sort_by_key(|(start, _, _)| *start);
+
+        let mut output = String::with_capacity(text.len());
+        let mut edits = Vec::with_capacity(matches.len());
+        let mut cursor = 0;
+        let mut char_cursor = 0;
+        for (start, end, replacement) in matches {
+            let before = &text[cursor..start];
+            output.push_str(before);
+            char_cursor += before.chars().count();
+            let removed = text[start..end].chars().count();
+            edits.push(Edit {
+                start: char_cursor,
+                end: char_cursor + removed,
+                replacement_len: replacement.chars().count(),
+            });
+            output.push_str(replacement);
+            char_cursor += removed;
+            cursor = end;
+        }
+        output.push_str(&text[cursor..]);
+        (output, edits)
+    }
+
+    pub fn profile(&self, profile: &CandidateProfile) -> CandidateProfile {
+        let mut anonymized = profile.clone();
+        anonymized.full_name = self.scrub(&profile.full_name);
+        anonymized.email = None;
+        anonymized.phone = None;
+        anonymized.summary = profile.summary.as_deref().map(|text| self.scrub(text));
+        anonymized.summary_translations.clear();
+
+        for experience in &mut anonymized.experiences {
+            experience.company = self.scrub(&experience.company);
+            experience.role = self.scrub(&experience.role);
+            experience.achievements = experience
+                .achievements
+                .iter()
+                .map(|text| self.scrub(text))
+                .collect();
+            experience.role_translations.clear();
+            experience.achievement_translations.clear();
+        }
+        for education in &mut anonymized.education {
+            education.school = self.scrub(&education.school);
+            education.notes = education.notes.as_deref().map(|text| self.scrub(text));
+        }
+        for project in &mut anonymized.projects {
+            project.description = self.scrub(&project.description);
+            project.impact = project.impact.as_deref().map(|text| self.scrub(text));
+            project.url = None;
+        }
+        anonymized
+    }
+
+    pub fn adaptation(&self, adaptation: &AdaptationResult) -> AdaptationResult {
+        let (adapted_resume, highlights) =
+            self.scrub_highlighted(&adaptation.adapted_resume, &adaptation.highlights);
+        // Chaque champ de la lettre est masqué ; les surlignages suivent sa forme texte.
+        let (_, cover_letter_highlights) = self.scrub_highlighted(
+            &adaptation.adapted_cover_letter.to_text(),
+            &adaptation.cover_letter_highlights,
+        );
+        let adapted_cover_letter = adaptation
+            .adapted_cover_letter
+            .map_text(|text| self.scrub(text));
+
+        let resume_document = adaptation
+            .resume_document
+            .as_ref()
+            .map(|document| document.map_text(|rich| self.scrub_rich(rich)));
+
+        AdaptationResult {
+            adapted_resume,
+            resume_document,
+            adapted_cover_letter,
+            highlights,
+            cover_letter_highlights,
+            ..adaptation.clone()
+        }
+    }
+
+    /// Masque un texte riche d'un seul tenant puis le redécoupe sur les mises en valeur
+    /// décalées : une valeur coupée par une mise en valeur (« École » + « Centrale ») est
+    /// masquée comme les autres.
+    fn scrub_rich(&self, rich: &RichText) -> RichText {
+        let mut text = String::new();
+        let mut emphasis = Vec::new();
+        for inline in rich {
+            let start = text.chars().count();
+            text.push_str(&inline.text);
+            let end = text.chars().count();
+            if inline.emphasis && end > start {
+                emphasis.push((start, end));
+            }
+        }
+        let (scrubbed, edits) = self.scrub_with_edits(&text);
+
+        // Deux segments d'une même valeur masquée se rejoignent sur son libellé.
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for (start, end) in emphasis {
+            let (start, end) = (
+                remap_offset(start, &edits, false),
+                remap_offset(end, &edits, true),
+            );
+            match ranges.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ if end > start => ranges.push((start, end)),
+                _ => {}
+            }
+        }
+
+        let chars: Vec<char> = scrubbed.chars().collect();
+        let mut result = Vec::new();
+        let mut push = |start: usize, end: usize, emphasis: bool| {
+            if end > start {
+                result.push(Inline {
+                    text: chars[start..end].iter().collect(),
+                    emphasis,
+                });
+            }
+        };
+        let mut cursor = 0;
+        for (start, end) in ranges {
+            push(cursor, start, false);
+            push(start, end, true);
+            cursor = end;
+        }
+        push(cursor, chars.len(), false);
+        result
+    }
+
+    fn scrub_highlighted(
+        &self,
+        text: &str,
+        highlights: &[HighlightSpan],
+    ) -> (String, Vec<HighlightSpan>) {
+        let (scrubbed, edits) = self.scrub_with_edits(text);
+        let mut highlights: Vec<HighlightSpan> = highlights
+            .iter()
+            .filter_map(|span| remap_span(span, &edits))
+            .map(|span| HighlightSpan {
+                // Texte d'origine supprimé : il peut contenir le nom, l'e-mail ou l'école.
+                removed_text: span.removed_text.as_deref().map(|text| self.scrub(text)),
+                ..span
+            })
+            .collect();
+        fill_offsets(&scrubbed, &mut highlights);
+        (scrubbed, highlights)
+    }
+}
+
+/// Écarte les plages de dates (« 2019 - 2021 ») que le motif téléphone capture aussi.
+pub(crate) fn looks_like_phone(candidate: &str) -> bool {
+    let digits = candidate.chars().filter(char::is_ascii_digit).count();
+    digits >= 9 && candidate.starts_with(['+', '0', '('])
+}
+
+fn letter(index: usize) -> String {
+    let mut label = String::new();
+    let mut n = index;
+    loop {
+        label.insert(0, (b'A' + (n % 26) as u8) as char);
+        if n < 26 {
+            break;
+        }
+        n = n / 26 - 1;
+    }
+    label
+}
+
+/// Décale une position (en caractères) du texte d'origine vers le texte masqué.
+fn remap_offset(offset: usize, edits: &[Edit], at_end: bool) -> usize {
+    let mut shift: isize = 0;
+    for edit in edits {
+        if offset >= edit.end {
+            shift += edit.replacement_len as isize - (edit.end - edit.start) as isize;
+        } else if offset > edit.start {
+            // À l'intérieur d'une plage remplacée : on englobe le libellé entier.
+            let base = edit.start as isize + shift;
+            let inside = if at_end { edit.replacement_len } else { 0 };
+            return (base + inside as isize) as usize;
+        } else {
+            break;
+        }
+    }
+    (offset as isize + shift) as usize
+}
+
+fn remap_span(span: &HighlightSpan, edits: &[Edit]) -> Option<HighlightSpan> {
+    let start = remap_offset(span.start, edits, false);
+    // Une suppression reste de largeur nulle.
+    if span.start == span.end {
+        return Some(HighlightSpan {
+            start,
+            end: start,
+            ..span.clone()
+        });
+    }
+    let end = remap_offset(span.end, edits, true);
+    (end > start).then(|| HighlightSpan {
+        start,
+        end,
+        ..span.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{letter, AnonymizeOptions, Redactor};
+    use crate::{
+        locale::Language,
+        models::{
+            AdaptationResult, CandidateProfile, Education, Experience, HighlightSpan,
+            HighlightType, Inline, ResumeBlock, ResumeDocument, ResumeSection, RichText,
+        },
+    };
+
+    fn profile() -> CandidateProfile {
+        CandidateProfile {
+            id: "p1".into(),
+            full_name: "Jeanne Dupont".into(),
+            email: Some("jeanne.dupont@example.com".into()),
+            phone: Some("+33 6 12 34 56 78".into()),
+            summary: Some("Jeanne, 34 ans, ingénieure chez Acme.".into()),
+            experiences: vec![Experience {
+                id: "e1".into(),
+                company: "Acme".into(),
+                role: "Ingénieure".into(),
+                start_date: "2020".into(),
+                end_date: None,
+                achievements: vec!["Migration Rust chez Acme".into()],
+                technologies: vec![],
+                role_translations: Default::default(),
+                achievement_translations: Default::default(),
+            }],
+            skills: vec![],
+            education: vec![Education {
+                id: "ed1".into(),
+                school: "École Centrale".into(),
+                degree: "Ingénieur".into(),
+                start_date: "2012".into(),
+                end_date: None,
+                notes: None,
+            }],
+            projects: vec![],
+            languages: vec![],
+            language: None,
+            summary_translations: Default::default(),
+        }
+    }
+
+    #[test]
+    fn scrubs_identity_from_profile_and_free_text() {
+        let source = profile();
+        let redactor = Redactor::new(&source, AnonymizeOptions { companies: true }, Language::Fr);
+        let anonymized = redactor.profile(&source);
+
+        assert_eq!(anonymized.full_name, "Candidat·e");
+        assert!(anonymized.email.is_none() && anonymized.phone.is_none());
+        assert_eq!(
+            anonymized.summary.as_deref(),
+            Some("Candidat·e, [âge masqué], ingénieure chez Entreprise A.")
+        );
+        assert_eq!(anonymized.experiences[0].company, "Entreprise A");
+        assert_eq!(anonymized.education[0].school, "Établissement 1");
+
+        let letter = redactor.scrub("Contact : jeanne.dupont@example.com / 06 12 34 56 78. Dupont");
+        assert_eq!(letter, "Contact : [e-mail] / [téléphone]. Candidat·e");
+        assert_eq!(redactor.scrub("(2019 - 2021)"), "(2019 - 2021)");
+    }
+
+    #[test]
+    fn companies_are_kept_unless_requested() {
+        let source = profile();
+        let redactor = Redactor::new(&source, AnonymizeOptions::default(), Language::Fr);
+        assert_eq!(redactor.profile(&source).experiences[0].company, "Acme");
+    }
+
+    #[test]
+    fn highlights_follow_replacements() {
+        let source = profile();
+        let redactor = Redactor::new(&source, AnonymizeOptions::default(), Language::En);
+        let resume = "Jeanne Dupont\nRust expert";
+        let rust = resume.find("Rust").unwrap();
+        let adaptation = AdaptationResult {
+            adapted_resume: resume.into(),
+            resume_document: None,
+            adapted_cover_letter: Default::default(),
+            highlights: vec![
+                HighlightSpan {
+                    id: "h1".into(),
+                    highlight_type: HighlightType::Emphasis,
+                    start: rust,
+                    end: rust + 4,
+                    utf8: None,
+                    utf16: None,
+                    removed_text: None,
+                },
+                HighlightSpan {
+                    id: "h2".into(),
+                    highlight_type: HighlightType::Addition,
+                    start: 0,
+                    end: 6,
+                    utf8: None,
+                    utf16: None,
+                    removed_text: None,
+                },
+                HighlightSpan {
+                    id: "h3".into(),
+                    highlight_type: HighlightType::Removal,
+                    start: rust,
+                    end: rust,
+                    utf8: None,
+                    utf16: None,
+                    removed_text: Some("Jeanne Dupont, Acme".into()),
+                },
+            ],
+            cover_letter_highlights: Vec::new(),
+            language: None,
+            offer_id: None,
+            user_edited: false,
+        };
+
+        let anonymized = redactor.adaptation(&adaptation);
+        assert_eq!(anonymized.adapted_resume, "Candidate\nRust expert");
+        let removal = anonymized
+            .highlights
+            .iter()
+            .find(|span| span.id == "h3")
+            .unwrap();
+        assert_eq!(removal.removed_text.as_deref(), Some("Candidate, Acme"));
+        let rust_span = &anonymized.highlights[0];
+        assert_eq!(
+            &anonymized.adapted_resume[rust_span.start..rust_span.end],
+            "Rust"
+        );
+        let name_span = &anonymized.highlights[1];
+        assert_eq!(
+            &anonymized.adapted_resume[name_span.start..name_span.end],
+            "Candidate"
+        );
+    }
+
+    #[test]
+    fn emphasis_does_not_split_a_redacted_value() {
+        let source = profile();
+        let redactor = Redactor::new(&source, AnonymizeOptions { companies: true }, Language::Fr);
+        let inline = |text: &str, emphasis: bool| Inline {
+            text: text.into(),
+            emphasis,
+        };
+        let document = ResumeDocument {
+            name: "Jeanne Dupont".into(),
+            sections: vec![ResumeSection {
+                heading: Some("Formation".into()),
+                blocks: vec![
+                    ResumeBlock::Paragraph {
+                        text: vec![
+                            inline("Diplômée de l'École ", false),
+                            inline("Centrale", true),
+                        ],
+                    },
+                    ResumeBlock::Paragraph {
+                        text: vec![
+                            inline("Lead chez A", false),
+                            inline("cme", true),
+                            inline(", ", false),
+                            inline("Rust", true),
+                        ],
+                    },
+                ],
+            }],
+        };
+
+        let anonymized = document.map_text(|rich| redactor.scrub_rich(rich));
+        assert_eq!(anonymized.name, "Candidat·e");
+        let paragraphs: Vec<&RichText> = anonymized.sections[0]
+            .blocks
+            .iter()
+            .filter_map(|block| match block {
+                ResumeBlock::Paragraph { text } => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            *paragraphs[0],
+            [
+                inline("Diplômée de l'", false),
+                inline("Établissement 1", true)
+            ]
+        );
+        assert_eq!(
+            *paragraphs[1],
+            [
+                inline("Lead chez ", false),
+                inline("Entreprise A", true),
+                inline(", ", false),
+                inline("Rust", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn letters_continue_after_z() {
+        assert_eq!(letter(0), "A");
+        assert_eq!(letter(25), "Z");
+        assert_eq!(letter(26), "AA");
+    }
+}
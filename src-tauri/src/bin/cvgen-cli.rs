@@ -0,0 +1,394 @@
+//! `cvgen-cli` : le pipeline import → offre → adaptation → export, sans fenêtre.
+//!
+//! Lit et écrit les mêmes fichiers que l'application (`settings.json`, `vault.json`,
+//! `storage.json`) ; chaque commande affiche son résultat en JSON sur la sortie standard et,
+//! en cas d'échec, l'erreur (`key`, `args`, `message`) sur la sortie d'erreur.
+
+use std::{fs, io::Read, path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use cvgen::{
+    ai_engine::{adapt, analyze, AdaptRequest, ConfiguredEngine},
+    anonymize::AnonymizeOptions,
+    exporter::{export, ExportRequest},
+    file_import::{add_offer, import_profile},
+    locale::Language,
+    logging::{init_logging, LogLevel},
+    messages::{apply_ui_language, AppError},
+    models::{ExportFormat, JobOffer},
+    paths::AppPaths,
+    python_bridge::PythonBridge,
+    search::DocumentKind,
+    settings::{read_settings, Settings},
+    state::{read_persisted, try_get_offer, write_persisted, AppMemory, SharedState},
+    vault::{read_vault, upgrade_legacy_vault, Vault},
+};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+/// Variable lue pour déverrouiller les données chiffrées.
+const PASSPHRASE_ENV: &str = "CVGEN_PASSPHRASE";
+
+#[derive(Parser)]
+#[command(name = "cvgen-cli", version, about = "CVGen sans interface graphique")]
+struct Cli {
+    /// JSON indenté plutôt que compact.
+    #[arg(long, global = true)]
+    pretty: bool,
+    /// Journaux détaillés sur la sortie d'erreur.
+    #[arg(short, long, global = true)]
+    verbose: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Importe un CV (PDF, DOCX, PNG/JPG) et enregistre le profil extrait.
+    Import { file: PathBuf },
+    /// Offres d'emploi.
+    #[command(subcommand)]
+    Offer(OfferCommand),
+    /// Adapte CV et lettre d'un profil (ou d'une variante) à une offre.
+    Adapt {
+        #[arg(long)]
+        profile: String,
+        #[arg(long)]
+        offer: String,
+        #[arg(long)]
+        variant: Option<String>,
+        #[arg(long)]
+        language: Option<Language>,
+        /// Relance le moteur même si une adaptation est en cache.
+        #[arg(long)]
+        regenerate: bool,
+        /// Régénère aussi une adaptation retouchée, en perdant les retouches.
+        #[arg(long)]
+        discard_edits: bool,
+    },
+    /// Exporte la dernière adaptation d'un profil.
+    Export {
+        #[arg(long)]
+        profile: String,
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+        #[arg(long)]
+        variant: Option<String>,
+        #[arg(long)]
+        language: Option<Language>,
+        /// Export anonymisé (nom, coordonnées, écoles).
+        #[arg(long)]
+        anonymize: bool,
+        /// Avec `--anonymize`, masque aussi les entreprises.
+        #[arg(long, requires = "anonymize")]
+        anonymize_companies: bool,
+    },
+    /// Liste les éléments enregistrés.
+    List {
+        #[arg(value_enum)]
+        kind: ListKind,
+    },
+    /// Recherche plein texte (mots, `préfixe*`, "expression exacte").
+    Search {
+        query: String,
+        /// Restreint à un type d'élément (répétable).
+        #[arg(long, value_enum)]
+        kind: Vec<SearchKind>,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum OfferCommand {
+    /// Enregistre une offre ; la description est lue dans `--file`, ou sur l'entrée standard.
+    Add {
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        company: Option<String>,
+        #[arg(long)]
+        location: Option<String>,
+        #[arg(long)]
+        language: Option<Language>,
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Analyse une offre enregistrée (mots-clés, langue).
+    Analyze { id: String },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FormatArg {
+    Pdf,
+    Docx,
+}
+
+impl From<FormatArg> for ExportFormat {
+    fn from(value: FormatArg) -> Self {
+        match value {
+            FormatArg::Pdf => ExportFormat::Pdf,
+            FormatArg::Docx => ExportFormat::Docx,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ListKind {
+    Profiles,
+    Offers,
+    Adaptations,
+    Variants,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SearchKind {
+    Profiles,
+    Offers,
+    Adaptations,
+}
+
+impl From<SearchKind> for DocumentKind {
+    fn from(value: SearchKind) -> Self {
+        match value {
+            SearchKind::Profiles => DocumentKind::Profile,
+            SearchKind::Offers => DocumentKind::Offer,
+            SearchKind::Adaptations => DocumentKind::Adaptation,
+        }
+    }
+}
+
+/// Données chargées depuis le disque pour la durée d'une commande.
+struct Backend {
+    paths: AppPaths,
+    settings: Settings,
+    vault: Vault,
+    state: SharedState,
+    bridge: PythonBridge,
+}
+
+impl Backend {
+    fn open() -> Result<Self, AppError> {
+        let paths = AppPaths::headless().map_err(storage_error)?;
+        let settings = read_settings(&paths)
+            .map_err(storage_error)?
+            .unwrap_or_default();
+        apply_ui_language(settings.ui_language);
+
+        let mut vault = read_vault(&paths).map_err(storage_error)?;
+        if vault.status().enabled {
+            let passphrase = Zeroizing::new(
+                std::env::var(PASSPHRASE_ENV).map_err(|_| AppError::new("errors.vault.locked"))?,
+            );
+            vault.unlock(&passphrase)?;
+            upgrade_legacy_vault(&paths, &settings, &mut vault)?;
+        }
+        let memory = read_persisted(&paths.storage_file(), &vault)
+            .map_err(storage_error)?
+            .map(AppMemory::from)
+            .unwrap_or_default();
+        let bridge = PythonBridge::headless(settings.python_command.as_deref());
+
+        Ok(Self {
+            paths,
+            settings,
+            vault,
+            state: SharedState(Mutex::new(memory)),
+            bridge,
+        })
+    }
+
+    fn save(&self) -> Result<(), AppError> {
+        let mut memory = self.state.0.lock();
+        let written = write_persisted(&self.paths.storage_file(), &self.vault, memory.clone())
+            .map_err(storage_error)?;
+        *memory = written.into();
+        Ok(())
+    }
+}
+
+fn storage_error(error: anyhow::Error) -> AppError {
+    AppError::new("errors.cli.storage").arg("details", error)
+}
+
+async fn execute(command: Command) -> Result<Value, AppError> {
+    let backend = Backend::open()?;
+    let output = match command {
+        Command::Import { file } => {
+            let result =
+                import_profile(&backend.bridge, &backend.state, &file.to_string_lossy()).await?;
+            to_value(result)
+        }
+        Command::Offer(OfferCommand::Add {
+            title,
+            company,
+            location,
+            language,
+            file,
+        }) => {
+            let offer = JobOffer {
+                id: Uuid::new_v4().to_string(),
+                title,
+                company,
+                description: read_description(file)?,
+                location,
+                keywords: None,
+                language,
+                date_posted: None,
+                valid_through: None,
+                salary: None,
+                contact: None,
+            };
+            to_value(add_offer(&backend.state, offer))
+        }
+        Command::Offer(OfferCommand::Analyze { id }) => {
+            let offer = try_get_offer(&backend.state, &id)
+                .ok_or_else(|| AppError::new("errors.offerNotFound").arg("id", &id))?;
+            to_value(analyze(&backend.bridge, &backend.state, offer).await?)
+        }
+        Command::Adapt {
+            profile,
+            offer,
+            variant,
+            language,
+            regenerate,
+            discard_edits,
+        } => {
+            let request = AdaptRequest {
+                profile_id: profile,
+                offer_id: offer,
+                variant_id: variant,
+                language,
+                regenerate,
+                discard_edits,
+            };
+            let engine = ConfiguredEngine::new(&backend.settings, || Ok(backend.bridge.clone()))?;
+            to_value(adapt(&engine, &backend.state, &backend.settings, request).await?)
+        }
+        Command::Export {
+            profile,
+            format,
+            variant,
+            language,
+            anonymize,
+            anonymize_companies,
+        } => {
+            let request = ExportRequest {
+                profile_id: profile,
+                format: format.map(ExportFormat::from),
+                variant_id: variant,
+                language,
+                anonymize: anonymize.then_some(AnonymizeOptions {
+                    companies: anonymize_companies,
+                }),
+            };
+            // Un export ne modifie pas l'état : rien à réécrire.
+            return Ok(to_value(
+                export(&backend.bridge, &backend.state, &backend.settings, request).await?,
+            ));
+        }
+        Command::List { kind } => return Ok(list(&backend.state, kind)),
+        Command::Search { query, kind, limit } => {
+            let kinds: Vec<DocumentKind> = kind.into_iter().map(DocumentKind::from).collect();
+            let hits = backend.state.0.lock().search.search(&query, &kinds, limit);
+            return Ok(to_value(hits));
+        }
+    };
+
+    backend.save()?;
+    Ok(output)
+}
+
+fn read_description(file: Option<PathBuf>) -> Result<String, AppError> {
+    let read = || -> std::io::Result<String> {
+        match file {
+            Some(path) => fs::read_to_string(path),
+            None => {
+                let mut text = String::new();
+                std::io::stdin().read_to_string(&mut text)?;
+                Ok(text)
+            }
+        }
+    };
+    read().map_err(|error| AppError::new("errors.cli.input").arg("details", error))
+}
+
+fn list(state: &SharedState, kind: ListKind) -> Value {
+    let memory = state.0.lock();
+    let mut items: Vec<Value> = match kind {
+        ListKind::Profiles => memory
+            .profiles
+            .values()
+            .map(|profile| {
+                json!({ "id": profile.id, "fullName": profile.full_name, "language": profile.language })
+            })
+            .collect(),
+        ListKind::Offers => memory
+            .offers
+            .values()
+            .map(|offer| {
+                json!({
+                    "id": offer.id,
+                    "title": offer.title,
+                    "company": offer.company,
+                    "language": offer.language
+                })
+            })
+            .collect(),
+        ListKind::Adaptations => memory
+            .adaptations
+            .iter()
+            .map(|(key, adaptation)| json!({ "id": key, "language": adaptation.language }))
+            .collect(),
+        ListKind::Variants => memory
+            .variants
+            .values()
+            .map(|variant| {
+                json!({ "id": variant.id, "profileId": variant.profile_id, "name": variant.name })
+            })
+            .collect(),
+    };
+    items.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+    Value::Array(items)
+}
+
+fn to_value(value: impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+fn print_json(value: &impl Serialize, pretty: bool) -> String {
+    let rendered = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    rendered.unwrap_or_default()
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let level = if cli.verbose {
+        LogLevel::Debug
+    } else {
+        LogLevel::Warn
+    };
+    let log_dir = AppPaths::headless().ok().map(|paths| paths.log_dir);
+    if let Err(error) = init_logging(log_dir.as_deref(), level) {
+        eprintln!("Impossible d'initialiser la journalisation: {error:?}");
+    }
+
+    match execute(cli.command).await {
+        Ok(output) => {
+            println!("{}", print_json(&output, cli.pretty));
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{}", print_json(&error, cli.pretty));
+            ExitCode::FAILURE
+        }
+    }
+}
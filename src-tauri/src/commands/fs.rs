@@ -1,123 +1,744 @@
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{fs, path::{PathBuf}, io};
+use std::{cmp::Ordering, collections::BTreeMap, fs, path::{Path, PathBuf}, io};
 use chrono::Utc;
 
-const APP_DIR_NAME: &str = "CVGen"; // Stored in ~/Documents/CVGen
+use tauri::{AppHandle, State};
+use tracing::instrument;
 
-fn documents_base_dir() -> Result<PathBuf, String> {
-    let Some(home) = dirs::home_dir() else { return Err("Impossible de déterminer le répertoire home".into()); };
-    let docs = home.join("Documents").join(APP_DIR_NAME);
+use crate::{journal::JournalKind, library_history::record_change, messages::AppError, settings::{apply_settings, Settings, SettingsState}, state::SharedState, vault::{is_current, Vault, VaultState}};
+
+const APP_DIR_NAME: &str = "CVGen";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Library root: `Settings::library_dir`, else the legacy `~/Documents/CVGen` if it already
+/// exists, else the platform documents folder (XDG user-dirs on Linux), else the local data dir.
+/// Nothing is created here; writers call `ensure_library_dir`.
+pub fn library_root(settings: &Settings) -> Result<PathBuf, AppError> {
+    if let Some(dir) = &settings.library_dir { return Ok(dir.clone()); }
+    let legacy = dirs::home_dir().map(|home| home.join("Documents").join(APP_DIR_NAME));
+    if let Some(legacy) = legacy.filter(|dir| dir.is_dir()) { return Ok(legacy); }
+    dirs::document_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join(APP_DIR_NAME))
+        .ok_or_else(|| AppError::new("errors.fs.homeUnknown"))
+}
+
+pub(crate) fn ensure_library_dir(settings: &Settings) -> Result<PathBuf, AppError> {
+    let docs = library_root(settings)?;
     if !docs.exists() {
-        fs::create_dir_all(&docs).map_err(|e| format!("Création dossier échouée: {e}"))?;
+        fs::create_dir_all(&docs).map_err(|e| AppError::new("errors.fs.createDir").arg("details", e))?;
     }
     Ok(docs)
 }
 
-fn cv_file_path(id: &str) -> Result<PathBuf, String> {
-    let base = documents_base_dir()?;
+pub(crate) fn cv_file_path(settings: &Settings, id: &str) -> Result<PathBuf, AppError> {
+    let base = library_root(settings)?;
     // sanitize id (basic): keep alnum, dash, underscore
-    let safe: String = id.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_').collect();
-    if safe.is_empty() { return Err("ID CV invalide".into()); }
+    let safe = sanitize_id(id);
+    if safe.is_empty() { return Err(AppError::new("errors.fs.invalidId")); }
     Ok(base.join(format!("{safe}.json")))
 }
 
-fn manifest_path() -> Result<PathBuf, String> { Ok(documents_base_dir()?.join("manifest.json")) }
+fn sanitize_id(id: &str) -> String {
+    id.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_').collect()
+}
+
+/// CV id of a library file: its stem, unless it is the manifest or not a valid id.
+fn cv_id(path: &Path) -> Option<String> {
+    if path.file_name().and_then(|n| n.to_str()) == Some(MANIFEST_FILE) { return None; }
+    let stem = path.file_stem().and_then(|s| s.to_str())?;
+    (!stem.is_empty() && sanitize_id(stem) == stem).then(|| stem.to_string())
+}
+
+/// `manifest.json`: `{ "items": { id: entry } }`. Folders and tags are library metadata only;
+/// CV files always stay flat in the library root.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub items: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ManifestEntry {
+    pub title: String,
+    pub updated_at: String,
+    /// Missing for CVs saved before it was recorded; `updatedAt` is the best guess then.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    pub tags: Vec<String>,
+    /// Slash-separated virtual folder, `""` for the root.
+    pub folder: String,
+    pub favorite: bool,
+    pub offer_ids: Vec<String>,
+}
+
+impl Default for ManifestEntry {
+    fn default() -> Self {
+        Self { title: "CV".into(), updated_at: String::new(), created_at: None, tags: vec![], folder: String::new(), favorite: false, offer_ids: vec![] }
+    }
+}
+
+/// One `list_cvs_meta` row: the manifest entry plus its id.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CvMeta {
+    pub id: String,
+    #[serde(flatten)]
+    pub entry: ManifestEntry,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CvSortKey { #[default] UpdatedAt, CreatedAt, Title, Folder, Favorite }
+
+/// `list_cvs_meta` filters (all optional, combined with AND) and ordering.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CvListFilter {
+    /// Only CVs in this folder; with `recursive`, its subfolders too.
+    pub folder: Option<String>,
+    pub recursive: bool,
+    /// CVs carrying every listed tag (case-insensitive).
+    pub tags: Vec<String>,
+    pub favorite: Option<bool>,
+    pub offer_id: Option<String>,
+    pub sort_by: CvSortKey,
+    /// Defaults to newest first for dates, favourites first, A→Z otherwise.
+    pub descending: Option<bool>,
+}
+
+impl CvListFilter {
+    fn matches(&self, entry: &ManifestEntry) -> bool {
+        let in_folder = match &self.folder {
+            None => true,
+            Some(folder) if self.recursive => folder.is_empty() || entry.folder == *folder || entry.folder.starts_with(&format!("{folder}/")),
+            Some(folder) => entry.folder == *folder,
+        };
+        in_folder
+            && self.tags.iter().all(|tag| entry.tags.iter().any(|own| own.to_lowercase() == tag.to_lowercase()))
+            && self.favorite.is_none_or(|favorite| entry.favorite == favorite)
+            && self.offer_id.as_ref().is_none_or(|offer| entry.offer_ids.contains(offer))
+    }
+
+    fn compare(&self, a: &CvMeta, b: &CvMeta) -> Ordering {
+        let created = |meta: &CvMeta| meta.entry.created_at.clone().unwrap_or_else(|| meta.entry.updated_at.clone());
+        let (ordering, descending_by_default) = match self.sort_by {
+            CvSortKey::UpdatedAt => (a.entry.updated_at.cmp(&b.entry.updated_at), true),
+            CvSortKey::CreatedAt => (created(a).cmp(&created(b)), true),
+            CvSortKey::Title => (a.entry.title.to_lowercase().cmp(&b.entry.title.to_lowercase()), false),
+            CvSortKey::Folder => (a.entry.folder.cmp(&b.entry.folder), false),
+            CvSortKey::Favorite => (a.entry.favorite.cmp(&b.entry.favorite), true),
+        };
+        let ordering = if self.descending.unwrap_or(descending_by_default) { ordering.reverse() } else { ordering };
+        // Ties: most recent first, then id, so the order is stable across calls.
+        ordering.then_with(|| b.entry.updated_at.cmp(&a.entry.updated_at)).then_with(|| a.id.cmp(&b.id))
+    }
+}
+
+fn manifest_path(settings: &Settings) -> Result<PathBuf, AppError> { Ok(library_root(settings)?.join(MANIFEST_FILE)) }
+
+/// Associated data binding an encrypted library file to its name.
+pub(crate) fn library_context(name: &str) -> String { format!("library/{name}") }
+
+fn path_context(path: &Path) -> String { library_context(path.file_name().and_then(|n| n.to_str()).unwrap_or_default()) }
+
+pub(crate) fn load_manifest(settings: &Settings, vault: &Vault) -> Result<Manifest, AppError> {
+    let path = manifest_path(settings)?;
+    if !path.exists() { return Ok(Manifest::default()); }
+    let content = vault.decode(fs::read(&path).map_err(|e| AppError::new("errors.fs.readManifest").arg("details", e))?, &path_context(&path))?;
+    serde_json::from_slice(&content).map_err(|e| AppError::new("errors.fs.parseManifest").arg("details", e))
+}
+
+pub(crate) fn save_manifest(settings: &Settings, vault: &Vault, manifest: &Manifest) -> Result<(), AppError> {
+    let path = manifest_path(settings)?;
+    let pretty = serde_json::to_string_pretty(manifest).map_err(|e| AppError::new("errors.fs.serializeManifest").arg("details", e))?;
+    fs::write(&path, vault.encode(pretty.as_bytes(), &path_context(&path))?).map_err(|e| AppError::new("errors.fs.writeManifest").arg("details", e))
+}
+
+/// Every `*.json` file of the library root: CV documents and `manifest.json`.
+pub(crate) fn library_files(settings: &Settings) -> Result<Vec<PathBuf>, AppError> {
+    let base = library_root(settings)?;
+    if !base.exists() { return Ok(vec![]); }
+    let entries = fs::read_dir(&base).map_err(|e| AppError::new("errors.fs.readDir").arg("details", e))?;
+    let mut files: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json")).collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Encrypts every library file still stored as plaintext or in the legacy format (manifest included).
+/// Migration only: plaintext is rejected everywhere else once encryption is on.
+pub fn seal_library(settings: &Settings, vault: &Vault) -> Result<(), AppError> {
+    for path in library_files(settings)? {
+        let content = fs::read(&path).map_err(|e| AppError::new("errors.fs.read").arg("details", e))?;
+        if is_current(&content) { continue; }
+        let context = path_context(&path);
+        rewrite_file(&path, &vault.encode(&vault.decode_for_migration(content, &context)?, &context)?)?;
+    }
+    Ok(())
+}
 
-fn load_manifest() -> Result<Value, String> {
-    let path = manifest_path()?;
-    if !path.exists() { return Ok(Value::Object(serde_json::Map::new())); }
-    let content = fs::read_to_string(path).map_err(|e| format!("Lecture manifest échouée: {e}"))?;
-    serde_json::from_str(&content).map_err(|e| format!("Parse manifest échoué: {e}"))
+/// Decrypts the library back to plaintext; undoes an interrupted `seal_library`.
+pub(crate) fn unseal_library(settings: &Settings, sealed: &Vault) -> Result<(), AppError> {
+    for path in library_files(settings)? {
+        let content = fs::read(&path).map_err(|e| AppError::new("errors.fs.read").arg("details", e))?;
+        rewrite_file(&path, &sealed.decode_for_migration(content, &path_context(&path))?)?;
+    }
+    Ok(())
 }
 
-fn save_manifest(manifest: &Value) -> Result<(), String> {
-    let path = manifest_path()?;
-    let pretty = serde_json::to_string_pretty(manifest).map_err(|e| format!("Sérialisation manifest échouée: {e}"))?;
-    fs::write(path, pretty).map_err(|e| format!("Écriture manifest échouée: {e}"))
+/// Write next to the original then swap, so a crash never leaves a half-written file.
+fn rewrite_file(path: &Path, content: &[u8]) -> Result<(), AppError> {
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, content).map_err(|e| AppError::new("errors.fs.write").arg("details", e))?;
+    fs::rename(&tmp_path, path).map_err(|e| AppError::new("errors.fs.write").arg("details", e))
 }
 
 #[tauri::command]
-pub fn save_cv(id: String, mut data: Value) -> Result<(), String> {
-    let path = cv_file_path(&id)?;
+#[instrument(skip_all, fields(id = %id))]
+pub fn save_cv(id: String, data: Value, state: State<'_, SharedState>, settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<(), AppError> {
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    let settings = settings.0.lock().clone();
+    let before = match cv_snapshot(&settings, &vault, &id) {
+        // Saving over a file that is not JSON repairs it; there is nothing to undo to.
+        Err(error) if error.key == "errors.fs.parse" => None,
+        other => other?,
+    };
+    write_cv(&settings, &vault, &id, data)?;
+    let after = cv_snapshot(&settings, &vault, &id)?;
+    state.0.lock().journal.record(JournalKind::Cv, &id, before, after);
+    record_change(&settings, &format!("Enregistrement de {id}"));
+    Ok(())
+}
+
+/// Writes a CV and refreshes its manifest entry; returns the document as stored.
+pub(crate) fn write_cv(settings: &Settings, vault: &Vault, id: &str, mut data: Value) -> Result<Value, AppError> {
+    ensure_library_dir(settings)?;
+    let path = cv_file_path(settings, id)?;
     // inject / update updatedAt
     let now = Utc::now().to_rfc3339();
     if let Value::Object(map) = &mut data {
-        map.insert("id".into(), Value::String(id.clone()));
+        map.insert("id".into(), Value::String(id.to_string()));
         map.insert("updatedAt".into(), Value::String(now.clone()));
     }
-    let pretty = serde_json::to_string_pretty(&data).map_err(|e| format!("Sérialisation JSON échouée: {e}"))?;
-    fs::write(&path, pretty).map_err(|e| format!("Écriture fichier échouée: {e}"))?;
-
-    // update manifest (structure: { "items": { id: { "updatedAt": ..., "title": ... }}})
-    let mut manifest = load_manifest()?;
-    let items = manifest.as_object_mut().unwrap();
-    let entry = items.entry("items").or_insert_with(|| Value::Object(serde_json::Map::new()));
-    if let Value::Object(obj) = entry {
-        let mut meta = serde_json::Map::new();
-        // title fallback
-        let title = data.get("fullName").and_then(|v| v.as_str()).unwrap_or("CV");
-        meta.insert("updatedAt".into(), Value::String(now));
-        meta.insert("title".into(), Value::String(title.to_string()));
-        obj.insert(id, Value::Object(meta));
-    }
-    save_manifest(&manifest)?;
-    Ok(())
+    // Read the manifest first: an unreadable one must fail the save before the file changes.
+    let mut manifest = load_manifest(settings, vault)?;
+    // Tags, folder, favourite and linked offers survive a save; only title and dates change.
+    let entry = manifest.items.entry(id.to_string()).or_insert_with(|| ManifestEntry { created_at: Some(now.clone()), ..Default::default() });
+    entry.title = document_title(&data);
+    entry.updated_at = now;
+    store_cv(settings, vault, &path, &data, &manifest)?;
+    Ok(data)
+}
+
+/// Writes the CV file, then the manifest already updated for it.
+fn store_cv(settings: &Settings, vault: &Vault, path: &Path, data: &Value, manifest: &Manifest) -> Result<(), AppError> {
+    let pretty = serde_json::to_string_pretty(data).map_err(|e| AppError::new("errors.fs.serialize").arg("details", e))?;
+    fs::write(path, vault.encode(pretty.as_bytes(), &path_context(path))?).map_err(|e| AppError::new("errors.fs.write").arg("details", e))?;
+    save_manifest(settings, vault, manifest)
+}
+
+/// Undo journal snapshot of a CV: `{ "document", "entry" }`, the manifest entry included so
+/// that undoing a delete brings tags, folder, favourite and linked offers back. `None` if the
+/// file is missing; a file that is not JSON is an error (`errors.fs.parse`).
+pub(crate) fn cv_snapshot(settings: &Settings, vault: &Vault, id: &str) -> Result<Option<Value>, AppError> {
+    let Some(document) = read_cv(settings, vault, id)? else { return Ok(None) };
+    let entry = load_manifest(settings, vault)?.items.remove(id);
+    Ok(Some(serde_json::json!({ "document": document, "entry": entry })))
+}
+
+/// Puts a `cv_snapshot` back as it was (same `updatedAt`), or deletes the CV for `None`. The
+/// current manifest entry wins over the snapshot's: later tag or folder changes are kept.
+pub(crate) fn restore_cv(settings: &Settings, vault: &Vault, id: &str, snapshot: Option<&Value>) -> Result<(), AppError> {
+    let Some(snapshot) = snapshot else { return remove_cv(settings, vault, id) };
+    ensure_library_dir(settings)?;
+    let path = cv_file_path(settings, id)?;
+    let document = &snapshot["document"];
+    let mut manifest = load_manifest(settings, vault)?;
+    let saved = serde_json::from_value::<Option<ManifestEntry>>(snapshot["entry"].clone()).ok().flatten().unwrap_or_default();
+    let entry = manifest.items.entry(id.to_string()).or_insert(saved);
+    entry.title = document_title(document);
+    if let Some(updated_at) = document.get("updatedAt").and_then(Value::as_str) { entry.updated_at = updated_at.to_string(); }
+    store_cv(settings, vault, &path, document, &manifest)
+}
+
+/// Manifest title of a CV document.
+fn document_title(data: &Value) -> String {
+    data.get("fullName").and_then(|v| v.as_str()).unwrap_or("CV").to_string()
+}
+
+/// Current content of a CV: `None` if missing, `errors.fs.parse` if not valid JSON.
+fn read_cv(settings: &Settings, vault: &Vault, id: &str) -> Result<Option<Value>, AppError> {
+    let path = cv_file_path(settings, id)?;
+    if !path.exists() { return Ok(None); }
+    let content = vault.decode(fs::read(&path).map_err(|e| AppError::new("errors.fs.read").arg("details", e))?, &path_context(&path))?;
+    serde_json::from_slice(&content).map(Some).map_err(|e| AppError::new("errors.fs.parse").arg("details", e))
 }
 
 #[tauri::command]
-pub fn load_cv(id: String) -> Result<Value, String> {
-    let path = cv_file_path(&id)?;
-    let content = fs::read_to_string(&path).map_err(|e| format!("Lecture fichier échouée: {e}"))?;
-    let json: Value = serde_json::from_str(&content).map_err(|e| format!("Parse JSON échoué: {e}"))?;
+#[instrument(skip_all, fields(id = %id))]
+pub fn load_cv(id: String, settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<Value, AppError> {
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    let path = cv_file_path(&settings.0.lock(), &id)?;
+    let content = vault.decode(fs::read(&path).map_err(|e| AppError::new("errors.fs.read").arg("details", e))?, &path_context(&path))?;
+    let json: Value = serde_json::from_slice(&content).map_err(|e| AppError::new("errors.fs.parse").arg("details", e))?;
     Ok(json)
 }
 
 #[tauri::command]
-pub fn list_cvs() -> Result<Value, String> {
-    let base = documents_base_dir()?;
-    let mut ids = vec![];
-    for entry in fs::read_dir(&base).map_err(|e| format!("Lecture répertoire échouée: {e}"))? {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) == Some("json") {
-                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                    ids.push(stem.to_string());
-                }
+#[instrument(skip_all)]
+pub fn list_cvs(settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<Value, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let files = library_files(&settings.0.lock())?;
+    Ok(Value::Array(files.iter().filter_map(|path| cv_id(path)).map(Value::String).collect()))
+}
+
+/// Without a filter: every CV, most recently updated first.
+#[tauri::command]
+#[instrument(skip_all)]
+pub fn list_cvs_meta(filter: Option<CvListFilter>, settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<Vec<CvMeta>, AppError> {
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    let mut filter = filter.unwrap_or_default();
+    if let Some(folder) = &filter.folder { filter.folder = Some(normalize_folder(folder)?); }
+    let manifest = load_manifest(&settings.0.lock(), &vault)?;
+    let mut list: Vec<CvMeta> = manifest.items.into_iter()
+        .filter(|(_, entry)| filter.matches(entry))
+        .map(|(id, entry)| CvMeta { id, entry })
+        .collect();
+    list.sort_by(|a, b| filter.compare(a, b));
+    Ok(list)
+}
+
+/// Replaces the tags of a CV (trimmed, duplicates dropped case-insensitively).
+#[tauri::command]
+#[instrument(skip_all, fields(id = %id))]
+pub fn tag_cv(id: String, tags: Vec<String>, settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<CvMeta, AppError> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+        if !cleaned.iter().any(|own| own.to_lowercase() == tag.to_lowercase()) { cleaned.push(tag.to_string()); }
+    }
+    update_entry(&id, &settings, &vault, |entry| entry.tags = cleaned)
+}
+
+/// Files a CV under `folder` (`""` for the root); the CV file itself does not move.
+#[tauri::command]
+#[instrument(skip_all, fields(id = %id))]
+pub fn move_cv(id: String, folder: String, settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<CvMeta, AppError> {
+    let folder = normalize_folder(&folder)?;
+    update_entry(&id, &settings, &vault, |entry| entry.folder = folder)
+}
+
+#[tauri::command]
+#[instrument(skip_all, fields(id = %id))]
+pub fn favorite_cv(id: String, favorite: bool, settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<CvMeta, AppError> {
+    update_entry(&id, &settings, &vault, |entry| entry.favorite = favorite)
+}
+
+/// Replaces the offers a CV was written for.
+#[tauri::command]
+#[instrument(skip_all, fields(id = %id))]
+pub fn link_cv_offers(id: String, offer_ids: Vec<String>, settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<CvMeta, AppError> {
+    let mut linked: Vec<String> = Vec::new();
+    for offer in offer_ids { if !offer.is_empty() && !linked.contains(&offer) { linked.push(offer); } }
+    update_entry(&id, &settings, &vault, |entry| entry.offer_ids = linked)
+}
+
+/// Applies `change` to the manifest entry of an existing CV; `updatedAt` is left alone since
+/// the document itself did not change.
+fn update_entry(id: &str, settings: &State<'_, SettingsState>, vault: &State<'_, VaultState>, change: impl FnOnce(&mut ManifestEntry)) -> Result<CvMeta, AppError> {
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    let settings = settings.0.lock().clone();
+    if !cv_file_path(&settings, id)?.exists() { return Err(AppError::new("errors.fs.cvNotFound").arg("id", id)); }
+    let mut manifest = load_manifest(&settings, &vault)?;
+    let entry = manifest.items.entry(id.to_string()).or_default();
+    change(entry);
+    let meta = CvMeta { id: id.to_string(), entry: entry.clone() };
+    save_manifest(&settings, &vault, &manifest)?;
+    Ok(meta)
+}
+
+/// `" Clients / Acme/ "` → `"Clients/Acme"`; `.` and `..` segments are rejected.
+fn normalize_folder(folder: &str) -> Result<String, AppError> {
+    let segments: Vec<&str> = folder.split(['/', '\\']).map(str::trim).filter(|segment| !segment.is_empty()).collect();
+    if segments.iter().any(|segment| *segment == "." || *segment == "..") {
+        return Err(AppError::new("errors.fs.invalidFolder").arg("folder", folder));
+    }
+    Ok(segments.join("/"))
+}
+
+#[tauri::command]
+#[instrument(skip_all, fields(id = %id))]
+pub fn delete_cv(id: String, state: State<'_, SharedState>, settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<(), AppError> {
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    let settings = settings.0.lock().clone();
+    // A file that is not JSON could not be restored: the delete is refused.
+    let before = cv_snapshot(&settings, &vault, &id)?;
+    remove_cv(&settings, &vault, &id)?;
+    if before.is_some() {
+        state.0.lock().journal.record(JournalKind::Cv, &id, before, None);
+        record_change(&settings, &format!("Suppression de {id}"));
+    }
+    Ok(())
+}
+
+/// Deletes a CV file and its manifest entry.
+pub(crate) fn remove_cv(settings: &Settings, vault: &Vault, id: &str) -> Result<(), AppError> {
+    let path = cv_file_path(settings, id)?;
+    // As in `write_cv`, an unreadable manifest leaves the file in place.
+    let mut manifest = load_manifest(settings, vault)?;
+    match fs::remove_file(&path) {
+        Ok(_) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(AppError::new("errors.fs.delete").arg("details", e)),
+    }
+    if manifest.items.remove(id).is_some() { save_manifest(settings, vault, &manifest)?; }
+    Ok(())
+}
+
+/// `check_library` findings. Ids are file stems; all lists are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryReport {
+    /// CV files without a manifest entry.
+    pub orphans: Vec<String>,
+    /// Manifest entries whose file is gone.
+    pub missing: Vec<String>,
+    /// CV files that cannot be decrypted or are not JSON.
+    pub unparseable: Vec<UnparseableCv>,
+    /// Entries whose title or `updatedAt` no longer match the document.
+    pub stale: Vec<String>,
+    /// Set when `manifest.json` itself cannot be read; every CV is then an orphan.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnparseableCv {
+    pub id: String,
+    pub details: String,
+}
+
+/// Parsed content of every CV file, or why it could not be read.
+fn scan_library(settings: &Settings, vault: &Vault) -> Result<BTreeMap<String, Result<Value, String>>, AppError> {
+    let mut documents = BTreeMap::new();
+    for path in library_files(settings)? {
+        let Some(id) = cv_id(&path) else { continue };
+        let content = fs::read(&path).map_err(|e| AppError::new("errors.fs.read").arg("details", e))?;
+        let document = vault.decode(content, &path_context(&path)).map_err(|e| e.to_string()).and_then(|content| serde_json::from_slice(&content).map_err(|e| e.to_string()));
+        documents.insert(id, document);
+    }
+    Ok(documents)
+}
+
+fn inspect_library(manifest: &Manifest, documents: &BTreeMap<String, Result<Value, String>>) -> LibraryReport {
+    let mut report = LibraryReport::default();
+    for (id, document) in documents {
+        let entry = manifest.items.get(id);
+        match (document, entry) {
+            (Err(details), _) => report.unparseable.push(UnparseableCv { id: id.clone(), details: details.clone() }),
+            (Ok(_), None) => report.orphans.push(id.clone()),
+            (Ok(data), Some(entry)) => {
+                let updated_at = data.get("updatedAt").and_then(|v| v.as_str());
+                if entry.title != document_title(data) || updated_at.is_some_and(|at| at != entry.updated_at) { report.stale.push(id.clone()); }
             }
         }
     }
-    Ok(Value::Array(ids.into_iter().map(Value::String).collect()))
+    report.missing = manifest.items.keys().filter(|id| !documents.contains_key(*id)).cloned().collect();
+    report
+}
+
+/// Manifest matching the files: entries of missing files are dropped, orphans get one, titles
+/// and dates come from the documents. Tags, folders, favourites and offers are kept, as is
+/// the entry of a file that cannot be read.
+fn rebuild_manifest(mut previous: Manifest, documents: &BTreeMap<String, Result<Value, String>>) -> Manifest {
+    let mut manifest = Manifest::default();
+    for (id, document) in documents {
+        let entry = previous.items.remove(id);
+        let entry = match (document, entry) {
+            (Err(_), entry) => entry,
+            (Ok(data), entry) => {
+                let updated_at = data.get("updatedAt").and_then(|v| v.as_str()).map(str::to_string);
+                let mut entry = entry.unwrap_or_else(|| ManifestEntry { created_at: updated_at.clone(), ..Default::default() });
+                entry.title = document_title(data);
+                if let Some(updated_at) = updated_at { entry.updated_at = updated_at; }
+                Some(entry)
+            }
+        };
+        if let Some(entry) = entry { manifest.items.insert(id.clone(), entry); }
+    }
+    manifest
+}
+
+/// Read-only consistency check of the files against `manifest.json`.
+#[tauri::command]
+#[instrument(skip_all)]
+pub fn check_library(settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<LibraryReport, AppError> {
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    let settings = settings.0.lock().clone();
+    let documents = scan_library(&settings, &vault)?;
+    let (manifest, manifest_error) = match load_manifest(&settings, &vault) {
+        Ok(manifest) => (manifest, None),
+        Err(error) => (Manifest::default(), Some(error.to_string())),
+    };
+    Ok(LibraryReport { manifest_error, ..inspect_library(&manifest, &documents) })
 }
 
+/// Rebuilds `manifest.json` from the CV files and returns what is left to fix by hand (files
+/// that cannot be read). An unreadable manifest is kept as `manifest.json.bak`.
 #[tauri::command]
-pub fn list_cvs_meta() -> Result<Value, String> {
-    let manifest = load_manifest()?;
-    let mut list: Vec<(String, String, String)> = Vec::new(); // (id, updatedAt, title)
-    if let Some(items) = manifest.get("items").and_then(|v| v.as_object()) {
-        for (id, meta) in items.iter() {
-            let updated = meta.get("updatedAt").and_then(|v| v.as_str()).unwrap_or("");
-            let title = meta.get("title").and_then(|v| v.as_str()).unwrap_or("CV");
-            list.push((id.clone(), updated.to_string(), title.to_string()));
+#[instrument(skip_all)]
+pub fn repair_library(settings: State<'_, SettingsState>, vault: State<'_, VaultState>) -> Result<LibraryReport, AppError> {
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    let settings = settings.0.lock().clone();
+    let documents = scan_library(&settings, &vault)?;
+    let previous = match load_manifest(&settings, &vault) {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            let path = manifest_path(&settings)?;
+            tracing::warn!(key = error.key, "Bibliothèque: manifest illisible, sauvegardé en .bak");
+            fs::rename(&path, path.with_extension("json.bak")).map_err(|e| AppError::new("errors.fs.write").arg("details", e))?;
+            Manifest::default()
         }
+    };
+    let manifest = rebuild_manifest(previous, &documents);
+    if !documents.is_empty() || manifest_path(&settings)?.exists() {
+        ensure_library_dir(&settings)?;
+        save_manifest(&settings, &vault, &manifest)?;
     }
-    // sort by updated desc
-    list.sort_by(|a,b| b.1.cmp(&a.1));
-    let arr: Vec<Value> = list.into_iter().map(|(id, updated_at, title)| {
-        let mut m = serde_json::Map::new();
-        m.insert("id".into(), Value::String(id));
-        m.insert("updatedAt".into(), Value::String(updated_at));
-        m.insert("title".into(), Value::String(title));
-        Value::Object(m)
-    }).collect();
-    Ok(Value::Array(arr))
+    record_change(&settings, "Réparation de la bibliothèque");
+    Ok(inspect_library(&manifest, &documents))
 }
 
 #[tauri::command]
-pub fn delete_cv(id: String) -> Result<(), String> {
-    let path = cv_file_path(&id)?;
-    match fs::remove_file(&path) {
-        Ok(_) => Ok(()),
-        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
-        Err(e) => Err(format!("Suppression échouée: {e}")),
+#[instrument(skip_all)]
+pub fn library_location(settings: State<'_, SettingsState>) -> Result<String, AppError> {
+    Ok(library_root(&settings.0.lock())?.display().to_string())
+}
+
+/// Moves the whole library (CV files and manifest) to `target`, then persists the new location.
+///
+/// A same-volume move is a single `rename`. Across volumes the library is first copied into a
+/// staging folder next to `target`, which is renamed into place only once the copy is complete,
+/// so `target` never holds a partial library and the source is left untouched on failure.
+#[tauri::command]
+#[instrument(skip_all)]
+pub fn move_library(target: String, settings: State<'_, SettingsState>, app: AppHandle) -> Result<Settings, AppError> {
+    let current = settings.0.lock().clone();
+    let source = library_root(&current)?;
+    let moved = transfer_library(&source, PathBuf::from(target), |target| {
+        let mut next = current.clone();
+        next.library_dir = Some(target.to_path_buf());
+        apply_settings(&app, next)
+    })?;
+    Ok(moved.unwrap_or(current))
+}
+
+/// Moves `source` to `target` and runs `commit` (which persists the new location); the files go
+/// back if it fails. `None` when both paths already name the same folder.
+fn transfer_library<T>(source: &Path, target: PathBuf, commit: impl FnOnce(&Path) -> Result<T, AppError>) -> Result<Option<T>, AppError> {
+    if !target.is_absolute() {
+        return Err(AppError::new("errors.settings.relativePath").arg("field", "libraryDir").arg("path", target.display()));
+    }
+    // Compare resolved paths: a symlink or `..` must not hide a target inside the library.
+    let resolve = |path: &Path| resolve_path(path).map_err(|e| AppError::new("errors.library.move").arg("details", e));
+    let (resolved_source, resolved_target) = (resolve(source)?, resolve(&target)?);
+    if resolved_target == resolved_source {
+        return Ok(None);
+    }
+    if resolved_target.starts_with(&resolved_source) {
+        return Err(AppError::new("errors.library.targetInside").arg("path", target.display()));
+    }
+    if target.exists() {
+        let empty = fs::read_dir(&target).map(|mut entries| entries.next().is_none()).unwrap_or(false);
+        if !empty { return Err(AppError::new("errors.library.targetNotEmpty").arg("path", target.display())); }
+        fs::remove_dir(&target).map_err(|e| AppError::new("errors.library.move").arg("details", e))?;
+    }
+
+    let moved = source.exists();
+    if moved {
+        relocate_dir(source, &target).map_err(|e| AppError::new("errors.library.move").arg("details", e))?;
+    }
+
+    match commit(&target) {
+        Ok(committed) => Ok(Some(committed)),
+        Err(error) => {
+            // Keep the files where the persisted settings still point.
+            if moved {
+                if let Err(rollback) = relocate_dir(&target, source) {
+                    tracing::error!(error = ?rollback, path = %source.display(), "Bibliothèque: retour arrière impossible");
+                }
+            }
+            Err(error)
+        }
+    }
+}
+
+/// Canonical form of `path`, which may not exist yet: its deepest existing ancestor is
+/// canonicalised and the missing components appended (they cannot be symlinks).
+fn resolve_path(path: &Path) -> io::Result<PathBuf> {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    while !existing.exists() {
+        let (Some(parent), Some(last)) = (existing.parent(), existing.components().next_back()) else { break };
+        missing.push(last.as_os_str());
+        existing = parent;
+    }
+    let mut resolved = existing.canonicalize()?;
+    for component in missing.into_iter().rev() {
+        match component.to_str() {
+            Some(".") => {}
+            Some("..") => { resolved.pop(); }
+            _ => resolved.push(component),
+        }
+    }
+    Ok(resolved)
+}
+
+fn relocate_dir(source: &Path, target: &Path) -> io::Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(source, target).is_ok() {
+        return Ok(());
+    }
+
+    // Different volume: copy into a sibling staging folder, then swap it in atomically.
+    let name = target.file_name().and_then(|n| n.to_str()).unwrap_or(APP_DIR_NAME);
+    let staging = target.with_file_name(format!(".{name}.moving"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    if let Err(error) = copy_dir_recursive(source, &staging).and_then(|_| fs::rename(&staging, target)) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(error);
+    }
+    if let Err(error) = fs::remove_dir_all(source) {
+        tracing::warn!(?error, path = %source.display(), "Bibliothèque: ancien dossier non supprimé");
+    }
+    Ok(())
+}
+
+fn copy_dir_recursive(source: &Path, target: &Path) -> io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let destination = target.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&entry.path(), &destination)?;
+        } else {
+            fs::copy(entry.path(), &destination)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use super::{inspect_library, normalize_folder, rebuild_manifest, transfer_library, CvListFilter, CvMeta, CvSortKey, Manifest, ManifestEntry};
+    use crate::messages::AppError;
+
+    fn meta(id: &str, folder: &str, tags: &[&str], favorite: bool, updated_at: &str) -> CvMeta {
+        let tags = tags.iter().map(|tag| tag.to_string()).collect();
+        CvMeta { id: id.into(), entry: ManifestEntry { folder: folder.into(), tags, favorite, updated_at: updated_at.into(), ..Default::default() } }
+    }
+
+    #[test]
+    fn filters_and_sorts_library_entries() {
+        let all = [
+            meta("a", "Clients", &["Rust"], false, "2026-01-01"),
+            meta("b", "Clients/Acme", &["rust", "backend"], true, "2026-03-01"),
+            meta("c", "", &[], false, "2026-02-01"),
+        ];
+        let run = |filter: CvListFilter| {
+            let mut list: Vec<&CvMeta> = all.iter().filter(|meta| filter.matches(&meta.entry)).collect();
+            list.sort_by(|a, b| filter.compare(a, b));
+            list.into_iter().map(|meta| meta.id.as_str()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(CvListFilter::default()), ["b", "c", "a"]);
+        assert_eq!(run(CvListFilter { folder: Some("Clients".into()), ..Default::default() }), ["a"]);
+        assert_eq!(run(CvListFilter { folder: Some("Clients".into()), recursive: true, ..Default::default() }), ["b", "a"]);
+        assert_eq!(run(CvListFilter { tags: vec!["RUST".into()], descending: Some(false), ..Default::default() }), ["a", "b"]);
+        assert_eq!(run(CvListFilter { sort_by: CvSortKey::Favorite, ..Default::default() }), ["b", "c", "a"]);
+
+        assert_eq!(normalize_folder(" Clients / Acme/ ").unwrap(), "Clients/Acme");
+        assert!(normalize_folder("../outside").is_err());
+    }
+
+    #[test]
+    fn reads_legacy_manifest_entries() {
+        let manifest: super::Manifest = serde_json::from_str(r#"{ "items": { "cv1": { "updatedAt": "2026-01-01", "title": "Élise" } } }"#).unwrap();
+        let entry = &manifest.items["cv1"];
+        assert_eq!(entry.title, "Élise");
+        assert!(entry.tags.is_empty() && entry.created_at.is_none() && !entry.favorite);
+    }
+
+    #[test]
+    fn reports_and_repairs_library_inconsistencies() {
+        let entry = |title: &str, updated_at: &str| ManifestEntry { title: title.into(), updated_at: updated_at.into(), tags: vec!["rust".into()], ..Default::default() };
+        let manifest = Manifest { items: BTreeMap::from([
+            ("ok".to_string(), entry("Ana", "2026-01-01")),
+            ("renamed".to_string(), entry("Old", "2026-01-01")),
+            ("gone".to_string(), entry("Bob", "2026-01-01")),
+            ("broken".to_string(), entry("Eve", "2026-01-01")),
+        ]) };
+        let documents = BTreeMap::from([
+            ("ok".to_string(), Ok(json!({ "fullName": "Ana", "updatedAt": "2026-01-01" }))),
+            ("renamed".to_string(), Ok(json!({ "fullName": "New", "updatedAt": "2026-02-01" }))),
+            ("new".to_string(), Ok(json!({ "updatedAt": "2026-03-01" }))),
+            ("broken".to_string(), Err("expected value".to_string())),
+        ]);
+
+        let report = inspect_library(&manifest, &documents);
+        assert_eq!((report.orphans, report.missing, report.stale), (vec!["new".to_string()], vec!["gone".to_string()], vec!["renamed".to_string()]));
+        assert_eq!(report.unparseable[0].id, "broken");
+
+        let rebuilt = rebuild_manifest(manifest, &documents);
+        assert_eq!(rebuilt.items.keys().collect::<Vec<_>>(), ["broken", "new", "ok", "renamed"]);
+        assert_eq!((rebuilt.items["renamed"].title.as_str(), rebuilt.items["renamed"].tags.len()), ("New", 1));
+        assert_eq!(rebuilt.items["new"].created_at.as_deref(), Some("2026-03-01"));
+        let after = inspect_library(&rebuilt, &documents);
+        assert!(after.orphans.is_empty() && after.missing.is_empty() && after.stale.is_empty() && after.unparseable.len() == 1);
+    }
+
+    #[test]
+    fn moves_library_and_rolls_back_on_failure() {
+        let root = std::env::temp_dir().join(format!("cvgen-move-{}", uuid::Uuid::new_v4()));
+        let library = root.join("library");
+        std::fs::create_dir_all(&library).unwrap();
+        std::fs::write(library.join("cv1.json"), "{}").unwrap();
+        let keep = |target: &std::path::Path| Ok::<_, AppError>(target.to_path_buf());
+
+        let inside = |target| transfer_library(&library, target, keep).unwrap_err().key;
+        assert_eq!(inside(library.join("sub")), "errors.library.targetInside");
+        assert_eq!(inside(root.join("other/../library/sub")), "errors.library.targetInside");
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&library, root.join("link")).unwrap();
+            assert_eq!(inside(root.join("link/sub")), "errors.library.targetInside");
+        }
+        assert!(transfer_library(&library, root.join("./library"), keep).unwrap().is_none());
+
+        let moved = root.join("moved");
+        assert_eq!(transfer_library(&library, moved.clone(), keep).unwrap(), Some(moved.clone()));
+        assert!(moved.join("cv1.json").exists() && !library.exists());
+
+        let refused = transfer_library(&moved, root.join("again"), |_| Err::<(), _>(AppError::new("errors.settings.relativePath")));
+        assert_eq!(refused.unwrap_err().key, "errors.settings.relativePath");
+        assert!(moved.join("cv1.json").exists() && !root.join("again").exists());
+        std::fs::remove_dir_all(root).unwrap();
     }
 }
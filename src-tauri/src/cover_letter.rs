@@ -0,0 +1,190 @@
+//! Conversion de la lettre structurée (`CoverLetter`) vers et depuis le texte brut.
+//!
+//! La forme texte enchaîne les blocs séparés par une ligne vide : expéditeur, destinataire,
+//! date, objet, formule d'appel, paragraphes, puis formule de politesse et signature.
+
+use serde::{Deserialize, Deserializer};
+
+use crate::models::CoverLetter;
+
+/// Au-delà, une ligne isolée n'est pas prise pour une formule d'appel ou de politesse.
+const MAX_FORMULA_CHARS: usize = 60;
+
+impl CoverLetter {
+    pub fn to_text(&self) -> String {
+        let mut blocks: Vec<String> = Vec::new();
+        for lines in [&self.sender, &self.recipient] {
+            if !lines.is_empty() {
+                blocks.push(lines.join("\n"));
+            }
+        }
+        blocks.extend(self.date.iter().chain(&self.subject).cloned());
+        blocks.push(self.salutation.clone());
+        blocks.extend(self.paragraphs.iter().cloned());
+        blocks.push(
+            [self.closing.as_str(), self.signature.as_str()]
+                .into_iter()
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+        blocks.retain(|block| !block.trim().is_empty());
+        blocks.join("\n\n")
+    }
+
+    /// Découpe une lettre en texte brut. L'en-tête n'est reconnu que s'il précède une
+    /// formule d'appel (ligne seule terminée par une virgule) ; sinon tout est paragraphe.
+    pub fn from_text(text: &str) -> Self {
+        let mut blocks: Vec<Vec<&str>> = Vec::new();
+        let mut current: Vec<&str> = Vec::new();
+        for line in text.lines().map(str::trim_end) {
+            if line.trim().is_empty() {
+                if !current.is_empty() {
+                    blocks.push(std::mem::take(&mut current));
+                }
+            } else {
+                current.push(line);
+            }
+        }
+        if !current.is_empty() {
+            blocks.push(current);
+        }
+
+        let mut letter = Self::default();
+        let is_formula = |block: &[&str]| {
+            block.len() == 1
+                && block[0].trim_end().ends_with(',')
+                && block[0].chars().count() <= MAX_FORMULA_CHARS
+        };
+        let mut body = match blocks.iter().position(|block| is_formula(block)) {
+            Some(index) => {
+                let mut header = blocks.drain(..=index).collect::<Vec<_>>();
+                letter.salutation = header.pop().unwrap_or_default()[0].to_string();
+                letter.read_header(header);
+                blocks
+            }
+            None => blocks,
+        };
+
+        // « Cordialement,\nNom » : formule de politesse puis signature.
+        if let Some(last) = body.last() {
+            let short = last
+                .iter()
+                .all(|line| line.chars().count() <= MAX_FORMULA_CHARS);
+            if last.len() >= 2 && short {
+                let (signature, closing) = last.split_last().expect("au moins deux lignes");
+                letter.closing = closing.join("\n");
+                letter.signature = signature.to_string();
+                body.pop();
+            }
+        }
+        letter.paragraphs = body.into_iter().map(|block| block.join("\n")).collect();
+        letter
+    }
+
+    fn read_header(&mut self, header: Vec<Vec<&str>>) {
+        for block in header {
+            // Date et objet tiennent sur une ligne ; les coordonnées rarement.
+            let line = (block.len() == 1).then(|| block[0].to_string());
+            match line {
+                Some(line) if self.date.is_none() && looks_like_date(&line) => {
+                    self.date = Some(line)
+                }
+                Some(line)
+                    if self.subject.is_none()
+                        && (self.date.is_some() || !self.recipient.is_empty()) =>
+                {
+                    self.subject = Some(line)
+                }
+                _ if self.sender.is_empty() => self.sender = to_lines(block),
+                _ if self.recipient.is_empty() => self.recipient = to_lines(block),
+                _ => self.paragraphs.push(block.join("\n")),
+            }
+        }
+    }
+
+    /// Applique `transform` à chaque texte de la lettre (masquage des données personnelles).
+    pub fn map_text(&self, transform: impl Fn(&str) -> String) -> Self {
+        let lines = |lines: &[String]| lines.iter().map(|line| transform(line)).collect();
+        Self {
+            sender: lines(&self.sender),
+            recipient: lines(&self.recipient),
+            date: self.date.as_deref().map(&transform),
+            subject: self.subject.as_deref().map(&transform),
+            salutation: transform(&self.salutation),
+            paragraphs: lines(&self.paragraphs),
+            closing: transform(&self.closing),
+            signature: transform(&self.signature),
+        }
+    }
+}
+
+fn looks_like_date(line: &str) -> bool {
+    line.chars().count() <= 40 && line.chars().any(|c| c.is_ascii_digit())
+}
+
+fn to_lines(block: Vec<&str>) -> Vec<String> {
+    block.into_iter().map(str::to_string).collect()
+}
+
+/// Accepte la lettre structurée ou l'ancienne forme texte de `storage.json`.
+pub fn structured_or_text<'de, D>(deserializer: D) -> Result<CoverLetter, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Text(String),
+        Structured(CoverLetter),
+    }
+
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::Text(text) => CoverLetter::from_text(&text),
+        Stored::Structured(letter) => letter,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{AdaptationResult, CoverLetter};
+
+    #[test]
+    fn reads_legacy_text_letters() {
+        let text = "Madame, Monsieur,\n\nJe vous propose ma candidature.\n\n\
+                    Je maîtrise Rust.\n\nCordialement,\nÉlise Martin";
+        let stored = serde_json::json!({
+            "adaptedResume": "",
+            "adaptedCoverLetter": text,
+            "highlights": []
+        });
+        let adaptation: AdaptationResult = serde_json::from_value(stored).unwrap();
+        let letter = &adaptation.adapted_cover_letter;
+
+        assert_eq!(letter.salutation, "Madame, Monsieur,");
+        assert_eq!(letter.paragraphs.len(), 2);
+        assert_eq!(letter.closing, "Cordialement,");
+        assert_eq!(letter.signature, "Élise Martin");
+        assert_eq!(letter.to_text(), text);
+
+        let saved = serde_json::to_value(&adaptation).unwrap();
+        assert_eq!(saved["adaptedCoverLetter"]["signature"], "Élise Martin");
+        let reloaded: AdaptationResult = serde_json::from_value(saved).unwrap();
+        assert_eq!(&reloaded.adapted_cover_letter, letter);
+    }
+
+    #[test]
+    fn text_round_trip_keeps_header() {
+        let letter = CoverLetter {
+            sender: vec!["Élise Martin".into(), "elise@example.com".into()],
+            recipient: vec!["Acme".into(), "Lyon".into()],
+            date: Some("19/10/2026".into()),
+            subject: Some("Candidature au poste Ingénieure Rust".into()),
+            salutation: "Madame, Monsieur,".into(),
+            paragraphs: vec!["Premier paragraphe.".into(), "Second.".into()],
+            closing: "Cordialement,".into(),
+            signature: "Élise Martin".into(),
+        };
+        assert_eq!(CoverLetter::from_text(&letter.to_text()), letter);
+    }
+}
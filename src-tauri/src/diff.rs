@@ -0,0 +1,645 @@
+//! Différences mot à mot entre le texte saisi par le candidat (résumé, réalisations) et le CV
+//! adapté.
+//!
+//! Les mots sont découpés selon les frontières Unicode (UAX #29). Chaque surlignage garde ses
+//! positions en caractères (`start` / `end`, comme le moteur Python) et reçoit aussi ses
+//! positions en octets UTF-8 et en unités UTF-16, ces dernières servant à l'interface.
+
+use std::{
+    collections::HashSet,
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use similar::{capture_diff_slices_deadline, Algorithm, DiffOp};
+use unicode_segmentation::UnicodeSegmentation;
+use uuid::Uuid;
+
+use crate::models::{CandidateProfile, HighlightSpan, HighlightType, OffsetRange};
+
+/// Recouvrement minimal (coefficient de Dice) pour rapprocher une ligne du CV d'un texte source.
+const MIN_SIMILARITY: f64 = 0.5;
+const BULLETS: &[&str] = &["• ", "- ", "* ", "– "];
+/// Au-delà, l'alignement se contente d'une différence moins fine (textes très divergents).
+const ALIGN_DEADLINE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    start: usize,
+    end: usize,
+    text: &'a str,
+}
+
+/// Jetons contigus couvrant tout le texte, espaces compris.
+fn all_tokens(text: &str) -> Vec<Token<'_>> {
+    text.split_word_bound_indices()
+        .map(|(start, word)| Token {
+            start,
+            end: start + word.len(),
+            text: word,
+        })
+        .collect()
+}
+
+/// Mots et ponctuation ; les espaces ne comptent pas dans la comparaison.
+fn tokens(text: &str) -> Vec<Token<'_>> {
+    all_tokens(text)
+        .into_iter()
+        .filter(|token| !token.text.trim().is_empty())
+        .collect()
+}
+
+fn word_set(text: &str) -> HashSet<String> {
+    text.unicode_words().map(str::to_lowercase).collect()
+}
+
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+/// Changement entre deux textes, en octets UTF-8 dans le texte adapté.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordChange {
+    /// Mots ajoutés : `adapted[start..end]`.
+    Added { start: usize, end: usize },
+    /// Mots supprimés, situés juste avant l'octet `at` du texte adapté.
+    Removed { at: usize, text: String },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Keep(usize),
+    Insert(usize),
+    Delete(usize),
+}
+
+/// Alignement de Myers, en espace linéaire (un CV entier ne tient pas dans une table
+/// `n × m`) ; `Keep` et `Insert` portent l'indice du jeton dans `after`, `Delete` celui dans
+/// `before`.
+fn align(before: &[Token<'_>], after: &[Token<'_>]) -> Vec<Step> {
+    let old: Vec<&str> = before.iter().map(|token| token.text).collect();
+    let new: Vec<&str> = after.iter().map(|token| token.text).collect();
+    let deadline = Instant::now() + ALIGN_DEADLINE;
+
+    let mut steps = Vec::with_capacity(old.len().max(new.len()));
+    for op in capture_diff_slices_deadline(Algorithm::Myers, &old, &new, Some(deadline)) {
+        match op {
+            DiffOp::Equal { new_index, len, .. } => {
+                steps.extend((new_index..new_index + len).map(Step::Keep));
+            }
+            DiffOp::Delete {
+                old_index, old_len, ..
+            } => steps.extend((old_index..old_index + old_len).map(Step::Delete)),
+            DiffOp::Insert {
+                new_index, new_len, ..
+            } => steps.extend((new_index..new_index + new_len).map(Step::Insert)),
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } => {
+                steps.extend((new_index..new_index + new_len).map(Step::Insert));
+                steps.extend((old_index..old_index + old_len).map(Step::Delete));
+            }
+        }
+    }
+    steps
+}
+
+/// Étend une plage d'octets au jeton suivant.
+fn extend(range: Option<(usize, usize)>, token: Token<'_>) -> Option<(usize, usize)> {
+    Some(range.map_or((token.start, token.end), |(start, _)| (start, token.end)))
+}
+
+/// Alignement des deux suites de mots, puis regroupement des mots ajoutés ou supprimés
+/// consécutifs.
+pub fn diff_words(original: &str, adapted: &str) -> Vec<WordChange> {
+    let before = tokens(original);
+    let after = tokens(adapted);
+
+    let mut changes = Vec::new();
+    let mut removed: Option<(usize, usize)> = None;
+    let mut added: Option<(usize, usize)> = None;
+    // Position dans le texte adapté juste après le dernier mot conservé ou ajouté.
+    let mut cursor = 0;
+    let mut flush = |removed: &mut Option<(usize, usize)>,
+                     added: &mut Option<(usize, usize)>,
+                     cursor: usize| {
+        if let Some((start, end)) = removed.take() {
+            let at = added.map_or(cursor, |(start, _)| start);
+            changes.push(WordChange::Removed {
+                at,
+                text: original[start..end].to_string(),
+            });
+        }
+        if let Some((start, end)) = added.take() {
+            changes.push(WordChange::Added { start, end });
+        }
+    };
+
+    for step in align(&before, &after) {
+        match step {
+            Step::Keep(j) => {
+                flush(&mut removed, &mut added, cursor);
+                cursor = after[j].end;
+            }
+            Step::Insert(j) => added = extend(added, after[j]),
+            Step::Delete(i) => removed = extend(removed, before[i]),
+        }
+    }
+    flush(&mut removed, &mut added, cursor);
+    changes
+}
+
+/// Portion remplacée lors d'une retouche, en indices de caractères.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub old: Range<usize>,
+    pub new: Range<usize>,
+}
+
+/// Retouches entre deux versions d'un texte, espaces compris, triées et disjointes.
+pub fn text_edits(before: &str, after: &str) -> Vec<TextEdit> {
+    let old_tokens = all_tokens(before);
+    let new_tokens = all_tokens(after);
+    // Le début et la fin, presque toujours inchangés, n'entrent pas dans l'alignement.
+    let prefix = old_tokens
+        .iter()
+        .zip(&new_tokens)
+        .take_while(|(a, b)| a.text == b.text)
+        .count();
+    let suffix = old_tokens[prefix..]
+        .iter()
+        .rev()
+        .zip(new_tokens[prefix..].iter().rev())
+        .take_while(|(a, b)| a.text == b.text)
+        .count();
+    let old_middle = &old_tokens[prefix..old_tokens.len() - suffix];
+    let new_middle = &new_tokens[prefix..new_tokens.len() - suffix];
+
+    // Retouches en indices de jetons : (début avant, début après) puis position courante.
+    let mut hunks = Vec::new();
+    let mut open: Option<(usize, usize)> = None;
+    let (mut i, mut j) = (prefix, prefix);
+    for step in align(old_middle, new_middle) {
+        match step {
+            Step::Keep(_) => {
+                hunks.extend(open.take().map(|start| (start, (i, j))));
+                i += 1;
+                j += 1;
+            }
+            Step::Insert(_) => {
+                open.get_or_insert((i, j));
+                j += 1;
+            }
+            Step::Delete(_) => {
+                open.get_or_insert((i, j));
+                i += 1;
+            }
+        }
+    }
+    hunks.extend(open.map(|start| (start, (i, j))));
+
+    let position = |tokens: &[Token<'_>], text: &str, index: usize| {
+        let byte = tokens.get(index).map_or(text.len(), |token| token.start);
+        text[..byte].chars().count()
+    };
+    hunks
+        .into_iter()
+        .map(|((old_start, new_start), (old_end, new_end))| TextEdit {
+            old: position(&old_tokens, before, old_start)..position(&old_tokens, before, old_end),
+            new: position(&new_tokens, after, new_start)..position(&new_tokens, after, new_end),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Start,
+    End,
+}
+
+/// Position d'un indice de caractère après retouches. Un début tombant dans une portion
+/// retouchée passe après elle, une fin passe avant : la plage ne garde que le texte d'origine.
+fn rebase_offset(offset: usize, edits: &[TextEdit], side: Side) -> usize {
+    let (mut old_end, mut new_end) = (0, 0);
+    for edit in edits {
+        let before = match side {
+            Side::Start => offset < edit.old.start,
+            Side::End => offset <= edit.old.start,
+        };
+        if before {
+            break;
+        }
+        let inside = match side {
+            Side::Start => offset < edit.old.end || edit.old.is_empty() && offset == edit.old.start,
+            Side::End => offset <= edit.old.end,
+        };
+        if inside {
+            return match side {
+                Side::Start => edit.new.end,
+                Side::End => edit.new.start,
+            };
+        }
+        (old_end, new_end) = (edit.old.end, edit.new.end);
+    }
+    offset - old_end + new_end
+}
+
+/// Reporte les surlignages sur le texte retouché et marque les portions saisies par
+/// l'utilisateur (`userEdit`). Un surlignage entièrement réécrit disparaît.
+pub fn rebase_highlights(
+    highlights: &[HighlightSpan],
+    before: &str,
+    after: &str,
+) -> Vec<HighlightSpan> {
+    let edits = text_edits(before, after);
+    let mut spans: Vec<HighlightSpan> = highlights
+        .iter()
+        .filter_map(|span| {
+            let start = rebase_offset(span.start, &edits, Side::Start);
+            if span.start == span.end {
+                return Some(HighlightSpan {
+                    start,
+                    end: start,
+                    ..span.clone()
+                });
+            }
+            let end = rebase_offset(span.end, &edits, Side::End);
+            (end > start).then(|| HighlightSpan {
+                start,
+                end,
+                ..span.clone()
+            })
+        })
+        .collect();
+
+    for edit in edits.iter().filter(|edit| !edit.new.is_empty()) {
+        let inserted: String = after
+            .chars()
+            .skip(edit.new.start)
+            .take(edit.new.len())
+            .collect();
+        let text = inserted.trim();
+        if text.is_empty() {
+            continue;
+        }
+        // Les espaces autour du passage saisi ne sont pas surlignés.
+        let start = edit.new.start + inserted.chars().take_while(|c| c.is_whitespace()).count();
+        spans.push(HighlightSpan {
+            id: format!("edit-{}", Uuid::new_v4()),
+            highlight_type: HighlightType::UserEdit,
+            start,
+            end: start + text.chars().count(),
+            utf8: None,
+            utf16: None,
+            removed_text: None,
+        });
+    }
+    spans.sort_by_key(|span| span.start);
+    fill_offsets(after, &mut spans);
+    spans
+}
+
+/// Texte régénéré `after` où les passages saisis par l'utilisateur dans `before` (surlignages
+/// `userEdit`) sont conservés : une portion réécrite qui en touche un garde le texte de
+/// `before`.
+pub fn keep_user_edits(before: &str, highlights: &[HighlightSpan], after: &str) -> String {
+    let protected: Vec<(usize, usize)> = highlights
+        .iter()
+        .filter(|span| matches!(span.highlight_type, HighlightType::UserEdit))
+        .map(|span| (span.start, span.end))
+        .collect();
+    if protected.is_empty() {
+        return after.to_owned();
+    }
+    let old: Vec<char> = before.chars().collect();
+    let new: Vec<char> = after.chars().collect();
+    let mut merged = String::with_capacity(after.len());
+    let mut cursor = 0;
+    for edit in text_edits(before, after) {
+        merged.extend(&new[cursor..edit.new.start]);
+        // Une insertion compte si elle tombe dans un passage saisi.
+        let end = edit.old.end.max(edit.old.start + 1);
+        let kept = protected
+            .iter()
+            .any(|&(start, stop)| start < end && edit.old.start < stop);
+        if kept {
+            merged.extend(&old[edit.old.clone()]);
+        } else {
+            merged.extend(&new[edit.new.clone()]);
+        }
+        cursor = edit.new.end;
+    }
+    merged.extend(&new[cursor..]);
+    merged
+}
+
+/// Surlignages d'ajout et de suppression du CV adapté par rapport au résumé et aux
+/// réalisations du profil. Chaque ligne du CV est comparée au texte source le plus proche ;
+/// les lignes sans équivalent (titres, listes de compétences) ne sont pas surlignées.
+pub fn word_highlights(profile: &CandidateProfile, adapted: &str) -> Vec<HighlightSpan> {
+    let sources: Vec<(&str, HashSet<String>)> = profile
+        .summary
+        .iter()
+        .flat_map(|summary| summary.lines())
+        .chain(
+            profile
+                .experiences
+                .iter()
+                .flat_map(|experience| experience.achievements.iter().map(String::as_str)),
+        )
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(|text| (text, word_set(text)))
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut line_start = 0;
+    for line in adapted.split_inclusive('\n') {
+        let (offset, content) = strip_bullet(line.trim_end_matches(['\n', '\r']));
+        let words = word_set(content);
+        let best = sources
+            .iter()
+            .map(|(text, set)| (*text, similarity(&words, set)))
+            .filter(|(_, score)| *score >= MIN_SIMILARITY)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((source, _)) = best {
+            let base = line_start + offset;
+            changes.extend(
+                diff_words(source, content)
+                    .into_iter()
+                    .map(|change| match change {
+                        WordChange::Added { start, end } => WordChange::Added {
+                            start: base + start,
+                            end: base + end,
+                        },
+                        WordChange::Removed { at, text } => WordChange::Removed {
+                            at: base + at,
+                            text,
+                        },
+                    }),
+            );
+        }
+        line_start += line.len();
+    }
+
+    let mut spans: Vec<HighlightSpan> = changes
+        .into_iter()
+        .enumerate()
+        .map(|(index, change)| {
+            let (highlight_type, start, end, removed_text) = match change {
+                WordChange::Added { start, end } => (HighlightType::Addition, start, end, None),
+                WordChange::Removed { at, text } => (HighlightType::Removal, at, at, Some(text)),
+            };
+            HighlightSpan {
+                id: format!("diff-{index}"),
+                highlight_type,
+                start: adapted[..start].chars().count(),
+                end: adapted[..end].chars().count(),
+                utf8: None,
+                utf16: None,
+                removed_text,
+            }
+        })
+        .collect();
+    fill_offsets(adapted, &mut spans);
+    spans
+}
+
+/// Retire l'indentation et la puce éventuelle ; renvoie le décalage en octets du contenu.
+fn strip_bullet(line: &str) -> (usize, &str) {
+    let trimmed = line.trim_start();
+    let content = BULLETS
+        .iter()
+        .find_map(|bullet| trimmed.strip_prefix(bullet))
+        .unwrap_or(trimmed);
+    (line.len() - content.len(), content)
+}
+
+/// Surlignages du moteur complétés par ceux calculés ; un ajout ou une suppression calculé
+/// qui touche un ajout ou une suppression du moteur est écarté.
+pub fn merge_highlights(
+    mut engine: Vec<HighlightSpan>,
+    computed: Vec<HighlightSpan>,
+) -> Vec<HighlightSpan> {
+    let reported: Vec<(usize, usize)> = engine
+        .iter()
+        .filter(|span| {
+            matches!(
+                span.highlight_type,
+                HighlightType::Addition | HighlightType::Removal
+            )
+        })
+        .map(|span| (span.start, span.end))
+        .collect();
+    engine.extend(computed.into_iter().filter(|span| {
+        !reported
+            .iter()
+            .any(|&(start, end)| span.start <= end && start <= span.end)
+    }));
+    engine.sort_by_key(|span| span.start);
+    engine
+}
+
+/// Renseigne `utf8` et `utf16` à partir des positions en caractères de chaque surlignage.
+pub fn fill_offsets(text: &str, spans: &mut [HighlightSpan]) {
+    // Position (octets, unités UTF-16) de chaque frontière de caractère.
+    let mut boundaries = Vec::with_capacity(text.len() + 1);
+    let mut utf16 = 0;
+    for (byte, character) in text.char_indices() {
+        boundaries.push((byte, utf16));
+        utf16 += character.len_utf16();
+    }
+    boundaries.push((text.len(), utf16));
+
+    let at = |index: usize| boundaries[index.min(boundaries.len() - 1)];
+    for span in spans {
+        let (start, end) = (at(span.start), at(span.end));
+        span.utf8 = Some(OffsetRange {
+            start: start.0,
+            end: end.0,
+        });
+        span.utf16 = Some(OffsetRange {
+            start: start.1,
+            end: end.1,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        diff_words, fill_offsets, keep_user_edits, rebase_highlights, text_edits, word_highlights,
+        WordChange,
+    };
+    use crate::models::{CandidateProfile, HighlightSpan, HighlightType, OffsetRange};
+
+    #[test]
+    fn groups_added_and_removed_words() {
+        let original = "Migration des services vers Kubernetes";
+        let adapted = "Migration de 40 services critiques vers Kubernetes";
+        let changes = diff_words(original, adapted);
+        let at = adapted.find("de 40").unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                WordChange::Removed {
+                    at,
+                    text: "des".into()
+                },
+                WordChange::Added {
+                    start: at,
+                    end: at + "de 40".len()
+                },
+                WordChange::Added {
+                    start: adapted.find("critiques").unwrap(),
+                    end: adapted.find(" vers").unwrap()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn offsets_follow_multibyte_text() {
+        let mut profile: CandidateProfile = serde_json::from_value(serde_json::json!({
+            "id": "p1",
+            "fullName": "Zoé",
+            "email": null,
+            "phone": null,
+            "summary": "Ingénieure 🚀 passionnée",
+            "experiences": [],
+            "skills": [],
+            "education": [],
+            "projects": [],
+            "languages": []
+        }))
+        .unwrap();
+        let adapted = "Zoé\n\nIngénieure 🚀 très passionnée";
+        let spans = word_highlights(&profile, adapted);
+        assert_eq!(spans.len(), 1);
+        let span = &spans[0];
+        assert!(matches!(span.highlight_type, HighlightType::Addition));
+        assert_eq!(
+            &adapted[span.utf8.unwrap().start..span.utf8.unwrap().end],
+            "très"
+        );
+        // La fusée compte pour un caractère mais deux unités UTF-16.
+        let before: String = adapted.chars().take(span.start).collect();
+        assert_eq!(span.utf16.unwrap().start, before.encode_utf16().count());
+        assert_eq!(span.utf16.unwrap().start, span.start + 1);
+
+        profile.summary = Some("Texte sans rapport".into());
+        assert!(word_highlights(&profile, adapted).is_empty());
+
+        let mut removal = [HighlightSpan {
+            id: "r".into(),
+            highlight_type: HighlightType::Removal,
+            start: 4,
+            end: 4,
+            utf8: None,
+            utf16: None,
+            removed_text: Some("x".into()),
+        }];
+        fill_offsets("🚀🚀🚀🚀", &mut removal);
+        assert_eq!(removal[0].utf16, Some(OffsetRange { start: 8, end: 8 }));
+        assert_eq!(removal[0].utf8, Some(OffsetRange { start: 16, end: 16 }));
+    }
+
+    #[test]
+    fn rebases_highlights_across_user_edits() {
+        let before = "Expert Rust et Go, équipe de 5";
+        let after = "Expert Rust confirmé et Python, équipe de 5";
+        let span = |id: &str, word: &str| {
+            let start = before[..before.find(word).unwrap()].chars().count();
+            HighlightSpan {
+                id: id.into(),
+                highlight_type: HighlightType::Emphasis,
+                start,
+                end: start + word.chars().count(),
+                utf8: None,
+                utf16: None,
+                removed_text: None,
+            }
+        };
+        let highlights = [
+            span("rust", "Rust"),
+            span("go", "Go"),
+            span("team", "équipe"),
+        ];
+
+        let rebased = rebase_highlights(&highlights, before, after);
+        let text = |span: &HighlightSpan| -> String {
+            after
+                .chars()
+                .skip(span.start)
+                .take(span.end - span.start)
+                .collect()
+        };
+        let ids: Vec<&str> = rebased.iter().map(|span| span.id.as_str()).collect();
+        assert_eq!(ids.len(), 4);
+        assert_eq!(ids[0], "rust");
+        assert_eq!(text(&rebased[0]), "Rust");
+        assert!(matches!(rebased[1].highlight_type, HighlightType::UserEdit));
+        assert_eq!(text(&rebased[1]), "confirmé");
+        assert!(matches!(rebased[2].highlight_type, HighlightType::UserEdit));
+        assert_eq!(text(&rebased[2]), "Python");
+        assert_eq!(ids[3], "team");
+        assert_eq!(text(&rebased[3]), "équipe");
+        let utf8 = rebased[3].utf8.unwrap();
+        assert_eq!(&after[utf8.start..utf8.end], "équipe");
+
+        assert!(text_edits(after, after).is_empty());
+    }
+
+    #[test]
+    fn regeneration_keeps_user_passages() {
+        let edited = "Expert Rust confirmé, équipe de 5";
+        let start = edited.find("confirmé").unwrap();
+        let highlights = [HighlightSpan {
+            id: "edit-1".into(),
+            highlight_type: HighlightType::UserEdit,
+            start,
+            end: start + "confirmé".chars().count(),
+            utf8: None,
+            utf16: None,
+            removed_text: None,
+        }];
+        let regenerated = "Expert Rust senior, équipe de 8";
+
+        assert_eq!(
+            keep_user_edits(edited, &highlights, regenerated),
+            "Expert Rust confirmé, équipe de 8"
+        );
+        assert_eq!(keep_user_edits(edited, &[], regenerated), regenerated);
+    }
+
+    #[test]
+    fn diffs_long_texts() {
+        // 2 × 30 000 mots : une table de plus longue sous-séquence commune dépasserait 7 Go.
+        let words: Vec<String> = (0..30_000).map(|index| format!("mot{index}")).collect();
+        let original = words.join(" ");
+        let adapted = original.replace("mot15000 ", "terme ");
+
+        let changes = diff_words(&original, &adapted);
+        let at = adapted.find("terme").unwrap();
+        assert_eq!(
+            changes,
+            [
+                WordChange::Removed {
+                    at,
+                    text: "mot15000".into()
+                },
+                WordChange::Added {
+                    start: at,
+                    end: at + "terme".len()
+                },
+            ]
+        );
+        assert_eq!(text_edits(&original, &adapted).len(), 1);
+    }
+}
@@ -1,32 +1,99 @@
+use serde::Deserialize;
 use serde_json::json;
 use tauri::{command, AppHandle, State};
+use tracing::instrument;
 
 use crate::{
-    models::{AdaptationResult, CandidateProfile, ExportPayload},
-    python_bridge::call_python,
-    state::{get_adaptation, try_get_profile, SharedState},
+    anonymize::{AnonymizeOptions, Redactor},
+    locale::{section_labels, Language},
+    messages::AppError,
+    models::{AdaptationResult, CandidateProfile, ExportFormat, ExportPayload},
+    python_bridge::PythonBridge,
+    settings::{current_settings, Settings},
+    state::{adaptation_key, get_adaptation, SharedState},
+    variants::resolve_profile,
+    vault::VaultState,
 };
 
-#[command]
-pub async fn export_documents(
-    profile_id: String,
-    format: String,
-    state: State<'_, SharedState>,
-    app: AppHandle,
-) -> Result<ExportPayload, String> {
-    let profile: CandidateProfile =
-        try_get_profile(&state, &profile_id).ok_or_else(|| "Profil introuvable".to_string())?;
-    let adaptation: AdaptationResult = get_adaptation(&state, &profile_id)
-        .ok_or_else(|| "Aucune adaptation disponible pour export".to_string())?;
+/// Paramètres d'un export, communs à la commande Tauri, au CLI et à l'API HTTP.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRequest {
+    pub profile_id: String,
+    pub format: Option<ExportFormat>,
+    pub variant_id: Option<String>,
+    pub language: Option<Language>,
+    pub anonymize: Option<AnonymizeOptions>,
+}
+
+/// Génère CV et lettre à partir de la dernière adaptation du profil (ou de sa variante).
+pub async fn export(
+    bridge: &PythonBridge,
+    state: &SharedState,
+    settings: &Settings,
+    request: ExportRequest,
+) -> Result<ExportPayload, AppError> {
+    let ExportRequest {
+        profile_id,
+        format,
+        variant_id,
+        language,
+        anonymize,
+    } = request;
+    let key = adaptation_key(&profile_id, variant_id.as_deref());
+    let mut adaptation: AdaptationResult =
+        get_adaptation(state, &key).ok_or_else(|| AppError::new("errors.noAdaptation"))?;
+    // Les exporteurs mettent en page l'arbre, relu ici pour les anciennes adaptations.
+    adaptation.resume_document = Some(adaptation.document());
+    let format = format.unwrap_or(settings.export_format);
+    let language = language
+        .or(adaptation.language)
+        .unwrap_or(settings.default_target_language);
+    let mut profile: CandidateProfile =
+        resolve_profile(state, &profile_id, variant_id.as_deref())?.localized(language);
+    if let Some(options) = anonymize {
+        let redactor = Redactor::new(&profile, options, language);
+        adaptation = redactor.adaptation(&adaptation);
+        profile = redactor.profile(&profile);
+    }
 
     let payload = json!({
         "profile": profile,
         "adaptation": adaptation,
-        "format": format
+        "format": format,
+        "output_dir": settings.export_dir,
+        "language": language,
+        "labels": section_labels(language)
     });
-    let result: ExportPayload = call_python(&app, "export_documents", payload)
+    let result: ExportPayload = bridge
+        .call("export_documents", payload)
         .await
-        .map_err(|error| error.to_string())?;
+        .map_err(AppError::python)?;
 
     Ok(result)
 }
+
+#[command]
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(state, vault, app))]
+pub async fn export_documents(
+    profile_id: String,
+    format: Option<ExportFormat>,
+    variant_id: Option<String>,
+    language: Option<Language>,
+    anonymize: Option<AnonymizeOptions>,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+    app: AppHandle,
+) -> Result<ExportPayload, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let bridge = PythonBridge::from_app(&app).map_err(AppError::python)?;
+    let request = ExportRequest {
+        profile_id,
+        format,
+        variant_id,
+        language,
+        anonymize,
+    };
+    export(&bridge, &state, &current_settings(&app), request).await
+}
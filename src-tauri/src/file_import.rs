@@ -1,33 +1,88 @@
+use std::path::Path;
+
 use serde_json::json;
 use tauri::{command, AppHandle, State};
+use tracing::{info, instrument};
 
 use crate::{
-    models::{ExtractionPayload, JobOffer},
-    python_bridge::call_python,
+    locale::detect_language,
+    messages::AppError,
+    models::{ExtractedText, ExtractionPayload, JobOffer},
+    python_bridge::PythonBridge,
     state::{store_offer, store_profile, SharedState},
+    vault::VaultState,
 };
 
+/// Extrait un profil d'un fichier (PDF, DOCX, image) et l'enregistre.
+pub async fn import_profile(
+    bridge: &PythonBridge,
+    state: &SharedState,
+    file_path: &str,
+) -> Result<ExtractionPayload, AppError> {
+    let result = extract_profile(bridge, file_path).await?;
+    store_profile(state, result.profile.clone());
+    Ok(result)
+}
+
+/// Lit le profil d'un CV sans l'enregistrer.
+pub async fn extract_profile(
+    bridge: &PythonBridge,
+    file_path: &str,
+) -> Result<ExtractionPayload, AppError> {
+    // Le nom du fichier contient souvent celui du candidat : seule l'extension est journalisée.
+    info!(
+        extension = ?Path::new(file_path).extension(),
+        "Import d'un CV"
+    );
+    let payload = json!({ "file_path": file_path });
+    bridge
+        .call("import_cv", payload)
+        .await
+        .map_err(AppError::python)
+}
+
+/// Extrait le texte d'un document avec la même chaîne que `import_cv` (pdfminer, python-docx,
+/// OCR), sans en tirer de profil.
+pub async fn extract_document_text(
+    bridge: &PythonBridge,
+    file_path: &Path,
+) -> Result<ExtractedText, AppError> {
+    let payload = json!({ "file_path": file_path });
+    bridge
+        .call("extract_text", payload)
+        .await
+        .map_err(AppError::python)
+}
+
+/// Enregistre une offre telle quelle, en détectant sa langue si besoin.
+pub fn add_offer(state: &SharedState, mut offer: JobOffer) -> JobOffer {
+    if offer.language.is_none() {
+        offer.language = detect_language(&offer.description);
+    }
+    store_offer(state, offer.clone());
+    offer
+}
+
 #[command]
+#[instrument(skip_all)]
 pub async fn import_cv(
     file_path: String,
     state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
     app: AppHandle,
-) -> Result<ExtractionPayload, String> {
-    println!("[file_import] import_cv called with {file_path}");
-    let payload = json!({ "file_path": file_path });
-    let result: ExtractionPayload = call_python(&app, "import_cv", payload)
-        .await
-        .map_err(|error| error.to_string())?;
-
-    store_profile(&state, result.profile.clone());
-    Ok(result)
+) -> Result<ExtractionPayload, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let bridge = PythonBridge::from_app(&app).map_err(AppError::python)?;
+    import_profile(&bridge, &state, &file_path).await
 }
 
 #[command]
+#[instrument(skip_all, fields(offer_id = %offer.id))]
 pub async fn register_offer(
     offer: JobOffer,
     state: State<'_, SharedState>,
-) -> Result<JobOffer, String> {
-    store_offer(&state, offer.clone());
-    Ok(offer)
+    vault: State<'_, VaultState>,
+) -> Result<JobOffer, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    Ok(add_offer(&state, offer))
 }
@@ -0,0 +1,384 @@
+//! API HTTP locale pour les outils d'intégration (bookmarklet, scrapers d'offres).
+//!
+//! Le serveur n'écoute que sur `127.0.0.1` et exige `Authorization: Bearer <jeton>` sur toutes
+//! les routes sauf `/openapi.json`. Les routes s'appuient sur le même état que les commandes
+//! Tauri ; chaque ajout émet `external-data-changed` pour que l'interface se rafraîchisse.
+
+use std::{net::Ipv4Addr, sync::Arc};
+
+use axum::{
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use subtle::ConstantTimeEq;
+use tauri::{command, AppHandle, Manager};
+use tokio::sync::oneshot;
+use tower_http::cors::{Any, CorsLayer};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    ai_engine::{adapt, analyze, AdaptRequest, ConfiguredEngine},
+    exporter::{export, ExportRequest},
+    file_import::add_offer,
+    locale::Language,
+    messages::AppError,
+    models::{AdaptationResult, ExportPayload, JobOffer},
+    python_bridge::PythonBridge,
+    settings::{current_settings, HttpApiSettings, MIN_API_TOKEN_LEN},
+    state::SharedState,
+    vault::VaultState,
+};
+
+pub const EXTERNAL_CHANGE_EVENT: &str = "external-data-changed";
+
+const OPENAPI: &str = include_str!("../openapi.json");
+
+#[derive(Default)]
+pub struct HttpApiState(pub Mutex<Option<RunningServer>>);
+
+pub struct RunningServer {
+    port: u16,
+    /// Partagé avec le serveur : un nouveau jeton s'applique sans redémarrage.
+    token: Arc<RwLock<String>>,
+    shutdown: oneshot::Sender<()>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpApiStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+}
+
+#[derive(Clone)]
+struct ApiContext {
+    app: AppHandle,
+    token: Arc<RwLock<String>>,
+}
+
+/// Démarre, arrête ou reconfigure le serveur selon les préférences.
+pub fn apply_http_api(app: &AppHandle, settings: &HttpApiSettings) {
+    let state = app.state::<HttpApiState>();
+    let mut running = state.0.lock();
+    let wanted = match (settings.enabled, &settings.token) {
+        (true, Some(token)) if token.len() >= MIN_API_TOKEN_LEN => {
+            Some((settings.port, token.clone()))
+        }
+        (true, _) => {
+            warn!("API HTTP activée sans jeton valide : serveur non démarré");
+            None
+        }
+        (false, _) => None,
+    };
+
+    if let (Some(server), Some((port, token))) = (running.as_ref(), &wanted) {
+        if server.port == *port {
+            *server.token.write() = token.clone();
+            return;
+        }
+    }
+    if let Some(server) = running.take() {
+        let _ = server.shutdown.send(());
+        info!(port = server.port, "API HTTP arrêtée");
+    }
+
+    let Some((port, token)) = wanted else {
+        return;
+    };
+    let token = Arc::new(RwLock::new(token));
+    match start(app.clone(), port, token.clone()) {
+        Ok(shutdown) => {
+            info!(port, "API HTTP démarrée");
+            *running = Some(RunningServer {
+                port,
+                token,
+                shutdown,
+            });
+        }
+        Err(error) => error!(%error, port, "Impossible de démarrer l'API HTTP"),
+    }
+}
+
+fn start(
+    app: AppHandle,
+    port: u16,
+    token: Arc<RwLock<String>>,
+) -> std::io::Result<oneshot::Sender<()>> {
+    // Liaison synchrone : un port déjà pris est signalé tout de suite.
+    let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    listener.set_nonblocking(true)?;
+    let (shutdown, stopped) = oneshot::channel::<()>();
+    let router = router(ApiContext { app, token });
+
+    tauri::async_runtime::spawn(async move {
+        let serve = async {
+            let listener = tokio::net::TcpListener::from_std(listener)?;
+            axum::serve(listener, router)
+                .with_graceful_shutdown(async {
+                    let _ = stopped.await;
+                })
+                .await
+        };
+        if let Err(error) = serve.await {
+            error!(%error, "API HTTP interrompue");
+        }
+    });
+    Ok(shutdown)
+}
+
+fn router(context: ApiContext) -> Router {
+    // Le bookmarklet appelle l'API depuis la page de l'offre : CORS ouvert, le jeton protège.
+    let cors = CorsLayer::new()
+        .allow_origin(Any)
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+        .allow_private_network(true);
+
+    let routes = Router::new()
+        .route("/profiles", get(list_profiles))
+        .route("/offers", post(register_offer))
+        .route("/adaptations", post(create_adaptation))
+        .route("/exports", post(create_export));
+    authorize(routes, context.token.clone())
+        .route("/openapi.json", get(openapi))
+        .layer(cors)
+        .with_state(context)
+}
+
+/// Exige le jeton sur toutes les routes de `routes`.
+fn authorize<S: Clone + Send + Sync + 'static>(
+    routes: Router<S>,
+    token: Arc<RwLock<String>>,
+) -> Router<S> {
+    routes.route_layer(middleware::from_fn_with_state(token, require_token))
+}
+
+async fn require_token(
+    State(token): State<Arc<RwLock<String>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let authorized = provided.is_some_and(|provided| {
+        let expected = token.read();
+        // Un jeton vide ou trop court n'authentifie jamais, même comparé à lui-même.
+        expected.len() >= MIN_API_TOKEN_LEN
+            && bool::from(provided.as_bytes().ct_eq(expected.as_bytes()))
+    });
+    if !authorized {
+        return ApiError(AppError::new("errors.httpApi.unauthorized")).into_response();
+    }
+    next.run(request).await
+}
+
+/// Erreur de catalogue renvoyée en JSON, avec un statut HTTP dérivé de sa clé.
+struct ApiError(AppError);
+
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self.0.key {
+            "errors.httpApi.unauthorized" => StatusCode::UNAUTHORIZED,
+            "errors.vault.locked" => StatusCode::LOCKED,
+            "errors.adaptationEdited" => StatusCode::CONFLICT,
+            "errors.python" => StatusCode::BAD_GATEWAY,
+            key if key.starts_with("errors.llm.") => StatusCode::BAD_GATEWAY,
+            key if key.ends_with("NotFound") => StatusCode::NOT_FOUND,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        (status, Json(self.0)).into_response()
+    }
+}
+
+fn ensure_unlocked(app: &AppHandle) -> Result<(), AppError> {
+    app.state::<VaultState>().0.lock().ensure_unlocked()
+}
+
+fn notify(app: &AppHandle, change: Value) {
+    if let Err(error) = app.emit_all(EXTERNAL_CHANGE_EVENT, change) {
+        warn!(?error, "Impossible d'émettre {EXTERNAL_CHANGE_EVENT}");
+    }
+}
+
+async fn openapi() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI)
+}
+
+async fn list_profiles(State(context): State<ApiContext>) -> Result<Json<Value>, ApiError> {
+    ensure_unlocked(&context.app)?;
+    let state = context.app.state::<SharedState>();
+    let memory = state.0.lock();
+    let mut profiles: Vec<Value> = memory
+        .profiles
+        .values()
+        .map(|profile| {
+            let mut variants: Vec<Value> = memory
+                .variants
+                .values()
+                .filter(|variant| variant.profile_id == profile.id)
+                .map(|variant| json!({ "id": variant.id, "name": variant.name }))
+                .collect();
+            variants.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+            json!({ "id": profile.id, "fullName": profile.full_name, "variants": variants })
+        })
+        .collect();
+    profiles.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+    Ok(Json(Value::Array(profiles)))
+}
+
+/// Offre poussée par un outil externe ; l'identifiant est généré s'il manque.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OfferInput {
+    id: Option<String>,
+    title: String,
+    company: Option<String>,
+    description: String,
+    location: Option<String>,
+    keywords: Option<Vec<String>>,
+    language: Option<Language>,
+    /// Lance aussi l'analyse (mots-clés) avant de répondre.
+    #[serde(default)]
+    analyze: bool,
+}
+
+async fn register_offer(
+    State(context): State<ApiContext>,
+    Json(input): Json<OfferInput>,
+) -> Result<Json<JobOffer>, ApiError> {
+    ensure_unlocked(&context.app)?;
+    let state = context.app.state::<SharedState>();
+    let offer = JobOffer {
+        id: input.id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+        title: input.title,
+        company: input.company,
+        description: input.description,
+        location: input.location,
+        keywords: input.keywords,
+        language: input.language,
+        date_posted: None,
+        valid_through: None,
+        salary: None,
+        contact: None,
+    };
+    let offer = if input.analyze {
+        let bridge = PythonBridge::from_app(&context.app).map_err(AppError::python)?;
+        analyze(&bridge, &state, offer).await?
+    } else {
+        add_offer(&state, offer)
+    };
+
+    notify(&context.app, json!({ "kind": "offer", "id": offer.id }));
+    Ok(Json(offer))
+}
+
+async fn create_adaptation(
+    State(context): State<ApiContext>,
+    Json(request): Json<AdaptRequest>,
+) -> Result<Json<AdaptationResult>, ApiError> {
+    ensure_unlocked(&context.app)?;
+    let settings = current_settings(&context.app);
+    let engine = ConfiguredEngine::from_app(&context.app, &settings)?;
+    let change = json!({
+        "kind": "adaptation",
+        "profileId": request.profile_id,
+        "offerId": request.offer_id,
+        "variantId": request.variant_id
+    });
+    let state = context.app.state::<SharedState>();
+    let result = adapt(&engine, &state, &settings, request).await?;
+
+    notify(&context.app, change);
+    Ok(Json(result))
+}
+
+async fn create_export(
+    State(context): State<ApiContext>,
+    Json(request): Json<ExportRequest>,
+) -> Result<Json<ExportPayload>, ApiError> {
+    ensure_unlocked(&context.app)?;
+    let bridge = PythonBridge::from_app(&context.app).map_err(AppError::python)?;
+    let state = context.app.state::<SharedState>();
+    let payload = export(&bridge, &state, &current_settings(&context.app), request).await?;
+
+    notify(
+        &context.app,
+        json!({ "kind": "export", "resumePath": payload.resume_path }),
+    );
+    Ok(Json(payload))
+}
+
+#[command]
+pub fn http_api_status(state: tauri::State<'_, HttpApiState>) -> HttpApiStatus {
+    let running = state.0.lock();
+    HttpApiStatus {
+        running: running.is_some(),
+        port: running.as_ref().map(|server| server.port),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+        routing::get,
+        Router,
+    };
+    use parking_lot::RwLock;
+    use tower::ServiceExt;
+
+    use super::authorize;
+
+    async fn status(token: &str, authorization: Option<&str>) -> StatusCode {
+        let routes = Router::new().route("/profiles", get(|| async { "[]" }));
+        let app = authorize(routes, Arc::new(RwLock::new(token.to_string())));
+        let mut request = Request::builder().uri("/profiles");
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        response.status()
+    }
+
+    #[tokio::test]
+    async fn requires_a_matching_non_empty_token() {
+        let token = "t".repeat(43);
+        assert_eq!(status(&token, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status(&token, Some("Bearer wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&token, Some("Bearer ")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(status("", Some("Bearer ")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status(&token, Some(&format!("Bearer {token}"))).await,
+            StatusCode::OK
+        );
+    }
+}
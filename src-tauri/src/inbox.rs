@@ -0,0 +1,416 @@
+//! Dossier d'arrivée surveillé (préférence `inboxDir`).
+//!
+//! Les CV (PDF, DOCX, images) y déposés passent par `import_cv`, les pages `.html`, e-mails
+//! `.eml` et textes `.txt` sont importés comme offres. Chaque fichier lu est rangé dans
+//! `archive/` avant que son contenu soit enregistré ; un échec laisse le fichier en place,
+//! repris au prochain `scan_inbox` sans doublon. Le résultat est publié par l'événement
+//! `inbox-file-processed`.
+
+use std::{
+    collections::HashSet,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    file_import::{add_offer, extract_profile},
+    messages::AppError,
+    models::{CandidateProfile, JobOffer},
+    offer_import::{offer_from_email, offer_from_html, offer_from_text},
+    python_bridge::PythonBridge,
+    state::{store_profile, SharedState},
+    vault::VaultState,
+};
+
+pub const INBOX_EVENT: &str = "inbox-file-processed";
+
+const ARCHIVE_DIR: &str = "archive";
+const CV_EXTENSIONS: &[&str] = &["pdf", "docx", "doc", "png", "jpg", "jpeg"];
+const OFFER_EXTENSIONS: &[&str] = &["html", "htm", "eml", "txt"];
+/// Téléchargements et copies en cours laissés par les navigateurs.
+const PARTIAL_EXTENSIONS: &[&str] = &["part", "crdownload", "download", "tmp"];
+const SETTLE_DELAY: Duration = Duration::from_millis(500);
+const SETTLE_ATTEMPTS: usize = 60;
+
+/// Un dépôt déclenche souvent plusieurs événements : un fichier n'est traité qu'une fois.
+static IN_FLIGHT: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(Default::default);
+
+#[derive(Default)]
+pub struct InboxState(pub Mutex<Option<RunningInbox>>);
+
+pub struct RunningInbox {
+    dir: PathBuf,
+    /// La surveillance s'arrête quand le watcher est libéré.
+    _watcher: RecommendedWatcher,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InboxKind {
+    Cv,
+    Offer,
+}
+
+/// Contenu lu dans un fichier déposé, pas encore enregistré.
+enum Parsed {
+    Profile(CandidateProfile),
+    Offer(JobOffer),
+}
+
+/// Résultat publié pour chaque fichier traité.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxResult {
+    pub file: String,
+    pub kind: InboxKind,
+    /// Profil ou offre créés.
+    pub id: Option<String>,
+    pub archived_to: Option<PathBuf>,
+    pub error: Option<AppError>,
+}
+
+/// Démarre, arrête ou déplace la surveillance selon les préférences.
+pub fn apply_inbox(app: &AppHandle, dir: Option<&Path>) {
+    {
+        let state = app.state::<InboxState>();
+        let mut running = state.0.lock();
+        if running.as_ref().map(|inbox| inbox.dir.as_path()) == dir {
+            return;
+        }
+        if let Some(inbox) = running.take() {
+            info!(dir = %inbox.dir.display(), "Dossier d'arrivée non surveillé");
+        }
+        let Some(dir) = dir else {
+            return;
+        };
+        match watch(app.clone(), dir) {
+            Ok(watcher) => {
+                info!(dir = %dir.display(), "Dossier d'arrivée surveillé");
+                *running = Some(RunningInbox {
+                    dir: dir.to_path_buf(),
+                    _watcher: watcher,
+                });
+            }
+            Err(error) => {
+                error!(%error, "Impossible de surveiller le dossier d'arrivée");
+                return;
+            }
+        }
+    }
+    scan_inbox(app);
+}
+
+fn watch(app: AppHandle, dir: &Path) -> notify::Result<RecommendedWatcher> {
+    fs::create_dir_all(dir)?;
+    let mut watcher = RecommendedWatcher::new(
+        move |event: notify::Result<Event>| match event {
+            Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                for path in event.paths {
+                    enqueue(&app, path);
+                }
+            }
+            Ok(_) => {}
+            Err(error) => warn!(?error, "Dossier d'arrivée : événement invalide"),
+        },
+        Config::default(),
+    )?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// Traite les fichiers déjà présents : déposés application fermée, coffre verrouillé ou
+/// import échoué.
+pub fn scan_inbox(app: &AppHandle) {
+    let dir = app
+        .state::<InboxState>()
+        .0
+        .lock()
+        .as_ref()
+        .map(|inbox| inbox.dir.clone());
+    let Some(dir) = dir else {
+        return;
+    };
+    match fs::read_dir(&dir) {
+        Ok(entries) => {
+            for entry in entries.flatten() {
+                enqueue(app, entry.path());
+            }
+        }
+        Err(error) => warn!(?error, "Dossier d'arrivée illisible"),
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+fn classify(path: &Path) -> Option<InboxKind> {
+    let name = path.file_name()?.to_str()?;
+    if name.starts_with('.') || name.starts_with("~$") || name.ends_with('~') {
+        return None;
+    }
+    let extension = extension(path);
+    if PARTIAL_EXTENSIONS.contains(&extension.as_str()) {
+        None
+    } else if CV_EXTENSIONS.contains(&extension.as_str()) {
+        Some(InboxKind::Cv)
+    } else if OFFER_EXTENSIONS.contains(&extension.as_str()) {
+        Some(InboxKind::Offer)
+    } else {
+        None
+    }
+}
+
+fn enqueue(app: &AppHandle, path: PathBuf) {
+    let Some(kind) = classify(&path) else {
+        return;
+    };
+    if !IN_FLIGHT.lock().insert(path.clone()) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Some(result) = process(&app, &path, kind).await {
+            if let Err(error) = app.emit_all(INBOX_EVENT, &result) {
+                warn!(?error, "Impossible d'émettre {INBOX_EVENT}");
+            }
+        }
+        IN_FLIGHT.lock().remove(&path);
+    });
+}
+
+/// Attend que la taille du fichier se stabilise (copie ou téléchargement en cours).
+async fn wait_until_settled(path: &Path) -> bool {
+    let mut last_size = None;
+    for _ in 0..SETTLE_ATTEMPTS {
+        let Ok(metadata) = tokio::fs::metadata(path).await else {
+            return false;
+        };
+        if !metadata.is_file() {
+            return false;
+        }
+        if last_size == Some(metadata.len()) {
+            return true;
+        }
+        last_size = Some(metadata.len());
+        tokio::time::sleep(SETTLE_DELAY).await;
+    }
+    // Toujours en cours d'écriture : le prochain événement relancera le traitement.
+    false
+}
+
+async fn process(app: &AppHandle, path: &Path, kind: InboxKind) -> Option<InboxResult> {
+    if !wait_until_settled(path).await {
+        return None;
+    }
+    // Coffre verrouillé : le fichier attend le déverrouillage (`scan_inbox`).
+    if app.state::<VaultState>().0.lock().is_locked() {
+        debug!("Coffre verrouillé : import différé");
+        return None;
+    }
+
+    // Le nom du fichier peut contenir celui du candidat : seule l'extension est journalisée.
+    info!(?kind, extension = %extension(path), "Import depuis le dossier d'arrivée");
+    let parsed = parse(app, path, kind).await;
+    if let Err(error) = &parsed {
+        warn!(key = error.key, "Import depuis le dossier d'arrivée échoué");
+    }
+    Some(settle(&app.state::<SharedState>(), path, kind, parsed))
+}
+
+/// Archive le fichier lu puis enregistre son contenu. Un fichier qui ne peut être archivé n'est
+/// pas enregistré et reste en place : le reprendre ne crée pas de doublon.
+fn settle(
+    state: &SharedState,
+    path: &Path,
+    kind: InboxKind,
+    parsed: Result<Parsed, AppError>,
+) -> InboxResult {
+    let settled = parsed.and_then(|parsed| {
+        let target = archive(path)
+            .map_err(|error| AppError::new("errors.inbox.archive").arg("details", error))?;
+        let id = match parsed {
+            Parsed::Profile(profile) => {
+                let id = profile.id.clone();
+                store_profile(state, profile);
+                id
+            }
+            Parsed::Offer(offer) => add_offer(state, offer).id,
+        };
+        Ok((id, target))
+    });
+    let (id, archived_to, error) = match settled {
+        Ok((id, target)) => (Some(id), Some(target), None),
+        Err(error) => (None, None, Some(error)),
+    };
+    InboxResult {
+        file: path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned(),
+        kind,
+        id,
+        archived_to,
+        error,
+    }
+}
+
+async fn parse(app: &AppHandle, path: &Path, kind: InboxKind) -> Result<Parsed, AppError> {
+    let bridge = PythonBridge::from_app(app).map_err(AppError::python)?;
+    if kind == InboxKind::Cv {
+        let result = extract_profile(&bridge, &path.to_string_lossy()).await?;
+        return Ok(Parsed::Profile(result.profile));
+    }
+
+    let raw = tokio::fs::read(path)
+        .await
+        .map_err(|error| AppError::new("errors.inbox.read").arg("details", error))?;
+    let offer = match extension(path).as_str() {
+        "eml" => {
+            let email = offer_from_email(&bridge, &raw).await?;
+            if !email.warnings.is_empty() {
+                warn!(
+                    count = email.warnings.len(),
+                    "Dossier d'arrivée : pièces jointes ignorées"
+                );
+            }
+            email.offer
+        }
+        "html" | "htm" => offer_from_html(&String::from_utf8_lossy(&raw))?,
+        _ => offer_from_text(&String::from_utf8_lossy(&raw))?,
+    };
+    Ok(Parsed::Offer(offer))
+}
+
+/// Range le fichier dans `archive/`, sans écraser un fichier du même nom.
+fn archive(path: &Path) -> std::io::Result<PathBuf> {
+    let dir = path.parent().unwrap_or(Path::new(".")).join(ARCHIVE_DIR);
+    fs::create_dir_all(&dir)?;
+    let target = archive_target(&dir, path);
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+fn archive_target(dir: &Path, path: &Path) -> PathBuf {
+    let candidate = dir.join(path.file_name().unwrap_or_default());
+    if !candidate.exists() {
+        return candidate;
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let suffix = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|index| dir.join(format!("{stem} ({index}){suffix}")))
+        .find(|candidate| !candidate.exists())
+        .expect("suite infinie")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use parking_lot::Mutex;
+
+    use super::{archive, classify, settle, InboxKind, Parsed};
+    use crate::{
+        messages::AppError,
+        offer_import::offer_from_text,
+        state::{AppMemory, SharedState},
+    };
+
+    #[test]
+    fn classifies_dropped_files() {
+        assert_eq!(
+            classify(Path::new("/in/CV Dupont.PDF")),
+            Some(InboxKind::Cv)
+        );
+        assert_eq!(classify(Path::new("/in/photo.jpeg")), Some(InboxKind::Cv));
+        assert_eq!(classify(Path::new("/in/offre.eml")), Some(InboxKind::Offer));
+        assert_eq!(classify(Path::new("/in/offre.htm")), Some(InboxKind::Offer));
+        assert_eq!(classify(Path::new("/in/offre.pdf.crdownload")), None);
+        assert_eq!(classify(Path::new("/in/~$lettre.docx")), None);
+        assert_eq!(classify(Path::new("/in/.DS_Store")), None);
+        assert_eq!(classify(Path::new("/in/archive")), None);
+    }
+
+    #[test]
+    fn archive_keeps_existing_files() {
+        let inbox = std::env::temp_dir().join(format!("cvgen-inbox-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&inbox).unwrap();
+        for content in ["premier", "second"] {
+            fs::write(inbox.join("offre.txt"), content).unwrap();
+            archive(&inbox.join("offre.txt")).unwrap();
+        }
+
+        let archived = inbox.join("archive");
+        assert_eq!(
+            fs::read_to_string(archived.join("offre.txt")).unwrap(),
+            "premier"
+        );
+        assert_eq!(
+            fs::read_to_string(archived.join("offre (1).txt")).unwrap(),
+            "second"
+        );
+        assert!(!inbox.join("offre.txt").exists());
+        fs::remove_dir_all(inbox).unwrap();
+    }
+
+    #[test]
+    fn failed_imports_stay_in_the_inbox() {
+        let inbox = std::env::temp_dir().join(format!("cvgen-inbox-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&inbox).unwrap();
+        let file = inbox.join("offre.eml");
+        fs::write(&file, "invalide").unwrap();
+
+        let state = SharedState(Mutex::new(AppMemory::default()));
+        let offer = || {
+            Ok(Parsed::Offer(
+                offer_from_text("Ingénieure Rust\nPoste").unwrap(),
+            ))
+        };
+
+        let failed = settle(
+            &state,
+            &file,
+            InboxKind::Offer,
+            Err(AppError::new("errors.offerEml.invalid")),
+        );
+        assert!(failed.archived_to.is_none());
+        assert!(file.exists());
+
+        // Archivage impossible : rien n'est enregistré, le fichier sera repris.
+        fs::write(inbox.join("archive"), "").unwrap();
+        let unarchived = settle(&state, &file, InboxKind::Offer, offer());
+        assert_eq!(unarchived.error.unwrap().key, "errors.inbox.archive");
+        assert!(unarchived.id.is_none());
+        assert!(state.0.lock().offers.is_empty());
+        assert!(file.exists());
+        fs::remove_file(inbox.join("archive")).unwrap();
+
+        let imported = settle(&state, &file, InboxKind::Offer, offer());
+        let id = imported.id.unwrap();
+        assert!(state.0.lock().offers.contains_key(&id));
+        assert_eq!(state.0.lock().offers.len(), 1);
+        assert_eq!(
+            imported.archived_to,
+            Some(inbox.join("archive").join("offre.eml"))
+        );
+        assert!(!file.exists());
+        fs::remove_dir_all(inbox).unwrap();
+    }
+}
@@ -0,0 +1,475 @@
+//! Journal des modifications réversibles : profils (`store_profile`) et CV de la bibliothèque
+//! (`save_cv`, `delete_cv`).
+//!
+//! Chaque entrée garde l'état complet avant et après la modification (`None` : document
+//! absent) ; pour un CV, le document et son entrée du manifeste (`cv_snapshot`). L'historique est propre à chaque document et borné, comme le nombre de documents
+//! suivis ; une annulation n'écrase pas un document modifié depuis. Il est enregistré avec
+//! l'état dans `storage.json` (chiffré comme lui), si bien qu'une annulation reste possible
+//! après un redémarrage.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{command, State};
+use tracing::instrument;
+
+use crate::{
+    commands::fs::{cv_snapshot, restore_cv},
+    library_history::record_change,
+    logging::remember_name,
+    messages::AppError,
+    models::CandidateProfile,
+    search::DocumentKind,
+    settings::SettingsState,
+    state::{changed, AppMemory, SharedState},
+    vault::VaultState,
+};
+
+/// Modifications conservées par document ; les plus anciennes sont oubliées.
+pub const MAX_HISTORY: usize = 50;
+/// Documents suivis ; l'historique modifié le moins récemment est oublié (documents supprimés
+/// compris).
+pub const MAX_DOCUMENTS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JournalKind {
+    Profile,
+    /// Fichier de la bibliothèque de CV.
+    Cv,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub kind: JournalKind,
+    pub id: String,
+    pub at: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Historique d'un document : `done` du plus ancien au plus récent, `undone` empile les
+/// annulations à rétablir.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct History {
+    #[serde(default)]
+    pub done: VecDeque<JournalEntry>,
+    #[serde(default)]
+    pub undone: Vec<JournalEntry>,
+}
+
+impl History {
+    /// Date de la dernière modification ou annulation.
+    fn last_at(&self) -> Option<&str> {
+        let done = self.done.back().map(|entry| entry.at.as_str());
+        let undone = self.undone.last().map(|entry| entry.at.as_str());
+        done.max(undone)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Undo,
+    Redo,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    /// Clé `profile/<id>` ou `cv/<id>`.
+    #[serde(default)]
+    histories: BTreeMap<String, History>,
+}
+
+fn history_key(kind: JournalKind, id: &str) -> String {
+    match kind {
+        JournalKind::Profile => format!("profile/{id}"),
+        JournalKind::Cv => format!("cv/{id}"),
+    }
+}
+
+impl Journal {
+    /// Fusion avec le journal écrit par un autre processus (`state::write_persisted`) :
+    /// l'historique d'un document modifié ici remplace celui du fichier.
+    pub fn merge(&mut self, base: &Journal, ours: Journal) {
+        for key in base.histories.keys() {
+            if !ours.histories.contains_key(key) {
+                self.histories.remove(key);
+            }
+        }
+        for (key, history) in ours.histories {
+            if changed(base.histories.get(&key), &history) {
+                self.histories.insert(key, history);
+            }
+        }
+    }
+
+    /// Enregistre une modification ; les annulations en attente du document sont abandonnées.
+    pub fn record(
+        &mut self,
+        kind: JournalKind,
+        id: &str,
+        before: Option<Value>,
+        after: Option<Value>,
+    ) {
+        if before == after {
+            return;
+        }
+        let key = history_key(kind, id);
+        let history = self.histories.entry(key.clone()).or_default();
+        history.undone.clear();
+        history.done.push_back(JournalEntry {
+            kind,
+            id: id.to_string(),
+            at: Utc::now().to_rfc3339(),
+            before,
+            after,
+        });
+        while history.done.len() > MAX_HISTORY {
+            history.done.pop_front();
+        }
+        while self.histories.len() > MAX_DOCUMENTS {
+            let oldest = self
+                .histories
+                .iter()
+                .filter(|(other, _)| **other != key)
+                .min_by_key(|(_, history)| history.last_at())
+                .map(|(other, _)| other.clone());
+            match oldest {
+                Some(oldest) => self.histories.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+
+    pub fn history(&self, kind: JournalKind, id: &str) -> History {
+        self.histories
+            .get(&history_key(kind, id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Prochaine entrée à annuler ou rétablir, sans la déplacer.
+    fn next(&self, kind: JournalKind, id: &str, direction: Direction) -> Option<&JournalEntry> {
+        let history = self.histories.get(&history_key(kind, id))?;
+        match direction {
+            Direction::Undo => history.done.back(),
+            Direction::Redo => history.undone.last(),
+        }
+    }
+
+    /// Déplace l'entrée renvoyée par `next` une fois la modification appliquée.
+    fn complete(&mut self, kind: JournalKind, id: &str, direction: Direction) {
+        let Some(history) = self.histories.get_mut(&history_key(kind, id)) else {
+            return;
+        };
+        match direction {
+            Direction::Undo => {
+                if let Some(entry) = history.done.pop_back() {
+                    history.undone.push(entry);
+                }
+            }
+            Direction::Redo => {
+                if let Some(entry) = history.undone.pop() {
+                    history.done.push_back(entry);
+                }
+            }
+        }
+    }
+}
+
+/// Remplace le profil par `snapshot` (ou le retire), sans passer par le journal.
+fn restore_profile(
+    memory: &mut AppMemory,
+    id: &str,
+    snapshot: Option<&Value>,
+) -> Result<(), AppError> {
+    match snapshot {
+        Some(snapshot) => {
+            let profile: CandidateProfile = serde_json::from_value(snapshot.clone())
+                .map_err(|error| AppError::new("errors.journal.corrupted").arg("details", error))?;
+            remember_name(&profile.full_name);
+            memory.search.index_profile(&profile);
+            memory.profiles.insert(id.to_string(), profile);
+        }
+        None => {
+            memory.search.remove(DocumentKind::Profile, id);
+            memory.profiles.remove(id);
+        }
+    }
+    Ok(())
+}
+
+fn step(
+    direction: Direction,
+    kind: JournalKind,
+    id: &str,
+    state: &SharedState,
+    settings: &SettingsState,
+    vault: &VaultState,
+) -> Result<JournalEntry, AppError> {
+    // Même ordre de verrouillage que `save_cv` : coffre, puis état.
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    let mut memory = state.0.lock();
+    let entry = memory
+        .journal
+        .next(kind, id, direction)
+        .cloned()
+        .ok_or_else(|| {
+            let key = match direction {
+                Direction::Undo => "errors.journal.nothingToUndo",
+                Direction::Redo => "errors.journal.nothingToRedo",
+            };
+            AppError::new(key).arg("id", id)
+        })?;
+    let (expected, target) = match direction {
+        Direction::Undo => (entry.after.as_ref(), entry.before.as_ref()),
+        Direction::Redo => (entry.before.as_ref(), entry.after.as_ref()),
+    };
+
+    // Le document doit être resté tel que l'entrée l'a laissé : sinon l'annulation écraserait
+    // une modification faite hors du journal (autre processus, import…).
+    let current = match kind {
+        JournalKind::Profile => memory
+            .profiles
+            .get(id)
+            .and_then(|profile| serde_json::to_value(profile).ok()),
+        JournalKind::Cv => cv_snapshot(&settings.0.lock(), &vault, id)?,
+    };
+    if compared(kind, current.as_ref()) != compared(kind, expected) {
+        return Err(AppError::new("errors.journal.conflict").arg("id", id));
+    }
+
+    match kind {
+        JournalKind::Profile => restore_profile(&mut memory, id, target)?,
+        JournalKind::Cv => {
+            let settings = settings.0.lock().clone();
+            restore_cv(&settings, &vault, id, target)?;
+            let message = match direction {
+                Direction::Undo => format!("Annulation sur {id}"),
+                Direction::Redo => format!("Rétablissement sur {id}"),
+            };
+            record_change(&settings, &message);
+        }
+    }
+    memory.journal.complete(kind, id, direction);
+    Ok(entry)
+}
+
+/// Partie d'un état comparée avant d'annuler : pour un CV, le document seul (ses étiquettes
+/// ou son dossier ont pu changer depuis sans passer par le journal).
+fn compared(kind: JournalKind, snapshot: Option<&Value>) -> Option<&Value> {
+    match kind {
+        JournalKind::Profile => snapshot,
+        JournalKind::Cv => snapshot.map(|snapshot| &snapshot["document"]),
+    }
+}
+
+/// Annule la dernière modification du document ; renvoie l'entrée annulée.
+#[command]
+#[instrument(skip_all, fields(kind = ?kind, id = %id))]
+pub fn undo(
+    kind: JournalKind,
+    id: String,
+    state: State<'_, SharedState>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, VaultState>,
+) -> Result<JournalEntry, AppError> {
+    step(Direction::Undo, kind, &id, &state, &settings, &vault)
+}
+
+/// Rétablit la dernière modification annulée du document.
+#[command]
+#[instrument(skip_all, fields(kind = ?kind, id = %id))]
+pub fn redo(
+    kind: JournalKind,
+    id: String,
+    state: State<'_, SharedState>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, VaultState>,
+) -> Result<JournalEntry, AppError> {
+    step(Direction::Redo, kind, &id, &state, &settings, &vault)
+}
+
+#[command]
+#[instrument(skip_all, fields(kind = ?kind, id = %id))]
+pub fn journal_history(
+    kind: JournalKind,
+    id: String,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+) -> Result<History, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let history = state.0.lock().journal.history(kind, &id);
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use parking_lot::Mutex;
+
+    use super::{step, Direction, Journal, JournalKind, MAX_DOCUMENTS, MAX_HISTORY};
+    use crate::{
+        commands::fs::{cv_snapshot, load_manifest, remove_cv, save_manifest, write_cv},
+        models::CandidateProfile,
+        settings::{Settings, SettingsState},
+        state::{AppMemory, SharedState},
+        vault::{Vault, VaultState},
+    };
+
+    #[test]
+    fn bounds_history_and_clears_redo_on_new_change() {
+        let mut journal = Journal::default();
+        for version in 0..MAX_HISTORY + 5 {
+            journal.record(
+                JournalKind::Cv,
+                "cv1",
+                Some(json!({ "v": version })),
+                Some(json!({ "v": version + 1 })),
+            );
+        }
+        let history = journal.history(JournalKind::Cv, "cv1");
+        assert_eq!(history.done.len(), MAX_HISTORY);
+        assert_eq!(history.done[0].before, Some(json!({ "v": 5 })));
+
+        let last = journal
+            .next(JournalKind::Cv, "cv1", Direction::Undo)
+            .cloned();
+        assert_eq!(last.unwrap().before, Some(json!({ "v": MAX_HISTORY + 4 })));
+        journal.complete(JournalKind::Cv, "cv1", Direction::Undo);
+        assert!(journal
+            .next(JournalKind::Cv, "cv1", Direction::Redo)
+            .is_some());
+        assert!(journal
+            .next(JournalKind::Profile, "cv1", Direction::Undo)
+            .is_none());
+
+        journal.record(JournalKind::Cv, "cv1", None, Some(json!({})));
+        assert!(journal
+            .next(JournalKind::Cv, "cv1", Direction::Redo)
+            .is_none());
+
+        // Survit à un aller-retour par `storage.json`.
+        let saved: Journal =
+            serde_json::from_value(serde_json::to_value(&journal).unwrap()).unwrap();
+        assert_eq!(
+            saved.history(JournalKind::Cv, "cv1").done.len(),
+            MAX_HISTORY
+        );
+    }
+
+    #[test]
+    fn bounds_the_number_of_documents() {
+        let mut journal = Journal::default();
+        for index in 0..MAX_DOCUMENTS + 3 {
+            journal.record(
+                JournalKind::Cv,
+                &format!("cv{index}"),
+                None,
+                Some(json!({})),
+            );
+        }
+        assert!(journal
+            .next(JournalKind::Cv, "cv0", Direction::Undo)
+            .is_none());
+        let last = format!("cv{}", MAX_DOCUMENTS + 2);
+        assert!(journal
+            .next(JournalKind::Cv, &last, Direction::Undo)
+            .is_some());
+    }
+
+    #[test]
+    fn undo_refuses_to_overwrite_a_later_change() {
+        let profile = |name: &str| -> CandidateProfile {
+            serde_json::from_value(json!({
+                "id": "p1", "fullName": name, "email": null, "phone": null, "summary": null,
+                "experiences": [], "skills": [], "education": [], "projects": [], "languages": []
+            }))
+            .unwrap()
+        };
+        let mut memory = AppMemory::default();
+        let recorded = profile("Élise Martin");
+        memory.journal.record(
+            JournalKind::Profile,
+            "p1",
+            None,
+            Some(serde_json::to_value(&recorded).unwrap()),
+        );
+        // Modifié hors du journal depuis l'entrée.
+        memory.profiles.insert("p1".into(), profile("Élise Durand"));
+        let state = SharedState(Mutex::new(memory));
+        let settings = SettingsState(Mutex::new(Settings::default()));
+        let vault = VaultState(Mutex::new(Vault::default()));
+
+        let undo = || {
+            step(
+                Direction::Undo,
+                JournalKind::Profile,
+                "p1",
+                &state,
+                &settings,
+                &vault,
+            )
+        };
+        assert_eq!(undo().unwrap_err().key, "errors.journal.conflict");
+        assert_eq!(state.0.lock().profiles["p1"].full_name, "Élise Durand");
+
+        state.0.lock().profiles.insert("p1".into(), recorded);
+        undo().unwrap();
+        assert!(state.0.lock().profiles.is_empty());
+    }
+
+    #[test]
+    fn undoing_a_cv_delete_restores_its_manifest_entry() {
+        let dir = std::env::temp_dir().join(format!("cvgen-journal-{}", uuid::Uuid::new_v4()));
+        let settings = Settings {
+            library_dir: Some(dir.clone()),
+            ..Settings::default()
+        };
+        let vault = Vault::default();
+        write_cv(
+            &settings,
+            &vault,
+            "cv1",
+            json!({ "fullName": "Élise Martin" }),
+        )
+        .unwrap();
+        let mut manifest = load_manifest(&settings, &vault).unwrap();
+        let entry = manifest.items.get_mut("cv1").unwrap();
+        entry.tags = vec!["rust".into()];
+        entry.folder = "Clients".into();
+        save_manifest(&settings, &vault, &manifest).unwrap();
+
+        let before = cv_snapshot(&settings, &vault, "cv1").unwrap();
+        remove_cv(&settings, &vault, "cv1").unwrap();
+        let mut memory = AppMemory::default();
+        memory
+            .journal
+            .record(JournalKind::Cv, "cv1", before.clone(), None);
+        let state = SharedState(Mutex::new(memory));
+        let settings_state = SettingsState(Mutex::new(settings.clone()));
+        let vault_state = VaultState(Mutex::new(Vault::default()));
+        let run = |direction| {
+            step(
+                direction,
+                JournalKind::Cv,
+                "cv1",
+                &state,
+                &settings_state,
+                &vault_state,
+            )
+        };
+
+        run(Direction::Undo).unwrap();
+        assert_eq!(cv_snapshot(&settings, &vault, "cv1").unwrap(), before);
+        run(Direction::Redo).unwrap();
+        assert!(cv_snapshot(&settings, &vault, "cv1").unwrap().is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
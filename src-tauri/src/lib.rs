@@ -0,0 +1,259 @@
+pub mod ai_engine;
+pub mod anonymize;
+pub mod cover_letter;
+pub mod diff;
+pub mod exporter;
+pub mod file_import;
+pub mod http_api;
+pub mod inbox;
+pub mod journal;
+pub mod library_history;
+pub mod llm_engine;
+pub mod locale;
+pub mod logging;
+pub mod messages;
+pub mod models;
+pub mod offer_import;
+pub mod paths;
+pub mod python_bridge;
+pub mod resume_document;
+pub mod rule_engine;
+pub mod search;
+pub mod settings;
+pub mod state;
+pub mod variants;
+pub mod vault;
+pub mod workspace;
+pub mod commands {
+    pub mod fs;
+}
+
+use ai_engine::{adapt_documents, analyze_offer, update_adaptation};
+use exporter::export_documents;
+use file_import::{import_cv, register_offer};
+use http_api::HttpApiState;
+use inbox::InboxState;
+use logging::LogLevel;
+use parking_lot::Mutex;
+use paths::AppPaths;
+use settings::{Settings, SettingsState};
+use state::{AppMemory, SharedState};
+use tauri::{App, AppHandle, Manager, RunEvent};
+use vault::{Vault, VaultState};
+
+/// Point d'entrée de l'interface ; le CLI (`src/bin/cvgen-cli.rs`) réutilise les mêmes modules.
+pub fn run() {
+    let app = tauri::Builder::default()
+        .manage(SharedState(Mutex::new(AppMemory::default())))
+        .manage(SettingsState(Mutex::new(Settings::default())))
+        .manage(VaultState(Mutex::new(Vault::default())))
+        .manage(HttpApiState::default())
+        .manage(InboxState::default())
+        .setup(|app| {
+            // Niveau par défaut jusqu'à la lecture des préférences.
+            let log_dir = AppPaths::from_app(&app.handle())
+                .ok()
+                .map(|paths| paths.log_dir);
+            if let Err(error) = logging::init_logging(log_dir.as_deref(), LogLevel::default()) {
+                eprintln!("Impossible d'initialiser la journalisation: {error:?}");
+            }
+            #[cfg(debug_assertions)]
+            {
+                if let Err(error) = init_dev_autoreload(app) {
+                    tracing::warn!(?error, "Impossible d'initialiser l'autoreload");
+                }
+            }
+            if let Err(error) = settings::load_settings(&app.handle()) {
+                tracing::error!(?error, "Impossible de charger les préférences");
+            }
+            if let Err(error) = vault::load_vault(&app.handle()) {
+                tracing::error!(?error, "Impossible de charger le coffre");
+            }
+            // Chiffrement actif : l'état est chargé par la commande `unlock`.
+            if !app.state::<VaultState>().0.lock().is_locked() {
+                if let Err(error) = load_state(&app.handle()) {
+                    tracing::error!(?error, "Impossible de charger l'état");
+                }
+            }
+            let handle = app.handle();
+            let settings = settings::current_settings(&handle);
+            http_api::apply_http_api(&handle, &settings.http_api);
+            inbox::apply_inbox(&handle, settings.inbox_dir.as_deref());
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            import_cv,
+            register_offer,
+            offer_import::import_offer_html,
+            offer_import::import_offer_eml,
+            analyze_offer,
+            adapt_documents,
+            update_adaptation,
+            export_documents,
+            variants::create_variant,
+            variants::update_variant,
+            variants::get_variant,
+            variants::list_variants,
+            variants::delete_variant,
+            variants::resolve_variant,
+            search::search,
+            journal::undo,
+            journal::redo,
+            journal::journal_history,
+            workspace::export_workspace,
+            workspace::import_workspace,
+            settings::get_settings,
+            settings::update_settings,
+            settings::regenerate_api_token,
+            http_api::http_api_status,
+            vault::encryption_status,
+            vault::enable_encryption,
+            vault::unlock,
+            vault::lock,
+            vault::change_passphrase,
+            commands::fs::save_cv,
+            commands::fs::load_cv,
+            commands::fs::list_cvs,
+            commands::fs::list_cvs_meta,
+            commands::fs::tag_cv,
+            commands::fs::move_cv,
+            commands::fs::favorite_cv,
+            commands::fs::link_cv_offers,
+            commands::fs::delete_cv,
+            commands::fs::check_library,
+            commands::fs::repair_library,
+            commands::fs::library_location,
+            library_history::cv_history,
+            library_history::cv_diff,
+            library_history::restore_cv_revision,
+            commands::fs::move_library
+        ])
+        .build(tauri::generate_context!())
+        .expect("Échec de l'initialisation Tauri");
+
+    app.run(|app_handle, event| {
+        if matches!(event, RunEvent::ExitRequested { .. } | RunEvent::Exit) {
+            if let Err(error) = save_state(app_handle) {
+                tracing::error!(?error, "Impossible de sauvegarder l'état");
+            }
+        }
+    });
+}
+
+#[cfg(debug_assertions)]
+fn init_dev_autoreload(app: &App) -> anyhow::Result<()> {
+    use notify::{Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+    use std::path::PathBuf;
+    use std::sync::mpsc::channel;
+    use std::time::{Duration, Instant};
+
+    let app_handle = app.handle();
+    let workspace_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .map(|path| path.to_path_buf())
+        .unwrap_or_else(|| PathBuf::from(".."));
+
+    let watch_paths: Vec<PathBuf> = [
+        workspace_dir.join("src"),
+        workspace_dir.join("src-tauri").join("src"),
+        workspace_dir.join("python"),
+    ]
+    .into_iter()
+    .collect();
+
+    let (startup_tx, startup_rx) = std::sync::mpsc::channel::<anyhow::Result<()>>();
+
+    std::thread::spawn(move || {
+        let (event_tx, event_rx) = channel();
+
+        let mut watcher = match RecommendedWatcher::new(
+            event_tx,
+            Config::default().with_poll_interval(Duration::from_millis(300)),
+        ) {
+            Ok(watcher) => watcher,
+            Err(error) => {
+                let _ = startup_tx.send(Err(anyhow::Error::new(error)));
+                return;
+            }
+        };
+
+        for path in watch_paths {
+            if path.exists() {
+                if let Err(error) = watcher.watch(&path, RecursiveMode::Recursive) {
+                    tracing::warn!(
+                        ?error,
+                        path = %path.display(),
+                        "Watcher: impossible de suivre"
+                    );
+                }
+            }
+        }
+
+        let _ = startup_tx.send(Ok(()));
+
+        let mut last_reload = Instant::now();
+
+        loop {
+            match event_rx.recv() {
+                Ok(Ok(event)) => {
+                    if matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    ) {
+                        if last_reload.elapsed() < Duration::from_millis(250) {
+                            continue;
+                        }
+                        last_reload = Instant::now();
+                        if let Some(window) = app_handle.get_window("main") {
+                            if let Err(error) = window.eval("window.location.reload()") {
+                                tracing::warn!(?error, "Watcher: rechargement échoué");
+                            }
+                        }
+                    }
+                }
+                Ok(Err(error)) => {
+                    tracing::warn!(?error, "Watcher: événement invalide");
+                }
+                Err(_) => break,
+            }
+        }
+    });
+
+    startup_rx
+        .recv()
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("Watcher thread non démarré")))
+}
+
+fn load_state(app_handle: &AppHandle) -> anyhow::Result<()> {
+    let paths = AppPaths::from_app(app_handle)?;
+    let data = {
+        let vault = app_handle.state::<VaultState>();
+        let vault = vault.0.lock();
+        state::read_persisted(&paths.storage_file(), &vault)?
+    };
+    let Some(data) = data else {
+        return Ok(());
+    };
+
+    let state = app_handle.state::<SharedState>();
+    let mut guard = state.0.lock();
+    *guard = data.into();
+    Ok(())
+}
+
+fn save_state(app_handle: &tauri::AppHandle) -> anyhow::Result<()> {
+    let vault = app_handle.state::<VaultState>();
+    let vault = vault.0.lock();
+    // Verrouillé, l'état en mémoire est vide : ne pas écraser le fichier chiffré.
+    if vault.is_locked() {
+        return Ok(());
+    }
+
+    let state = app_handle.state::<SharedState>();
+    let path = AppPaths::from_app(app_handle)?.storage_file();
+    // Verrouillé pendant l'écriture : l'état relu après une fusion ne perd aucune modification.
+    let mut memory = state.0.lock();
+    let written = state::write_persisted(&path, &vault, memory.clone())?;
+    *memory = written.into();
+    Ok(())
+}
@@ -0,0 +1,354 @@
+//! Historique git de la bibliothèque de CV (préférence `libraryGit`).
+//!
+//! Le dossier de la bibliothèque devient un dépôt git local, créé au premier enregistrement :
+//! `save_cv`, `delete_cv`, les annulations et les restaurations y sont consignés par un commit
+//! au message généré, qui porte les fichiers `*.json` (CV et `manifest.json`). Aucun dépôt
+//! distant n'est utilisé. L'historique exclut le chiffrement (`apply_settings`,
+//! `enable_encryption`) : ses objets garderaient les CV en clair, même une fois chiffrés.
+
+use std::path::Path;
+
+use chrono::DateTime;
+use git2::{IndexAddOption, Oid, Patch, Repository, Signature, Sort};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{command, State};
+use tracing::instrument;
+
+use crate::{
+    commands::fs::{cv_file_path, cv_snapshot, library_context, library_root, write_cv},
+    journal::JournalKind,
+    messages::AppError,
+    settings::{Settings, SettingsState},
+    state::SharedState,
+    vault::{Vault, VaultState},
+};
+
+/// Fichiers suivis : les CV et le manifest, pas les fichiers temporaires `*.json.tmp`.
+const TRACKED: &str = "*.json";
+const AUTHOR_NAME: &str = "CVGen";
+const AUTHOR_EMAIL: &str = "cvgen@localhost";
+
+/// Commit ayant modifié un CV.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CvRevision {
+    /// Identifiant complet du commit.
+    pub revision: String,
+    pub at: String,
+    pub message: String,
+    /// Le CV a été supprimé par ce commit.
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CvDiff {
+    pub from: String,
+    /// `None` : fichier actuel de la bibliothèque.
+    pub to: Option<String>,
+    /// Différence unifiée, comme `git diff`.
+    pub patch: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+fn git_error(error: git2::Error) -> AppError {
+    AppError::new("errors.library.git").arg("details", error.message())
+}
+
+/// Dépôt de la bibliothèque ; `None` tant qu'aucun commit n'a été fait.
+fn open_repository(settings: &Settings) -> Result<Option<Repository>, AppError> {
+    if !settings.library_git {
+        return Err(AppError::new("errors.library.historyDisabled"));
+    }
+    let root = library_root(settings)?;
+    if !root.join(".git").exists() {
+        return Ok(None);
+    }
+    Repository::open(&root).map(Some).map_err(git_error)
+}
+
+/// Commit de l'état des fichiers suivis ; rien n'est fait s'ils n'ont pas changé.
+fn commit(settings: &Settings, message: &str) -> Result<Option<Oid>, AppError> {
+    let root = library_root(settings)?;
+    if !root.exists() {
+        return Ok(None);
+    }
+    let repository = match open_repository(settings)? {
+        Some(repository) => repository,
+        None => Repository::init(&root).map_err(git_error)?,
+    };
+    let commit = || -> Result<Option<Oid>, git2::Error> {
+        let mut index = repository.index()?;
+        index.add_all([TRACKED], IndexAddOption::DEFAULT, None)?;
+        index.update_all([TRACKED], None)?;
+        index.write()?;
+        let tree = repository.find_tree(index.write_tree()?)?;
+        let parent = match repository.head() {
+            Ok(head) => Some(head.peel_to_commit()?),
+            Err(_) => None,
+        };
+        if parent
+            .as_ref()
+            .is_some_and(|parent| parent.tree_id() == tree.id())
+        {
+            return Ok(None);
+        }
+        let signature = Signature::now(AUTHOR_NAME, AUTHOR_EMAIL)?;
+        let parents: Vec<_> = parent.iter().collect();
+        repository
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .map(Some)
+    };
+    commit().map_err(git_error)
+}
+
+/// Consigne une modification de la bibliothèque si l'historique est actif. Le fichier est
+/// déjà écrit : un échec de git est journalisé sans faire échouer la commande.
+pub(crate) fn record_change(settings: &Settings, message: &str) {
+    if !settings.library_git {
+        return;
+    }
+    if let Err(error) = commit(settings, message) {
+        tracing::warn!(?error, "Bibliothèque: commit git impossible");
+    }
+}
+
+fn file_name(settings: &Settings, id: &str) -> Result<String, AppError> {
+    let path = cv_file_path(settings, id)?;
+    Ok(path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Contenu brut du fichier dans l'arbre d'un commit.
+fn blob_at(
+    repository: &Repository,
+    commit: &git2::Commit<'_>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, git2::Error> {
+    let Some(entry) = commit.tree()?.get_name(name).map(|entry| entry.id()) else {
+        return Ok(None);
+    };
+    Ok(Some(repository.find_blob(entry)?.content().to_vec()))
+}
+
+fn find_commit<'r>(
+    repository: &'r Repository,
+    revision: &str,
+) -> Result<git2::Commit<'r>, AppError> {
+    repository
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| AppError::new("errors.library.revisionNotFound").arg("revision", revision))
+}
+
+/// Commits ayant modifié le CV, du plus récent au plus ancien.
+pub fn history(settings: &Settings, id: &str) -> Result<Vec<CvRevision>, AppError> {
+    let name = file_name(settings, id)?;
+    let Some(repository) = open_repository(settings)? else {
+        return Ok(vec![]);
+    };
+    let walk = || -> Result<Vec<CvRevision>, git2::Error> {
+        let mut revisions = Vec::new();
+        let mut walker = repository.revwalk()?;
+        if walker.push_head().is_err() {
+            return Ok(revisions);
+        }
+        walker.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        for oid in walker {
+            let oid = oid?;
+            let commit = repository.find_commit(oid)?;
+            let entry = commit.tree()?.get_name(&name).map(|entry| entry.id());
+            let previous = match commit.parent(0) {
+                Ok(parent) => parent.tree()?.get_name(&name).map(|entry| entry.id()),
+                Err(_) => None,
+            };
+            if entry == previous {
+                continue;
+            }
+            revisions.push(CvRevision {
+                revision: oid.to_string(),
+                at: DateTime::from_timestamp(commit.time().seconds(), 0)
+                    .map(|at| at.to_rfc3339())
+                    .unwrap_or_default(),
+                message: commit.summary().unwrap_or_default().to_string(),
+                deleted: entry.is_none(),
+            });
+        }
+        Ok(revisions)
+    };
+    walk().map_err(git_error)
+}
+
+/// Différence entre deux révisions du CV, ou entre `from` et le fichier actuel.
+pub fn diff(
+    settings: &Settings,
+    vault: &Vault,
+    id: &str,
+    from: &str,
+    to: Option<&str>,
+) -> Result<CvDiff, AppError> {
+    let name = file_name(settings, id)?;
+    let context = library_context(&name);
+    let repository = open_repository(settings)?
+        .ok_or_else(|| AppError::new("errors.library.revisionNotFound").arg("revision", from))?;
+    let content_at = |revision: &str| -> Result<Vec<u8>, AppError> {
+        let commit = find_commit(&repository, revision)?;
+        let content = blob_at(&repository, &commit, &name).map_err(git_error)?;
+        content.map_or(Ok(Vec::new()), |content| vault.decode(content, &context))
+    };
+    let old = content_at(from)?;
+    let new = match to {
+        Some(revision) => content_at(revision)?,
+        None => match std::fs::read(cv_file_path(settings, id)?) {
+            Ok(content) => vault.decode(content, &context)?,
+            Err(_) => Vec::new(),
+        },
+    };
+    let path = Path::new(&name);
+    let mut patch =
+        Patch::from_buffers(&old, Some(path), &new, Some(path), None).map_err(git_error)?;
+    let (_, additions, deletions) = patch.line_stats().map_err(git_error)?;
+    let text = patch.to_buf().map_err(git_error)?;
+    Ok(CvDiff {
+        from: from.to_string(),
+        to: to.map(str::to_string),
+        patch: String::from_utf8_lossy(&text).into_owned(),
+        additions,
+        deletions,
+    })
+}
+
+/// Document du CV tel qu'enregistré dans une révision.
+fn document_at(
+    settings: &Settings,
+    vault: &Vault,
+    id: &str,
+    revision: &str,
+) -> Result<Value, AppError> {
+    let name = file_name(settings, id)?;
+    let repository = open_repository(settings)?.ok_or_else(|| {
+        AppError::new("errors.library.revisionNotFound").arg("revision", revision)
+    })?;
+    let commit = find_commit(&repository, revision)?;
+    let content = blob_at(&repository, &commit, &name)
+        .map_err(git_error)?
+        .ok_or_else(|| {
+            AppError::new("errors.library.notInRevision")
+                .arg("id", id)
+                .arg("revision", revision)
+        })?;
+    serde_json::from_slice(&vault.decode(content, &library_context(&name))?)
+        .map_err(|error| AppError::new("errors.fs.parse").arg("details", error))
+}
+
+#[command]
+#[instrument(skip_all, fields(id = %id))]
+pub fn cv_history(
+    id: String,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, VaultState>,
+) -> Result<Vec<CvRevision>, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let settings = settings.0.lock().clone();
+    history(&settings, &id)
+}
+
+#[command]
+#[instrument(skip_all, fields(id = %id))]
+pub fn cv_diff(
+    id: String,
+    from: String,
+    to: Option<String>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, VaultState>,
+) -> Result<CvDiff, AppError> {
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    let settings = settings.0.lock().clone();
+    diff(&settings, &vault, &id, &from, to.as_deref())
+}
+
+/// Réenregistre le CV tel qu'il était dans `revision` ; l'opération est elle-même consignée
+/// (journal d'annulation et commit).
+#[command]
+#[instrument(skip_all, fields(id = %id, revision = %revision))]
+pub fn restore_cv_revision(
+    id: String,
+    revision: String,
+    state: State<'_, SharedState>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, VaultState>,
+) -> Result<Value, AppError> {
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    let settings = settings.0.lock().clone();
+    let document = document_at(&settings, &vault, &id, &revision)?;
+    let before = cv_snapshot(&settings, &vault, &id)?;
+    let after = write_cv(&settings, &vault, &id, document)?;
+    let snapshot = cv_snapshot(&settings, &vault, &id)?;
+    state
+        .0
+        .lock()
+        .journal
+        .record(JournalKind::Cv, &id, before, snapshot);
+    let short: String = revision.chars().take(7).collect();
+    record_change(&settings, &format!("Restauration de {id} ({short})"));
+    Ok(after)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{commit, diff, history};
+    use crate::{settings::Settings, vault::Vault};
+
+    #[test]
+    fn commits_and_diffs_cv_revisions() {
+        let dir = std::env::temp_dir().join(format!("cvgen-history-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let settings = Settings {
+            library_dir: Some(dir.clone()),
+            library_git: true,
+            ..Settings::default()
+        };
+        let path = dir.join("cv1.json");
+
+        fs::write(&path, "{\n  \"title\": \"Dev\"\n}\n").unwrap();
+        let first = commit(&settings, "Enregistrement de cv1").unwrap().unwrap();
+        assert!(commit(&settings, "Sans changement").unwrap().is_none());
+        fs::write(&path, "{\n  \"title\": \"Lead dev\"\n}\n").unwrap();
+        commit(&settings, "Enregistrement de cv1").unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+        commit(&settings, "Suppression de cv1").unwrap().unwrap();
+
+        let revisions = history(&settings, "cv1").unwrap();
+        assert_eq!(revisions.len(), 3);
+        assert!(revisions[0].deleted && !revisions[1].deleted);
+        assert_eq!(revisions[2].revision, first.to_string());
+
+        let changes = diff(
+            &settings,
+            &Vault::default(),
+            "cv1",
+            &first.to_string(),
+            Some(&revisions[1].revision),
+        )
+        .unwrap();
+        assert_eq!((changes.additions, changes.deletions), (1, 1));
+        assert!(changes.patch.contains("+  \"title\": \"Lead dev\""));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,407 @@
+//! Moteur d'adaptation par LLM local, via un serveur compatible OpenAI (llama.cpp, Ollama,
+//! LM Studio…). La réponse est lue en flux (SSE) puis analysée comme un JSON structuré ; les
+//! passages mis en avant par le modèle sont convertis en `HighlightSpan`.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{debug, info};
+
+use crate::{
+    ai_engine::{AdaptationEngine, EngineInput},
+    locale::Language,
+    messages::AppError,
+    models::{AdaptationResult, CoverLetter, HighlightSpan, HighlightType},
+    settings::LlmSettings,
+};
+
+/// Fragments de texte reçus pendant la génération, pour un affichage progressif.
+pub const ADAPTATION_PROGRESS_EVENT: &str = "adaptation-progress";
+
+pub const DEFAULT_SYSTEM_PROMPT: &str = include_str!("../prompts/adapt_system.txt");
+pub const DEFAULT_USER_PROMPT: &str = include_str!("../prompts/adapt_user.txt");
+
+/// Longueur maximale d'un corps d'erreur repris dans le message.
+const MAX_ERROR_BODY: usize = 500;
+
+type ProgressCallback = Box<dyn Fn(&str) + Send + Sync>;
+
+pub struct LlmEngine {
+    settings: LlmSettings,
+    client: reqwest::Client,
+    on_delta: Option<ProgressCallback>,
+}
+
+impl LlmEngine {
+    pub fn new(settings: LlmSettings) -> Self {
+        Self {
+            settings,
+            client: reqwest::Client::new(),
+            on_delta: None,
+        }
+    }
+
+    /// Appelé pour chaque fragment de la réponse en cours.
+    pub fn with_progress(mut self, on_delta: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.on_delta = Some(Box::new(on_delta));
+        self
+    }
+
+    async fn complete(&self, messages: Value) -> Result<String, AppError> {
+        let url = format!(
+            "{}/chat/completions",
+            self.settings.endpoint.trim_end_matches('/')
+        );
+        let body = json!({
+            "model": self.settings.model,
+            "messages": messages,
+            "temperature": self.settings.temperature,
+            "stream": true,
+            "response_format": { "type": "json_object" }
+        });
+        let mut request = self
+            .client
+            .post(&url)
+            .json(&body)
+            .timeout(Duration::from_secs(self.settings.timeout_secs));
+        if let Some(key) = self
+            .settings
+            .api_key
+            .as_deref()
+            .filter(|key| !key.is_empty())
+        {
+            request = request.bearer_auth(key);
+        }
+
+        info!(model = %self.settings.model, "Appel du LLM local");
+        let mut response = request.send().await.map_err(request_error)?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::new("errors.llm.status")
+                .arg("status", status.as_u16())
+                .arg("details", truncate(&body)));
+        }
+        let streamed = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        if !streamed {
+            // Serveur ignorant `stream` : réponse complète d'un bloc.
+            let body: Value = response.json().await.map_err(request_error)?;
+            return message_content(&body);
+        }
+
+        let mut decoder = SseDecoder::default();
+        let mut content = String::new();
+        while let Some(chunk) = response.chunk().await.map_err(request_error)? {
+            for data in decoder.push(&chunk) {
+                if data == "[DONE]" {
+                    return Ok(content);
+                }
+                let delta = delta_content(&data)?;
+                if !delta.is_empty() {
+                    if let Some(on_delta) = &self.on_delta {
+                        on_delta(&delta);
+                    }
+                    content.push_str(&delta);
+                }
+            }
+        }
+        Ok(content)
+    }
+}
+
+impl AdaptationEngine for LlmEngine {
+    async fn adapt(&self, input: EngineInput<'_>) -> Result<AdaptationResult, AppError> {
+        let messages = json!([
+            { "role": "system", "content": render(self.settings.system_prompt(), &input) },
+            { "role": "user", "content": render(self.settings.user_prompt(), &input) }
+        ]);
+        let content = self.complete(messages).await?;
+        debug!(length = content.len(), "Réponse du LLM reçue");
+        parse_adaptation(&content, input.language)
+    }
+}
+
+fn request_error(error: reqwest::Error) -> AppError {
+    AppError::new("errors.llm.request").arg("details", error)
+}
+
+fn invalid_response(details: impl std::fmt::Display) -> AppError {
+    AppError::new("errors.llm.invalidResponse").arg("details", details)
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_ERROR_BODY).collect()
+}
+
+fn language_name(language: Language) -> &'static str {
+    match language {
+        Language::Fr => "French",
+        Language::En => "English",
+        Language::De => "German",
+    }
+}
+
+/// Remplace les variables `{{…}}` des gabarits de prompt.
+fn render(template: &str, input: &EngineInput<'_>) -> String {
+    let offer = input.offer;
+    let company = offer
+        .company
+        .as_deref()
+        .map(|company| format!(" — {company}"))
+        .unwrap_or_default();
+    let keywords = offer.keywords.as_deref().unwrap_or_default().join(", ");
+    let profile = serde_json::to_string_pretty(input.profile).unwrap_or_default();
+    [
+        ("{{language}}", language_name(input.language)),
+        ("{{offer_title}}", offer.title.as_str()),
+        ("{{offer_company}}", company.as_str()),
+        ("{{keywords}}", keywords.as_str()),
+        ("{{offer}}", offer.description.as_str()),
+        ("{{profile}}", profile.as_str()),
+    ]
+    .into_iter()
+    .fold(template.to_string(), |text, (variable, value)| {
+        text.replace(variable, value)
+    })
+}
+
+/// Découpe un flux `text/event-stream` en valeurs `data:`, y compris à cheval sur deux blocs.
+#[derive(Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut data = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(value) = line.trim_end().strip_prefix("data:") {
+                data.push(value.trim_start().to_string());
+            }
+        }
+        data
+    }
+}
+
+fn delta_content(data: &str) -> Result<String, AppError> {
+    let event: Value = serde_json::from_str(data).map_err(invalid_response)?;
+    if let Some(error) = event.get("error") {
+        return Err(invalid_response(error));
+    }
+    let choice = &event["choices"][0];
+    Ok(choice["delta"]["content"]
+        .as_str()
+        .or_else(|| choice["text"].as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+fn message_content(body: &Value) -> Result<String, AppError> {
+    body["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| invalid_response("choices[0].message.content"))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LlmAdaptation {
+    adapted_resume: String,
+    adapted_cover_letter: String,
+    #[serde(default)]
+    highlights: Vec<LlmHighlight>,
+}
+
+#[derive(Deserialize)]
+struct LlmHighlight {
+    text: String,
+    #[serde(rename = "type")]
+    highlight_type: Option<HighlightType>,
+}
+
+/// Lit l'objet JSON de la réponse, même entouré de texte ou d'un bloc de code Markdown.
+fn parse_adaptation(content: &str, language: Language) -> Result<AdaptationResult, AppError> {
+    let json = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => return Err(invalid_response(truncate(content))),
+    };
+    let adaptation: LlmAdaptation = serde_json::from_str(json).map_err(invalid_response)?;
+    let highlights = locate_highlights(&adaptation.adapted_resume, &adaptation.highlights);
+    Ok(AdaptationResult {
+        adapted_resume: adaptation.adapted_resume,
+        resume_document: None,
+        adapted_cover_letter: CoverLetter::from_text(&adaptation.adapted_cover_letter),
+        highlights,
+        cover_letter_highlights: Vec::new(),
+        language: Some(language),
+        offer_id: None,
+        user_edited: false,
+    })
+}
+
+/// Le modèle cite des extraits plutôt que des positions (peu fiables) : chaque extrait est
+/// cherché dans le CV, à sa première occurrence non encore surlignée, en indices de caractères.
+fn locate_highlights(resume: &str, highlights: &[LlmHighlight]) -> Vec<HighlightSpan> {
+    let mut spans: Vec<HighlightSpan> = Vec::new();
+    for highlight in highlights {
+        let text = highlight.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let found = resume.match_indices(text).find_map(|(byte_start, _)| {
+            let start = resume[..byte_start].chars().count();
+            let end = start + text.chars().count();
+            let free = spans
+                .iter()
+                .all(|span| end <= span.start || start >= span.end);
+            free.then_some((start, end))
+        });
+        if let Some((start, end)) = found {
+            spans.push(HighlightSpan {
+                id: format!("llm-{}", spans.len()),
+                highlight_type: highlight
+                    .highlight_type
+                    .clone()
+                    .unwrap_or(HighlightType::Emphasis),
+                start,
+                end,
+                utf8: None,
+                utf16: None,
+                removed_text: None,
+            });
+        }
+    }
+    spans.sort_by_key(|span| span.start);
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{routing::post, Json, Router};
+    use parking_lot::Mutex;
+    use serde_json::{json, Value};
+
+    use super::{parse_adaptation, LlmEngine};
+    use crate::{
+        ai_engine::{AdaptationEngine, EngineInput},
+        locale::Language,
+        models::{CandidateProfile, HighlightType, JobOffer},
+        settings::LlmSettings,
+    };
+
+    fn sse_body(content: &str) -> String {
+        // Découpage arbitraire, au milieu des caractères multi-octets compris.
+        let chars: Vec<char> = content.chars().collect();
+        let mut body = String::from(": keep-alive\n\n");
+        for piece in chars.chunks(7) {
+            let delta = json!({ "choices": [{ "delta": { "content": piece.iter().collect::<String>() } }] });
+            body.push_str(&format!("data: {delta}\n\n"));
+        }
+        body.push_str("data: [DONE]\n\n");
+        body
+    }
+
+    #[tokio::test]
+    async fn streams_structured_adaptation_from_mock_server() {
+        let answer = json!({
+            "adaptedResume": "Élise Martin\nDéveloppeuse Rust — migration vers Kubernetes",
+            "adaptedCoverLetter": "Madame, Monsieur,\n…\nÉlise Martin",
+            "highlights": [
+                { "text": "Kubernetes", "type": "emphasis" },
+                { "text": "migration vers", "type": "addition" },
+                { "text": "absent du CV" }
+            ]
+        })
+        .to_string();
+        let received = Arc::new(Mutex::new(Value::Null));
+        let app = Router::new().route(
+            "/v1/chat/completions",
+            post({
+                let received = received.clone();
+                let body = sse_body(&format!("```json\n{answer}\n```"));
+                move |Json(request): Json<Value>| async move {
+                    *received.lock() = request;
+                    ([("content-type", "text/event-stream")], body)
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let settings = LlmSettings {
+            endpoint: format!("http://{address}/v1/"),
+            model: "mock".into(),
+            ..LlmSettings::default()
+        };
+        let deltas = Arc::new(Mutex::new(Vec::new()));
+        let engine = LlmEngine::new(settings).with_progress({
+            let deltas = deltas.clone();
+            move |delta| deltas.lock().push(delta.to_string())
+        });
+        let profile: CandidateProfile = serde_json::from_value(json!({
+            "id": "p1", "fullName": "Élise Martin", "email": null, "phone": null, "summary": null,
+            "experiences": [], "skills": ["Rust"], "education": [], "projects": [], "languages": []
+        }))
+        .unwrap();
+        let offer = JobOffer {
+            id: "o1".into(),
+            title: "Ingénieur Rust".into(),
+            company: Some("Acme".into()),
+            description: "Nous cherchons…".into(),
+            location: None,
+            keywords: Some(vec!["rust".into()]),
+            language: None,
+            date_posted: None,
+            valid_through: None,
+            salary: None,
+            contact: None,
+        };
+        let input = EngineInput {
+            profile: &profile,
+            offer: &offer,
+            language: Language::De,
+        };
+
+        let result = engine.adapt(input).await.unwrap();
+        assert_eq!(result.adapted_cover_letter.signature, "Élise Martin");
+        assert_eq!(result.language, Some(Language::De));
+        let spans: Vec<(usize, usize, HighlightType)> = result
+            .highlights
+            .iter()
+            .map(|span| (span.start, span.end, span.highlight_type.clone()))
+            .collect();
+        assert!(matches!(
+            spans.as_slice(),
+            [
+                (33, 47, HighlightType::Addition),
+                (48, 58, HighlightType::Emphasis)
+            ]
+        ));
+        assert!(deltas.lock().len() > 10);
+
+        let request = received.lock().clone();
+        assert_eq!(request["model"], "mock");
+        assert_eq!(request["stream"], true);
+        let system = request["messages"][0]["content"].as_str().unwrap();
+        assert!(system.contains("Write every document in German."));
+        let user = request["messages"][1]["content"].as_str().unwrap();
+        assert!(user.starts_with("Job offer: Ingénieur Rust — Acme\nKeywords: rust"));
+    }
+
+    #[test]
+    fn rejects_answers_without_json() {
+        let error = parse_adaptation("Désolé, je ne peux pas.", Language::Fr).unwrap_err();
+        assert_eq!(error.key, "errors.llm.invalidResponse");
+    }
+}
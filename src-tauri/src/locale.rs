@@ -0,0 +1,250 @@
+use std::{collections::BTreeMap, collections::HashMap, fmt, str::FromStr};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::CandidateProfile;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Fr,
+    En,
+    De,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::Fr, Language::En, Language::De];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Fr => "fr",
+            Language::En => "en",
+            Language::De => "de",
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        // Accepte aussi les variantes régionales (« en-GB », « de_CH »).
+        let primary = value
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Language::ALL
+            .into_iter()
+            .find(|language| language.code() == primary)
+            .ok_or_else(|| format!("Langue non prise en charge : {value}"))
+    }
+}
+
+type Catalog = HashMap<String, String>;
+
+static CATALOGS: Lazy<HashMap<Language, Catalog>> = Lazy::new(|| {
+    let sources = [
+        (Language::Fr, include_str!("../locales/fr.json")),
+        (Language::En, include_str!("../locales/en.json")),
+        (Language::De, include_str!("../locales/de.json")),
+    ];
+    sources
+        .into_iter()
+        .map(|(language, source)| {
+            let tree: Value = serde_json::from_str(source)
+                .unwrap_or_else(|error| panic!("Catalogue {language} invalide: {error}"));
+            let mut catalog = Catalog::new();
+            flatten_catalog("", &tree, &mut catalog);
+            (language, catalog)
+        })
+        .collect()
+});
+
+fn flatten_catalog(prefix: &str, node: &Value, catalog: &mut Catalog) {
+    match node {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_catalog(&path, child, catalog);
+            }
+        }
+        Value::String(text) => {
+            catalog.insert(prefix.to_string(), text.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Cherche `key` dans le catalogue de `language`, puis en anglais et en français.
+pub fn lookup(language: Language, key: &str) -> Option<&'static str> {
+    [language, Language::En, Language::Fr]
+        .into_iter()
+        .find_map(|candidate| CATALOGS.get(&candidate)?.get(key))
+        .map(String::as_str)
+}
+
+/// Intitulés de sections envoyés aux exporteurs, indexés sans le préfixe `sections.`.
+pub fn section_labels(language: Language) -> BTreeMap<String, String> {
+    catalog_group(language, "sections.")
+}
+
+/// Gabarits de la lettre de motivation (`coverLetter.*`) envoyés au pipeline Python.
+pub fn cover_letter_templates(language: Language) -> BTreeMap<String, String> {
+    catalog_group(language, "coverLetter.")
+}
+
+/// Entrées dont la clé commence par `prefix`, sans lui ; le français complète les manques.
+fn catalog_group(language: Language, prefix: &str) -> BTreeMap<String, String> {
+    let mut group = BTreeMap::new();
+    for fallback in [Language::Fr, language] {
+        if let Some(catalog) = CATALOGS.get(&fallback) {
+            for (key, text) in catalog {
+                if let Some(name) = key.strip_prefix(prefix) {
+                    group.insert(name.to_string(), text.clone());
+                }
+            }
+        }
+    }
+    group
+}
+
+const STOPWORDS: [(Language, &[&str]); 3] = [
+    (
+        Language::Fr,
+        &[
+            "le", "la", "les", "des", "une", "et", "est", "pour", "dans", "avec", "sur", "vous",
+            "nous", "du", "au", "aux", "qui", "que", "votre", "poste",
+        ],
+    ),
+    (
+        Language::En,
+        &[
+            "the", "and", "is", "are", "for", "with", "you", "we", "our", "your", "of", "to", "in",
+            "will", "this", "that", "as", "an", "role", "team",
+        ],
+    ),
+    (
+        Language::De,
+        &[
+            "der", "die", "das", "und", "ist", "sind", "für", "mit", "sie", "wir", "ihre", "ein",
+            "eine", "zu", "im", "auf", "bei", "den", "dem", "unser",
+        ],
+    ),
+];
+
+/// Mot-outil dans l'une des langues prises en charge (`word` en minuscules).
+pub(crate) fn is_stopword(word: &str) -> bool {
+    STOPWORDS
+        .iter()
+        .any(|(_, stopwords)| stopwords.contains(&word))
+}
+
+/// Détection grossière de la langue d'un texte par fréquence de mots-outils.
+pub fn detect_language(text: &str) -> Option<Language> {
+    let mut scores: HashMap<Language, usize> = HashMap::new();
+    for word in text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+    {
+        let word = word.to_lowercase();
+        for (language, stopwords) in STOPWORDS {
+            if stopwords.contains(&word.as_str()) {
+                *scores.entry(language).or_default() += 1;
+            }
+        }
+    }
+
+    let (language, best) = scores
+        .iter()
+        .max_by_key(|(language, score)| (**score, std::cmp::Reverse(**language)))?;
+    let runner_up = scores
+        .iter()
+        .filter(|(other, _)| *other != language)
+        .map(|(_, score)| *score)
+        .max()
+        .unwrap_or(0);
+    (*best >= 3 && *best > runner_up).then_some(*language)
+}
+
+impl CandidateProfile {
+    /// Profil dont les champs traduisibles sont remplacés par leur version dans `language`.
+    ///
+    /// Les champs sans traduction gardent la valeur de base.
+    pub fn localized(&self, language: Language) -> CandidateProfile {
+        let mut localized = self.clone();
+        if self.language == Some(language) {
+            return localized;
+        }
+
+        if let Some(summary) = self.summary_translations.get(&language) {
+            localized.summary = Some(summary.clone());
+        }
+        for experience in &mut localized.experiences {
+            if let Some(role) = experience.role_translations.get(&language) {
+                experience.role = role.clone();
+            }
+            if let Some(achievements) = experience.achievement_translations.get(&language) {
+                experience.achievements = achievements.clone();
+            }
+        }
+        localized.language = Some(language);
+        localized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cover_letter_templates, detect_language, section_labels, Language};
+
+    #[test]
+    fn detects_offer_language() {
+        assert_eq!(
+            detect_language(
+                "Nous recherchons un développeur Rust pour rejoindre la équipe et le produit"
+            ),
+            Some(Language::Fr)
+        );
+        assert_eq!(
+            detect_language("We are looking for a Rust engineer to join our team and the platform"),
+            Some(Language::En)
+        );
+        assert_eq!(
+            detect_language("Wir suchen eine Entwicklerin für die Plattform und das Team im Haus"),
+            Some(Language::De)
+        );
+        assert_eq!(detect_language("Rust, Tokio, SQL"), None);
+    }
+
+    #[test]
+    fn parses_regional_codes() {
+        assert_eq!("en-GB".parse::<Language>(), Ok(Language::En));
+        assert_eq!("de_CH".parse::<Language>(), Ok(Language::De));
+        assert!("es".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn section_labels_are_localized() {
+        assert_eq!(section_labels(Language::En)["skills"], "Key skills");
+        assert_eq!(section_labels(Language::De)["coverLetter"], "Anschreiben");
+        assert_eq!(
+            cover_letter_templates(Language::En)["signoff"],
+            "Kind regards,"
+        );
+    }
+}
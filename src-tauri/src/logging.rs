@@ -0,0 +1,192 @@
+//! Journalisation structurée (`tracing`) vers la console et des fichiers tournants.
+//!
+//! Chaque ligne passe par `Redacting` avant d'atteindre un sink : e-mails, téléphones et
+//! noms des candidats connus sont masqués, quel que soit le module qui journalise.
+
+use std::{
+    collections::BTreeSet,
+    io::{self, Write},
+    path::Path,
+};
+
+use once_cell::sync::OnceCell;
+use parking_lot::{const_mutex, const_rwlock, Mutex, RwLock};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    fmt::{self, MakeWriter},
+    layer::SubscriberExt,
+    reload,
+    util::SubscriberInitExt,
+    EnvFilter, Registry,
+};
+
+use crate::anonymize::{looks_like_phone, EMAIL, PHONE};
+
+/// Variable d'environnement qui, si définie, remplace le niveau des préférences
+/// (syntaxe `EnvFilter`, ex. `CVGEN_LOG=cvgen::python_bridge=trace`).
+const LOG_ENV: &str = "CVGEN_LOG";
+const LOG_FILE_PREFIX: &str = "cvgen";
+const MAX_LOG_FILES: usize = 7;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn directive(self) -> String {
+        let level = match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        };
+        // Les dépendances (webview, runtime) restent à `warn` pour ne pas noyer nos journaux.
+        format!("warn,cvgen={level}")
+    }
+}
+
+static FILTER: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+/// Installe le subscriber global ; à appeler une seule fois, au démarrage.
+///
+/// Sans `log_dir`, seule la sortie d'erreur reçoit les journaux.
+pub fn init_logging(log_dir: Option<&Path>, level: LogLevel) -> anyhow::Result<()> {
+    let filter =
+        EnvFilter::try_from_env(LOG_ENV).unwrap_or_else(|_| EnvFilter::new(level.directive()));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let file_layer = match log_dir {
+        Some(dir) => Some({
+            // L'élagage des anciens fichiers échoue bruyamment si le dossier n'existe pas encore.
+            std::fs::create_dir_all(dir)?;
+            RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(LOG_FILE_PREFIX)
+                .filename_suffix("log")
+                .max_log_files(MAX_LOG_FILES)
+                .build(dir)?
+        }),
+        None => None,
+    }
+    .map(|appender| {
+        fmt::layer()
+            .with_ansi(false)
+            .with_writer(Redacting(appender))
+    });
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(Redacting(io::stderr)))
+        .with(file_layer)
+        .try_init()?;
+    let _ = FILTER.set(handle);
+    Ok(())
+}
+
+/// Applique le niveau des préférences, sauf si `CVGEN_LOG` l'impose.
+pub fn apply_log_level(level: LogLevel) {
+    if std::env::var_os(LOG_ENV).is_some() {
+        return;
+    }
+    if let Some(handle) = FILTER.get() {
+        if let Err(error) = handle.reload(EnvFilter::new(level.directive())) {
+            tracing::warn!(%error, "Impossible de changer le niveau de journalisation");
+        }
+    }
+}
+
+static KNOWN_NAMES: Mutex<BTreeSet<String>> = const_mutex(BTreeSet::new());
+static NAME_PATTERN: RwLock<Option<Regex>> = const_rwlock(None);
+
+/// Ajoute le nom d'un candidat (et ses parties) aux valeurs masquées dans les journaux.
+pub fn remember_name(full_name: &str) {
+    let mut known = KNOWN_NAMES.lock();
+    let before = known.len();
+    let full_name = full_name.trim();
+    if full_name.chars().count() >= 3 {
+        known.insert(full_name.to_string());
+    }
+    known.extend(
+        full_name
+            .split_whitespace()
+            .filter(|part| part.chars().count() >= 3)
+            .map(str::to_string),
+    );
+    if known.len() == before {
+        return;
+    }
+
+    let mut names: Vec<&String> = known.iter().collect();
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+    let alternatives: Vec<String> = names.iter().map(|name| regex::escape(name)).collect();
+    let pattern = Regex::new(&format!(r"(?i)\b(?:{})\b", alternatives.join("|"))).ok();
+    *NAME_PATTERN.write() = pattern;
+}
+
+fn redact(line: &str) -> String {
+    let line = EMAIL.replace_all(line, "<email>");
+    let line = PHONE.replace_all(&line, |found: &regex::Captures| {
+        if looks_like_phone(&found[0]) {
+            "<phone>".to_string()
+        } else {
+            found[0].to_string()
+        }
+    });
+    match NAME_PATTERN.read().as_ref() {
+        Some(names) => names.replace_all(&line, "<name>").into_owned(),
+        None => line.into_owned(),
+    }
+}
+
+/// Enveloppe d'un sink qui masque les données personnelles de chaque ligne formatée.
+struct Redacting<M>(M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacting<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // `fmt` écrit chaque événement d'un bloc : la ligne est complète ici.
+        self.0
+            .write_all(redact(&String::from_utf8_lossy(buf)).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redact, remember_name};
+
+    #[test]
+    fn masks_emails_phones_and_known_names() {
+        remember_name("Jeanne Dupont");
+        let line = "2026-10-19T08:00:00Z INFO payload={\"name\":\"Jeanne DUPONT\",\"email\":\"jeanne@example.com\",\"phone\":\"+33 6 12 34 56 78\"}";
+        let redacted = redact(line);
+        assert_eq!(
+            redacted,
+            "2026-10-19T08:00:00Z INFO payload={\"name\":\"<name>\",\"email\":\"<email>\",\"phone\":\"<phone>\"}"
+        );
+        assert_eq!(redact("période 2019 - 2021"), "période 2019 - 2021");
+    }
+}
@@ -0,0 +1,95 @@
+use std::{collections::BTreeMap, fmt};
+
+use parking_lot::{const_rwlock, RwLock};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use crate::locale::{lookup, Language};
+
+/// Langue des messages renvoyés par le backend, pilotée par `Settings::ui_language`.
+static UI_LANGUAGE: RwLock<Language> = const_rwlock(Language::Fr);
+
+pub fn ui_language() -> Language {
+    *UI_LANGUAGE.read()
+}
+
+pub fn apply_ui_language(language: Language) {
+    *UI_LANGUAGE.write() = language;
+}
+
+/// Erreur renvoyée par les commandes : clé de catalogue (`errors.*`) et ses arguments.
+///
+/// La sérialisation ajoute le texte rendu dans la langue courante, pour que le frontend
+/// puisse afficher `message` tel quel ou retraduire à partir de `key` et `args`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppError {
+    pub key: &'static str,
+    pub args: BTreeMap<&'static str, String>,
+}
+
+impl AppError {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            args: BTreeMap::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl fmt::Display) -> Self {
+        self.args.insert(name, value.to_string());
+        self
+    }
+
+    pub fn python(error: anyhow::Error) -> Self {
+        Self::new("errors.python").arg("details", error)
+    }
+
+    pub fn render(&self, language: Language) -> String {
+        let mut text = lookup(language, self.key).unwrap_or(self.key).to_string();
+        for (name, value) in &self.args {
+            text = text.replace(&format!("{{{name}}}"), value);
+        }
+        text
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(ui_language()))
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("AppError", 3)?;
+        error.serialize_field("key", self.key)?;
+        error.serialize_field("args", &self.args)?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AppError;
+    use crate::locale::Language;
+
+    #[test]
+    fn renders_arguments_in_each_language() {
+        let error = AppError::new("errors.profileNotFound").arg("id", "p1");
+        assert_eq!(error.render(Language::Fr), "Profil p1 introuvable");
+        assert_eq!(error.render(Language::En), "Profile p1 not found");
+        // Pas de catalogue d'erreurs allemand : repli sur l'anglais.
+        assert_eq!(error.render(Language::De), "Profile p1 not found");
+    }
+
+    #[test]
+    fn serializes_key_args_and_message() {
+        let error = AppError::new("errors.fs.write").arg("details", "disk full");
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["key"], "errors.fs.write");
+        assert_eq!(json["args"]["details"], "disk full");
+        assert!(json["message"].as_str().unwrap().contains("disk full"));
+    }
+}
@@ -1,5 +1,9 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::locale::Language;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Experience {
@@ -10,6 +14,10 @@ pub struct Experience {
     pub end_date: Option<String>,
     pub achievements: Vec<String>,
     pub technologies: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub role_translations: BTreeMap<Language, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub achievement_translations: BTreeMap<Language, Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +54,11 @@ pub struct CandidateProfile {
     pub education: Vec<Education>,
     pub projects: Vec<Project>,
     pub languages: Vec<LanguageLevel>,
+    /// Langue des champs de base ; les traductions ci-dessous les surchargent.
+    #[serde(default)]
+    pub language: Option<Language>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub summary_translations: BTreeMap<Language, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +68,25 @@ pub struct LanguageLevel {
     pub level: String,
 }
 
+/// Surcouche nommée appliquée à un profil de base (ex. « backend », « data »).
+///
+/// Les champs `None` ou vides laissent la valeur du profil de base intacte.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileVariant {
+    pub id: String,
+    pub profile_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub hidden_experience_ids: Vec<String>,
+    #[serde(default)]
+    pub experience_order: Vec<String>,
+    #[serde(default)]
+    pub skills: Option<Vec<String>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JobOffer {
@@ -64,6 +96,37 @@ pub struct JobOffer {
     pub description: String,
     pub location: Option<String>,
     pub keywords: Option<Vec<String>>,
+    #[serde(default)]
+    pub language: Option<Language>,
+    /// Date de publication, telle que fournie par la page (ISO 8601 en général).
+    #[serde(default)]
+    pub date_posted: Option<String>,
+    #[serde(default)]
+    pub valid_through: Option<String>,
+    #[serde(default)]
+    pub salary: Option<Salary>,
+    /// Interlocuteur (recruteur) quand l'offre arrive par e-mail.
+    #[serde(default)]
+    pub contact: Option<OfferContact>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfferContact {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Rémunération annoncée ; `text` conserve la mention libre quand elle n'est pas structurée.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Salary {
+    pub currency: Option<String>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Période : `HOUR`, `DAY`, `WEEK`, `MONTH` ou `YEAR` (schema.org).
+    pub unit: Option<String>,
+    pub text: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,6 +137,21 @@ pub struct HighlightSpan {
     pub highlight_type: HighlightType,
     pub start: usize,
     pub end: usize,
+    /// Mêmes positions en octets UTF-8.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utf8: Option<OffsetRange>,
+    /// Mêmes positions en unités UTF-16, pour indexer les chaînes JavaScript.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utf16: Option<OffsetRange>,
+    /// Texte d'origine supprimé : une suppression est de largeur nulle dans le CV adapté.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub removed_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffsetRange {
+    pub start: usize,
+    pub end: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,14 +160,140 @@ pub enum HighlightType {
     Addition,
     Removal,
     Emphasis,
+    /// Texte saisi par l'utilisateur (`update_adaptation`), protégé de la régénération.
+    #[serde(rename = "userEdit")]
+    UserEdit,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AdaptationResult {
+    /// Texte du CV, indexé par `highlights` : forme texte de `resume_document` s'il existe.
     pub adapted_resume: String,
-    pub adapted_cover_letter: String,
+    /// Absent pour un texte libre (Python, LLM) et des adaptations enregistrées avant l'arbre :
+    /// les exporteurs le relisent alors depuis le texte.
+    #[serde(default)]
+    pub resume_document: Option<ResumeDocument>,
+    /// Les adaptations enregistrées avant la lettre structurée la stockent en texte brut.
+    #[serde(deserialize_with = "crate::cover_letter::structured_or_text")]
+    pub adapted_cover_letter: CoverLetter,
     pub highlights: Vec<HighlightSpan>,
+    /// Surlignages de la lettre (retouches de l'utilisateur), sur sa forme texte.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cover_letter_highlights: Vec<HighlightSpan>,
+    #[serde(default)]
+    pub language: Option<Language>,
+    /// Offre visée ; absente des adaptations enregistrées avant qu'elle soit retenue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offer_id: Option<String>,
+    /// Retouché par l'utilisateur, y compris par une simple suppression qui ne laisse aucun
+    /// surlignage `userEdit`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub user_edited: bool,
+}
+
+/// CV adapté sous forme d'arbre : sections, entrées (expériences, formations), puces et
+/// mises en valeur. Les exporteurs le mettent en page ; le texte et les surlignages en
+/// dérivent (`ResumeDocument::render`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeDocument {
+    pub name: String,
+    pub sections: Vec<ResumeSection>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeSection {
+    /// Intitulé localisé ; le résumé n'en a pas.
+    pub heading: Option<String>,
+    pub blocks: Vec<ResumeBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ResumeBlock {
+    Paragraph {
+        text: RichText,
+    },
+    /// Liste en ligne (compétences, langues).
+    Tags {
+        items: Vec<RichText>,
+    },
+    /// Liste à puces hors entrée.
+    List {
+        items: Vec<RichText>,
+    },
+    Entry(ResumeEntry),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeEntry {
+    /// « Poste chez Entreprise », « Diplôme - École ».
+    pub title: RichText,
+    pub period: Option<String>,
+    #[serde(default)]
+    pub bullets: Vec<RichText>,
+    /// Ligne libellée sous l'entrée (« Technologies : … »).
+    #[serde(default)]
+    pub tags: Option<TagLine>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagLine {
+    pub label: String,
+    pub items: Vec<RichText>,
+}
+
+/// Texte découpé en segments, mis en valeur ou non.
+pub type RichText = Vec<Inline>;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Inline {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub emphasis: bool,
+}
+
+/// Lettre de motivation découpée pour la mise en page des exporteurs. La forme texte
+/// (`CoverLetter::to_text`) sert aux retouches et aux anciens enregistrements.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CoverLetter {
+    /// Coordonnées du candidat, une ligne chacune.
+    pub sender: Vec<String>,
+    /// Entreprise, interlocuteur, lieu.
+    pub recipient: Vec<String>,
+    pub date: Option<String>,
+    pub subject: Option<String>,
+    pub salutation: String,
+    pub paragraphs: Vec<String>,
+    /// Formule de politesse (« Cordialement, »).
+    pub closing: String,
+    pub signature: String,
+}
+
+impl AdaptationResult {
+    /// Vrai si le CV ou la lettre a été retouché par l'utilisateur ; les adaptations
+    /// enregistrées avant `user_edited` se reconnaissent à leurs surlignages `userEdit`.
+    pub fn has_user_edits(&self) -> bool {
+        self.user_edited
+            || self
+                .highlights
+                .iter()
+                .chain(&self.cover_letter_highlights)
+                .any(|span| matches!(span.highlight_type, HighlightType::UserEdit))
+    }
+
+    /// Arbre du CV ; relu depuis le texte pour les adaptations qui n'en ont pas.
+    pub fn document(&self) -> ResumeDocument {
+        self.resume_document
+            .clone()
+            .unwrap_or_else(|| ResumeDocument::from_text(&self.adapted_resume, &self.highlights))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,6 +304,22 @@ pub struct ExtractionPayload {
     pub warnings: Vec<String>,
 }
 
+/// Texte brut d'un document (PDF, DOCX, image), sans analyse de profil.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedText {
+    pub raw_text: String,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Pdf,
+    Docx,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportPayload {
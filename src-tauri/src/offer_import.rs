@@ -0,0 +1,871 @@
+//! Import d'offres depuis une page HTML enregistrée (ou son code source), un e-mail `.eml` ou un
+//! texte brut.
+//!
+//! Pour une page, ordre de priorité : données structurées schema.org `JobPosting` en JSON-LD,
+//! puis en microdonnées, et à défaut extraction du contenu principal (titre + texte).
+
+use std::{fs, str::FromStr};
+
+use mail_parser::{HeaderName, MessageParser, MimeHeaders};
+use scraper::{node::Node, ElementRef, Html, Selector};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{command, AppHandle, State};
+use tracing::{instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    file_import::{add_offer, extract_document_text},
+    locale::Language,
+    messages::AppError,
+    models::{JobOffer, OfferContact, Salary},
+    python_bridge::PythonBridge,
+    state::SharedState,
+    vault::VaultState,
+};
+
+/// Longueur minimale d'un bloc pour être retenu comme contenu principal.
+const MIN_CONTENT_LEN: usize = 200;
+/// Au-delà, la première ligne d'un texte brut est un paragraphe plutôt qu'un intitulé.
+const MAX_TEXT_TITLE_LEN: usize = 120;
+
+const SKIPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "nav", "header", "footer", "aside", "form",
+    "button", "svg",
+];
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "ul",
+    "ol",
+    "li",
+    "br",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "tr",
+    "table",
+    "blockquote",
+    "pre",
+    "dd",
+    "dt",
+];
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("sélecteur CSS valide")
+}
+
+/// Construit une offre (sans l'enregistrer) à partir du HTML d'une page d'annonce.
+pub fn offer_from_html(html: &str) -> Result<JobOffer, AppError> {
+    let document = Html::parse_document(html);
+    let posting = json_ld_posting(&document).or_else(|| microdata_posting(&document));
+
+    let mut offer = match posting {
+        Some(posting) => offer_from_posting(&posting),
+        None => readable_offer(&document),
+    };
+    if offer.title.is_empty() {
+        offer.title = page_title(&document).unwrap_or_default();
+    }
+    if offer.description.trim().is_empty() {
+        offer.description = main_content(&document).unwrap_or_default();
+    }
+    if offer.title.is_empty() && offer.description.is_empty() {
+        return Err(AppError::new("errors.offerHtml.notFound"));
+    }
+    Ok(offer)
+}
+
+fn offer_from_posting(posting: &Value) -> JobOffer {
+    JobOffer {
+        id: Uuid::new_v4().to_string(),
+        title: text_of(&posting["title"])
+            .or_else(|| text_of(&posting["name"]))
+            .unwrap_or_default(),
+        company: organization_name(&posting["hiringOrganization"]),
+        description: text_of(&posting["description"])
+            .map(|description| html_to_text(&description))
+            .unwrap_or_default(),
+        location: location(&posting["jobLocation"]),
+        keywords: None,
+        language: text_of(&posting["inLanguage"]).and_then(|code| Language::from_str(&code).ok()),
+        date_posted: text_of(&posting["datePosted"]),
+        valid_through: text_of(&posting["validThrough"]),
+        salary: salary(&posting["baseSalary"]),
+        contact: None,
+    }
+}
+
+fn readable_offer(document: &Html) -> JobOffer {
+    JobOffer {
+        id: Uuid::new_v4().to_string(),
+        title: page_title(document).unwrap_or_default(),
+        company: None,
+        description: main_content(document).unwrap_or_default(),
+        location: None,
+        keywords: None,
+        language: None,
+        date_posted: None,
+        valid_through: None,
+        salary: None,
+        contact: None,
+    }
+}
+
+/// Offre en texte brut : la première ligne non vide sert de titre.
+pub fn offer_from_text(text: &str) -> Result<JobOffer, AppError> {
+    let text = text.trim_start_matches('\u{feff}').trim();
+    let title = text
+        .lines()
+        .map(collapse_spaces)
+        .find(|line| !line.is_empty())
+        .ok_or_else(|| AppError::new("errors.offerText.empty"))?;
+    Ok(JobOffer {
+        id: Uuid::new_v4().to_string(),
+        title: title.chars().take(MAX_TEXT_TITLE_LEN).collect(),
+        company: None,
+        description: text.to_string(),
+        location: None,
+        keywords: None,
+        language: None,
+        date_posted: None,
+        valid_through: None,
+        salary: None,
+        contact: None,
+    })
+}
+
+// --- JSON-LD ---------------------------------------------------------------------------------
+
+fn json_ld_posting(document: &Html) -> Option<Value> {
+    document
+        .select(&selector(r#"script[type="application/ld+json"]"#))
+        .filter_map(|script| {
+            let raw = script.text().collect::<String>();
+            // Certains sites laissent des retours à la ligne bruts dans les chaînes.
+            serde_json::from_str::<Value>(raw.trim())
+                .or_else(|_| serde_json::from_str(&raw.replace(['\n', '\r', '\t'], " ")))
+                .ok()
+        })
+        .find_map(|value| find_posting(&value).cloned())
+}
+
+fn find_posting(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.iter().find_map(find_posting),
+        Value::Object(object) => {
+            if has_type(value, "JobPosting") {
+                return Some(value);
+            }
+            object.values().find_map(find_posting)
+        }
+        _ => None,
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    let matches = |candidate: &str| candidate == name || candidate.ends_with(&format!("/{name}"));
+    match &value["@type"] {
+        Value::String(kind) => matches(kind),
+        Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).any(matches),
+        _ => false,
+    }
+}
+
+// --- Microdonnées ----------------------------------------------------------------------------
+
+fn microdata_posting(document: &Html) -> Option<Value> {
+    document
+        .select(&selector(r#"[itemscope][itemtype*="JobPosting"]"#))
+        .next()
+        .map(microdata_item)
+}
+
+/// Convertit un `itemscope` en objet JSON-LD équivalent, pour réutiliser le même mappage.
+fn microdata_item(scope: ElementRef) -> Value {
+    let mut item = Map::new();
+    if let Some(kind) = scope.value().attr("itemtype") {
+        item.insert("@type".into(), Value::String(kind.to_string()));
+    }
+    for property in scope.select(&selector("[itemprop]")) {
+        if !owned_by(property, scope) {
+            continue;
+        }
+        let value = if property.value().attr("itemscope").is_some() {
+            microdata_item(property)
+        } else {
+            Value::String(property_value(property))
+        };
+        let names = property.value().attr("itemprop").unwrap_or_default();
+        for name in names.split_whitespace() {
+            match item.get_mut(name) {
+                Some(Value::Array(values)) => values.push(value.clone()),
+                Some(existing) => *existing = Value::Array(vec![existing.clone(), value.clone()]),
+                None => {
+                    item.insert(name.to_string(), value.clone());
+                }
+            }
+        }
+    }
+    Value::Object(item)
+}
+
+/// Vrai si `scope` est l'`itemscope` le plus proche au-dessus de `element`.
+fn owned_by(element: ElementRef, scope: ElementRef) -> bool {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|ancestor| ancestor.value().attr("itemscope").is_some())
+        .is_some_and(|ancestor| ancestor.id() == scope.id())
+}
+
+fn property_value(element: ElementRef) -> String {
+    let node = element.value();
+    let attribute = match node.name() {
+        "meta" => node.attr("content"),
+        "time" => node.attr("datetime"),
+        "a" | "link" => node.attr("href"),
+        "img" => node.attr("src"),
+        "data" | "meter" => node.attr("value"),
+        _ => node.attr("content"),
+    };
+    match attribute {
+        Some(value) => value.trim().to_string(),
+        None if node.name() == "div" || node.name() == "section" => block_text(element),
+        None => collapse_spaces(&element.text().collect::<String>()),
+    }
+}
+
+// --- Mappage schema.org ----------------------------------------------------------------------
+
+fn text_of(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(text) => text.trim().to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::Array(items) => return items.iter().find_map(text_of),
+        Value::Object(object) => return object.get("name").and_then(text_of),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+fn organization_name(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(object) => object.get("name").and_then(text_of),
+        other => text_of(other),
+    }
+}
+
+fn location(value: &Value) -> Option<String> {
+    let places: Vec<String> = match value {
+        Value::Array(items) => items.iter().filter_map(place).collect(),
+        other => place(other).into_iter().collect(),
+    };
+    (!places.is_empty()).then(|| places.join(" / "))
+}
+
+fn place(value: &Value) -> Option<String> {
+    let address = match value {
+        Value::Object(object) => object.get("address").unwrap_or(value),
+        other => return text_of(other),
+    };
+    if let Value::String(text) = address {
+        return Some(text.trim().to_string()).filter(|text| !text.is_empty());
+    }
+    let parts: Vec<String> = ["addressLocality", "addressRegion", "addressCountry"]
+        .iter()
+        .filter_map(|field| text_of(&address[*field]))
+        .collect();
+    if parts.is_empty() {
+        return text_of(&address["streetAddress"]).or_else(|| text_of(&value["name"]));
+    }
+    Some(parts.join(", "))
+}
+
+fn number_of(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.replace([' ', '\u{a0}', ','], "").parse().ok(),
+        _ => None,
+    }
+}
+
+fn salary(value: &Value) -> Option<Salary> {
+    match value {
+        Value::Null => None,
+        Value::Object(_) => {
+            let amount = &value["value"];
+            let (min, max, unit) = match amount {
+                Value::Object(_) => {
+                    let exact = number_of(&amount["value"]);
+                    (
+                        number_of(&amount["minValue"]).or(exact),
+                        number_of(&amount["maxValue"]).or(exact),
+                        text_of(&amount["unitText"]),
+                    )
+                }
+                other => (number_of(other), number_of(other), None),
+            };
+            let salary = Salary {
+                currency: text_of(&value["currency"]),
+                min,
+                max,
+                unit: unit.or_else(|| text_of(&value["unitText"])),
+                text: None,
+            };
+            (salary != Salary::default()).then_some(salary)
+        }
+        other => text_of(other).map(|text| Salary {
+            text: Some(text),
+            ..Salary::default()
+        }),
+    }
+}
+
+// --- Contenu principal -----------------------------------------------------------------------
+
+fn page_title(document: &Html) -> Option<String> {
+    let meta = document
+        .select(&selector(r#"meta[property="og:title"]"#))
+        .find_map(|meta| meta.value().attr("content").map(collapse_spaces));
+    meta.or_else(|| first_text(document, "h1"))
+        .or_else(|| first_text(document, "title"))
+        .filter(|title| !title.is_empty())
+}
+
+fn first_text(document: &Html, css: &str) -> Option<String> {
+    document
+        .select(&selector(css))
+        .map(|element| collapse_spaces(&element.text().collect::<String>()))
+        .find(|text| !text.is_empty())
+}
+
+/// Bloc au texte le plus dense : `article`/`main` s'ils sont assez fournis, sinon le conteneur
+/// dont les paragraphes pèsent le plus une fois les liens (menus, listes d'offres) déduits.
+fn main_content(document: &Html) -> Option<String> {
+    let landmark = document
+        .select(&selector(r#"article, main, [role="main"]"#))
+        .map(block_text)
+        .filter(|text| text.chars().count() >= MIN_CONTENT_LEN)
+        .max_by_key(|text| text.chars().count());
+    if landmark.is_some() {
+        return landmark;
+    }
+
+    let paragraphs = selector("p, li");
+    let links = selector("a");
+    document
+        .select(&selector("div, section, td"))
+        .map(|candidate| {
+            let text_len: usize = candidate
+                .select(&paragraphs)
+                .map(|paragraph| paragraph.text().map(str::len).sum::<usize>())
+                .sum();
+            let link_len: usize = candidate
+                .select(&links)
+                .map(|link| link.text().map(str::len).sum::<usize>())
+                .sum();
+            (text_len.saturating_sub(link_len * 2), candidate)
+        })
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, candidate)| block_text(candidate))
+        .filter(|text| !text.is_empty())
+}
+
+/// Texte d'un fragment HTML (ex. `description` JSON-LD), paragraphes conservés.
+fn html_to_text(html: &str) -> String {
+    if !html.contains('<') {
+        return html.trim().to_string();
+    }
+    let fragment = Html::parse_fragment(html);
+    block_text(fragment.root_element())
+}
+
+fn block_text(element: ElementRef) -> String {
+    let mut raw = String::new();
+    push_text(element, &mut raw);
+    let lines: Vec<String> = raw
+        .lines()
+        .map(collapse_spaces)
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines.join("\n")
+}
+
+fn push_text(element: ElementRef, out: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(node) => {
+                let name = node.name();
+                if SKIPPED_TAGS.contains(&name) {
+                    continue;
+                }
+                let block = BLOCK_TAGS.contains(&name);
+                if block {
+                    out.push('\n');
+                }
+                if name == "li" {
+                    out.push_str("- ");
+                }
+                if let Some(child) = ElementRef::wrap(child) {
+                    push_text(child, out);
+                }
+                if block {
+                    out.push('\n');
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collapse_spaces(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// --- E-mails (.eml) --------------------------------------------------------------------------
+
+/// Domaines de messageries et de jobboards : l'expéditeur n'y désigne pas l'employeur.
+const GENERIC_MAIL_DOMAINS: &[&str] = &[
+    "gmail.com",
+    "googlemail.com",
+    "outlook.com",
+    "outlook.fr",
+    "hotmail.com",
+    "hotmail.fr",
+    "live.com",
+    "live.fr",
+    "yahoo.com",
+    "yahoo.fr",
+    "icloud.com",
+    "me.com",
+    "gmx.de",
+    "gmx.net",
+    "web.de",
+    "orange.fr",
+    "wanadoo.fr",
+    "free.fr",
+    "laposte.net",
+    "proton.me",
+    "protonmail.com",
+    "linkedin.com",
+    "indeed.com",
+    "welcometothejungle.com",
+    "stepstone.de",
+];
+
+/// Préfixes de réponse et de transfert retirés de l'objet (FR, EN, DE).
+const SUBJECT_PREFIXES: &[&str] = &["re:", "fwd:", "fw:", "tr:", "wg:", "aw:"];
+
+/// Pièce jointe PDF/DOCX décrivant le poste, dont le texte sera extrait par Python.
+#[derive(Debug)]
+struct JobAttachment {
+    name: String,
+    extension: &'static str,
+    data: Vec<u8>,
+}
+
+/// Offre tirée d'un e-mail ; `warnings` signale les pièces jointes qui n'ont pas pu être lues.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailOffer {
+    pub offer: JobOffer,
+    pub warnings: Vec<String>,
+}
+
+/// Construit une offre à partir d'un e-mail : objet → titre, en-têtes → entreprise et contact,
+/// corps texte (ou HTML) → description, complétée du texte des fiches de poste jointes. Une
+/// pièce jointe illisible est ignorée avec un avertissement.
+pub async fn offer_from_email(bridge: &PythonBridge, raw: &[u8]) -> Result<EmailOffer, AppError> {
+    let (mut offer, attachments) = parse_email(raw)?;
+    let mut warnings = Vec::new();
+    for attachment in attachments {
+        let text = match attachment_text(bridge, &attachment).await {
+            Ok(text) => text,
+            Err(error) => {
+                warn!(key = error.key, "Pièce jointe d'e-mail ignorée");
+                warnings.push(
+                    AppError::new("errors.offerEml.attachmentSkipped")
+                        .arg("name", &attachment.name)
+                        .arg("details", error)
+                        .to_string(),
+                );
+                continue;
+            }
+        };
+        if !text.trim().is_empty() {
+            if !offer.description.is_empty() {
+                offer.description.push_str("\n\n");
+            }
+            offer
+                .description
+                .push_str(&format!("{}\n{}", attachment.name, text.trim()));
+        }
+    }
+    if offer.title.is_empty() && offer.description.is_empty() {
+        return Err(AppError::new("errors.offerEml.empty"));
+    }
+    Ok(EmailOffer { offer, warnings })
+}
+
+async fn attachment_text(
+    bridge: &PythonBridge,
+    attachment: &JobAttachment,
+) -> Result<String, AppError> {
+    let path =
+        std::env::temp_dir().join(format!("cvgen-{}.{}", Uuid::new_v4(), attachment.extension));
+    tokio::fs::write(&path, &attachment.data)
+        .await
+        .map_err(|error| AppError::new("errors.offerEml.attachment").arg("details", error))?;
+    let extracted = extract_document_text(bridge, &path).await;
+    tokio::fs::remove_file(&path).await.ok();
+    Ok(extracted?.raw_text)
+}
+
+fn parse_email(raw: &[u8]) -> Result<(JobOffer, Vec<JobAttachment>), AppError> {
+    let message = MessageParser::default()
+        .parse(raw)
+        .ok_or_else(|| AppError::new("errors.offerEml.invalid"))?;
+
+    let sender = message
+        .reply_to()
+        .or_else(|| message.from())
+        .and_then(|address| address.first());
+    let contact = sender
+        .map(|sender| OfferContact {
+            name: sender
+                .name()
+                .map(collapse_spaces)
+                .filter(|name| !name.is_empty()),
+            email: sender.address().map(|email| email.trim().to_lowercase()),
+        })
+        .filter(|contact| *contact != OfferContact::default());
+    let company = message
+        .header(HeaderName::Organization)
+        .and_then(|value| value.as_text())
+        .map(collapse_spaces)
+        .filter(|company| !company.is_empty())
+        .or_else(|| company_from_email(contact.as_ref()?.email.as_deref()?));
+
+    let description = match message.text_part(0) {
+        Some(part) if !part.is_text_html() => {
+            part.text_contents().unwrap_or_default().trim().to_string()
+        }
+        _ => message
+            .body_html(0)
+            .map(|html| html_to_text(&html))
+            .unwrap_or_default(),
+    };
+
+    let attachments = message
+        .attachments()
+        .filter_map(|part| {
+            let name = part.attachment_name().unwrap_or_default().to_string();
+            let extension = if part.is_content_type("application", "pdf")
+                || name.to_lowercase().ends_with(".pdf")
+            {
+                "pdf"
+            } else if part.is_content_type(
+                "application",
+                "vnd.openxmlformats-officedocument.wordprocessingml.document",
+            ) || name.to_lowercase().ends_with(".docx")
+            {
+                "docx"
+            } else {
+                return None;
+            };
+            Some(JobAttachment {
+                name,
+                extension,
+                data: part.contents().to_vec(),
+            })
+        })
+        .collect();
+
+    let offer = JobOffer {
+        id: Uuid::new_v4().to_string(),
+        title: message.subject().map(clean_subject).unwrap_or_default(),
+        company,
+        description,
+        location: None,
+        keywords: None,
+        language: None,
+        date_posted: message.date().map(|date| date.to_rfc3339()),
+        valid_through: None,
+        salary: None,
+        contact,
+    };
+    Ok((offer, attachments))
+}
+
+fn clean_subject(subject: &str) -> String {
+    let mut subject = subject.trim();
+    while let Some(prefix) = SUBJECT_PREFIXES.iter().find(|prefix| {
+        subject
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    }) {
+        subject = subject[prefix.len()..].trim_start();
+    }
+    collapse_spaces(subject)
+}
+
+/// `recrutement@acme-robotics.fr` → `Acme-robotics` ; rien pour une messagerie grand public.
+fn company_from_email(email: &str) -> Option<String> {
+    let domain = email.rsplit_once('@')?.1.to_lowercase();
+    if GENERIC_MAIL_DOMAINS.contains(&domain.as_str()) {
+        return None;
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    let name = labels.len().checked_sub(2).map(|index| labels[index])?;
+    let mut chars = name.chars();
+    let first = chars.next()?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
+#[command]
+#[instrument(skip_all)]
+pub fn import_offer_html(
+    html: Option<String>,
+    file_path: Option<String>,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+) -> Result<JobOffer, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let html = match (html, file_path) {
+        (Some(html), _) => html,
+        (None, Some(path)) => {
+            let bytes = fs::read(&path)
+                .map_err(|error| AppError::new("errors.offerHtml.read").arg("details", error))?;
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        (None, None) => return Err(AppError::new("errors.offerHtml.missingInput")),
+    };
+    let offer = offer_from_html(&html)?;
+    Ok(add_offer(&state, offer))
+}
+
+#[command]
+#[instrument(skip_all)]
+pub async fn import_offer_eml(
+    file_path: String,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+    app: AppHandle,
+) -> Result<EmailOffer, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let raw = tokio::fs::read(&file_path)
+        .await
+        .map_err(|error| AppError::new("errors.offerEml.read").arg("details", error))?;
+    let bridge = PythonBridge::from_app(&app).map_err(AppError::python)?;
+    let email = offer_from_email(&bridge, &raw).await?;
+    Ok(EmailOffer {
+        offer: add_offer(&state, email.offer),
+        warnings: email.warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{offer_from_email, offer_from_html, parse_email};
+    use crate::python_bridge::PythonBridge;
+
+    #[test]
+    fn reads_json_ld_job_posting() {
+        let html = r#"<html><head><script type="application/ld+json">
+        {"@context": "https://schema.org", "@graph": [{"@type": "WebPage"}, {
+          "@type": "JobPosting",
+          "title": "Développeuse Rust",
+          "description": "<p>Rejoignez l'équipe.</p><ul><li>Rust</li><li>Tokio</li></ul>",
+          "datePosted": "2026-10-01",
+          "validThrough": "2026-11-30T00:00",
+          "hiringOrganization": {"@type": "Organization", "name": "Acme"},
+          "jobLocation": {"@type": "Place", "address": {"addressLocality": "Lyon", "addressCountry": "FR"}},
+          "baseSalary": {"@type": "MonetaryAmount", "currency": "EUR",
+            "value": {"@type": "QuantitativeValue", "minValue": 50000, "maxValue": 60000, "unitText": "YEAR"}}
+        }]}
+        </script></head><body><h1>Ignoré</h1></body></html>"#;
+
+        let offer = offer_from_html(html).unwrap();
+        assert_eq!(offer.title, "Développeuse Rust");
+        assert_eq!(offer.company.as_deref(), Some("Acme"));
+        assert_eq!(offer.location.as_deref(), Some("Lyon, FR"));
+        assert_eq!(offer.description, "Rejoignez l'équipe.\n- Rust\n- Tokio");
+        assert_eq!(offer.date_posted.as_deref(), Some("2026-10-01"));
+        assert_eq!(offer.valid_through.as_deref(), Some("2026-11-30T00:00"));
+        let salary = offer.salary.unwrap();
+        assert_eq!((salary.min, salary.max), (Some(50000.0), Some(60000.0)));
+        assert_eq!(salary.currency.as_deref(), Some("EUR"));
+        assert_eq!(salary.unit.as_deref(), Some("YEAR"));
+    }
+
+    #[test]
+    fn reads_microdata_job_posting() {
+        let html = r#"<div itemscope itemtype="https://schema.org/JobPosting">
+          <h2 itemprop="title">Data engineer</h2>
+          <span itemprop="hiringOrganization" itemscope itemtype="https://schema.org/Organization">
+            <span itemprop="name">Globex</span></span>
+          <div itemprop="jobLocation" itemscope itemtype="https://schema.org/Place">
+            <div itemprop="address" itemscope itemtype="https://schema.org/PostalAddress">
+              <span itemprop="addressLocality">Berlin</span></div></div>
+          <time itemprop="datePosted" datetime="2026-09-15">15 sept.</time>
+          <span itemprop="baseSalary">65 k€ brut annuel</span>
+          <div itemprop="description"><p>Pipelines Spark.</p></div>
+        </div>"#;
+
+        let offer = offer_from_html(html).unwrap();
+        assert_eq!(offer.title, "Data engineer");
+        assert_eq!(offer.company.as_deref(), Some("Globex"));
+        assert_eq!(offer.location.as_deref(), Some("Berlin"));
+        assert_eq!(offer.date_posted.as_deref(), Some("2026-09-15"));
+        assert_eq!(offer.description, "Pipelines Spark.");
+        assert_eq!(
+            offer.salary.unwrap().text.as_deref(),
+            Some("65 k€ brut annuel")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_main_content() {
+        let body = "Nous recherchons une personne pour maintenir nos services. ".repeat(5);
+        let html = format!(
+            r#"<html><head><title>Offre - Jobboard</title></head><body>
+            <nav><a href="/">Accueil</a><a href="/jobs">Offres</a></nav>
+            <div class="sidebar"><ul><li><a href="/1">Autre offre</a></li></ul></div>
+            <div class="content"><h1>Ingénieur SRE</h1><p>{body}</p><p>Astreintes partagées.</p></div>
+            <footer>Mentions légales</footer></body></html>"#
+        );
+
+        let offer = offer_from_html(&html).unwrap();
+        assert_eq!(offer.title, "Ingénieur SRE");
+        assert!(offer
+            .description
+            .starts_with("Ingénieur SRE\nNous recherchons"));
+        assert!(offer.description.ends_with("Astreintes partagées."));
+        assert!(!offer.description.contains("Autre offre"));
+        assert!(offer.salary.is_none());
+    }
+
+    #[test]
+    fn reads_multipart_email_with_attachment() {
+        let eml = concat!(
+            "From: =?UTF-8?Q?Clara_B=C3=A9ranger?= <Clara.Beranger@acme-robotics.fr>\r\n",
+            "Organization: Acme Robotics\r\n",
+            "Subject: TR: Re: =?UTF-8?B?RMOpdmVsb3BwZXVyIFJ1c3Q=?=\r\n",
+            "Date: Mon, 12 Oct 2026 09:30:00 +0200\r\n",
+            "MIME-Version: 1.0\r\n",
+            "Content-Type: multipart/mixed; boundary=\"outer\"\r\n",
+            "\r\n",
+            "--outer\r\n",
+            "Content-Type: multipart/alternative; boundary=\"inner\"\r\n",
+            "\r\n",
+            "--inner\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "Content-Transfer-Encoding: quoted-printable\r\n",
+            "\r\n",
+            "Bonjour,\r\n",
+            "Nous recrutons un d=C3=A9veloppeur Rust =C3=A0 Lyon.\r\n",
+            "--inner\r\n",
+            "Content-Type: text/html; charset=utf-8\r\n",
+            "\r\n",
+            "<p>Bonjour,</p><p>Version HTML</p>\r\n",
+            "--inner--\r\n",
+            "--outer\r\n",
+            "Content-Type: application/pdf; name=\"fiche-poste.pdf\"\r\n",
+            "Content-Disposition: attachment; filename=\"fiche-poste.pdf\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "JVBERi0xLjQK\r\n",
+            "--outer\r\n",
+            "Content-Type: image/png; name=\"logo.png\"\r\n",
+            "Content-Disposition: inline; filename=\"logo.png\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "iVBORw0KGgo=\r\n",
+            "--outer--\r\n"
+        );
+
+        let (offer, attachments) = parse_email(eml.as_bytes()).unwrap();
+        assert_eq!(offer.title, "Développeur Rust");
+        assert_eq!(offer.company.as_deref(), Some("Acme Robotics"));
+        assert_eq!(
+            offer.description,
+            "Bonjour,\r\nNous recrutons un développeur Rust à Lyon."
+        );
+        let contact = offer.contact.unwrap();
+        assert_eq!(contact.name.as_deref(), Some("Clara Béranger"));
+        assert_eq!(
+            contact.email.as_deref(),
+            Some("clara.beranger@acme-robotics.fr")
+        );
+        assert!(offer
+            .date_posted
+            .unwrap()
+            .starts_with("2026-10-12T09:30:00"));
+
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].name, "fiche-poste.pdf");
+        assert_eq!(attachments[0].extension, "pdf");
+        assert_eq!(attachments[0].data, b"%PDF-1.4\n");
+    }
+
+    #[test]
+    fn reads_html_only_email() {
+        let eml = concat!(
+            "From: jobs@globex.de\r\n",
+            "Subject: Fwd: Data engineer\r\n",
+            "Content-Type: text/html; charset=utf-8\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "PHA+UGlwZWxpbmVzIFNwYXJrLjwvcD48dWw+PGxpPkthZmthPC9saT48L3VsPg==\r\n"
+        );
+
+        let (offer, attachments) = parse_email(eml.as_bytes()).unwrap();
+        assert_eq!(offer.title, "Data engineer");
+        assert_eq!(offer.company.as_deref(), Some("Globex"));
+        assert_eq!(offer.description, "Pipelines Spark.\n- Kafka");
+        assert!(attachments.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unreadable_attachment_keeps_the_body() {
+        let eml = concat!(
+            "From: rh@initech.fr\r\n",
+            "Subject: Administrateur systèmes\r\n",
+            "MIME-Version: 1.0\r\n",
+            "Content-Type: multipart/mixed; boundary=\"b\"\r\n",
+            "\r\n",
+            "--b\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "\r\n",
+            "Poste basé à Nantes.\r\n",
+            "--b\r\n",
+            "Content-Type: application/pdf; name=\"fiche.pdf\"\r\n",
+            "Content-Disposition: attachment; filename=\"fiche.pdf\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "JVBERi0xLjQK\r\n",
+            "--b--\r\n"
+        );
+        // Script introuvable : l'extraction de la pièce jointe échoue.
+        let bridge = PythonBridge::new(
+            PathBuf::from("/nonexistent/cvgen/main.py"),
+            Some("/nonexistent/python3"),
+        );
+
+        let email = offer_from_email(&bridge, eml.as_bytes()).await.unwrap();
+        assert_eq!(email.offer.title, "Administrateur systèmes");
+        assert_eq!(email.offer.description, "Poste basé à Nantes.");
+        assert_eq!(email.warnings.len(), 1);
+        assert!(email.warnings[0].contains("fiche.pdf"));
+    }
+}
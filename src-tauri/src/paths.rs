@@ -0,0 +1,68 @@
+//! Répertoires de l'application, partagés par l'interface Tauri et le CLI.
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use tauri::AppHandle;
+
+/// Doit rester identique à `tauri.bundle.identifier` (`tauri.conf.json`).
+pub const APP_IDENTIFIER: &str = "com.cvgen.desktop";
+
+#[derive(Debug, Clone)]
+pub struct AppPaths {
+    pub config_dir: PathBuf,
+    pub data_dir: PathBuf,
+    pub log_dir: PathBuf,
+}
+
+impl AppPaths {
+    pub fn from_app(app: &AppHandle) -> anyhow::Result<Self> {
+        let resolver = app.path_resolver();
+        Ok(Self {
+            config_dir: resolver.app_config_dir().ok_or_else(|| {
+                anyhow!("Impossible de déterminer le répertoire de configuration")
+            })?,
+            data_dir: resolver
+                .app_data_dir()
+                .ok_or_else(|| anyhow!("Impossible de déterminer le répertoire des données"))?,
+            log_dir: resolver
+                .app_log_dir()
+                .ok_or_else(|| anyhow!("Impossible de déterminer le répertoire des journaux"))?,
+        })
+    }
+
+    /// Mêmes emplacements que ceux résolus par Tauri, sans fenêtre ni `AppHandle`.
+    pub fn headless() -> anyhow::Result<Self> {
+        let config_dir = dirs::config_dir()
+            .ok_or_else(|| anyhow!("Impossible de déterminer le répertoire de configuration"))?
+            .join(APP_IDENTIFIER);
+        let data_dir = dirs::data_dir()
+            .ok_or_else(|| anyhow!("Impossible de déterminer le répertoire des données"))?
+            .join(APP_IDENTIFIER);
+        #[cfg(target_os = "macos")]
+        let log_dir = dirs::home_dir()
+            .ok_or_else(|| anyhow!("Impossible de déterminer le répertoire des journaux"))?
+            .join("Library/Logs")
+            .join(APP_IDENTIFIER);
+        #[cfg(not(target_os = "macos"))]
+        let log_dir = config_dir.join("logs");
+
+        Ok(Self {
+            config_dir,
+            data_dir,
+            log_dir,
+        })
+    }
+
+    pub fn settings_file(&self) -> PathBuf {
+        self.config_dir.join("settings.json")
+    }
+
+    pub fn vault_file(&self) -> PathBuf {
+        self.config_dir.join("vault.json")
+    }
+
+    pub fn storage_file(&self) -> PathBuf {
+        self.data_dir.join("storage.json")
+    }
+}
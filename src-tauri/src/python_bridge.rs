@@ -1,6 +1,8 @@
 use std::{
     env,
+    ffi::OsStr,
     path::{Path, PathBuf},
+    time::Instant,
 };
 
 use anyhow::{anyhow, Context, Result};
@@ -8,27 +10,79 @@ use serde::de::DeserializeOwned;
 use serde_json::Value;
 use tauri::AppHandle;
 use tokio::{fs, process::Command};
+use tracing::{debug, info, instrument, warn};
 use uuid::Uuid;
 
-pub async fn call_python<T>(app: &AppHandle, verb: &str, payload: Value) -> Result<T>
+use crate::settings::current_settings;
+
+/// Script `python/main.py` et interpréteur résolus, prêts à être appelés.
+#[derive(Debug, Clone)]
+pub struct PythonBridge {
+    python_cmd: PathBuf,
+    script_path: PathBuf,
+}
+
+impl PythonBridge {
+    /// `python_command` (préférences) prime sur `PYTHON_CMD` et la détection d'un `.venv`.
+    pub fn new(script_path: PathBuf, python_command: Option<&str>) -> Self {
+        let python_cmd = python_command
+            .and_then(|cmd| resolve_env_python_cmd(cmd, &script_path))
+            .unwrap_or_else(|| resolve_python_cmd(&script_path));
+        Self {
+            python_cmd,
+            script_path,
+        }
+    }
+
+    pub fn from_app(app: &AppHandle) -> Result<Self> {
+        let script_path = resolve_script(app)?;
+        Ok(Self::new(
+            script_path,
+            current_settings(app).python_command.as_deref(),
+        ))
+    }
+
+    /// Hors Tauri : `CVGEN_PYTHON_SCRIPT`, puis `python/main.py` à côté de l'exécutable,
+    /// puis dans le répertoire courant.
+    pub fn headless(python_command: Option<&str>) -> Self {
+        let script_path = env::var_os("CVGEN_PYTHON_SCRIPT")
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::current_exe()
+                    .ok()?
+                    .parent()
+                    .map(|dir| dir.join("python/main.py"))
+                    .filter(|path| path.exists())
+            })
+            .unwrap_or_else(|| PathBuf::from("python/main.py"));
+        Self::new(script_path, python_command)
+    }
+
+    pub async fn call<T>(&self, verb: &str, payload: Value) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        call_python(&self.python_cmd, &self.script_path, verb, payload).await
+    }
+}
+
+#[instrument(name = "python_bridge", skip(python_cmd, script_path, payload))]
+async fn call_python<T>(
+    python_cmd: &Path,
+    script_path: &Path,
+    verb: &str,
+    payload: Value,
+) -> Result<T>
 where
     T: DeserializeOwned,
 {
-    let script_path = resolve_script(app)?;
-    let python_cmd = resolve_python_cmd(&script_path);
     let input_path = write_payload(&payload).await?;
 
-    println!(
-        "[python_bridge] invoking {:?} {:?} --input {:?}",
-        python_cmd, script_path, input_path
-    );
-    println!(
-        "[python_bridge] payload fragment: {}",
-        truncate_payload(&payload)
-    );
+    debug!(python = ?python_cmd, script = ?script_path, input = ?input_path, "Appel du script Python");
 
-    let output = Command::new(&python_cmd)
-        .arg(&script_path)
+    let started = Instant::now();
+    let output = Command::new(python_cmd)
+        .arg(script_path)
         .arg(verb)
         .arg(&input_path)
         .output()
@@ -37,18 +91,23 @@ where
 
     fs::remove_file(&input_path).await.ok();
 
-    println!(
-        "[python_bridge] status: {}",
-        output
+    info!(
+        status = output
             .status
             .code()
-            .map_or_else(|| "signal".to_string(), |c| c.to_string())
+            .map_or_else(|| "signal".to_string(), |c| c.to_string()),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Script Python terminé"
     );
-    if !output.stdout.is_empty() {
-        println!("[python_bridge] stdout: {}", truncate_bytes(&output.stdout));
-    }
+    // La charge utile et la réponse contiennent le CV (nom, e-mail, téléphone) : seule leur
+    // taille est journalisée, le nom n'étant connu du masquage qu'après l'import.
+    debug!(stdout_bytes = output.stdout.len(), "Réponse Python");
     if !output.stderr.is_empty() {
-        println!("[python_bridge] stderr: {}", truncate_bytes(&output.stderr));
+        if output.status.success() {
+            debug!(stderr = %truncate_bytes(&output.stderr));
+        } else {
+            warn!(stderr = %truncate_bytes(&output.stderr));
+        }
     }
 
     if !output.status.success() {
@@ -58,7 +117,7 @@ where
 
     let stdout = String::from_utf8(output.stdout)?;
     let result: T = serde_json::from_str(&stdout)
-        .with_context(|| format!("Réponse Python invalide : {stdout}"))?;
+        .with_context(|| format!("Réponse Python invalide ({} octets)", stdout.len()))?;
 
     Ok(result)
 }
@@ -69,9 +128,9 @@ fn resolve_python_cmd(script_path: &Path) -> PathBuf {
             if let Some(path) = resolve_env_python_cmd(&cmd, script_path) {
                 return path;
             } else {
-                println!(
-                    "[python_bridge] PYTHON_CMD {:?} introuvable, tentative de détection automatique",
-                    cmd
+                warn!(
+                    python_cmd = %cmd,
+                    "PYTHON_CMD introuvable, tentative de détection automatique"
                 );
             }
         }
@@ -162,12 +221,62 @@ fn resolve_env_python_cmd(cmd: &str, script_path: &Path) -> Option<PathBuf> {
         }
     }
 
+    // Nom nu (« python3 ») : cherché dans le PATH, comme le ferait un shell.
+    if candidate.components().count() == 1 {
+        return env::var_os("PATH").and_then(|path| find_on_path(candidate, &path));
+    }
+
     None
 }
 
+fn find_on_path(cmd: &Path, path: &OsStr) -> Option<PathBuf> {
+    let names = if cfg!(windows) && cmd.extension().is_none() {
+        vec![cmd.to_path_buf(), cmd.with_extension("exe")]
+    } else {
+        vec![cmd.to_path_buf()]
+    };
+    env::split_paths(path)
+        .filter(|dir| dir.is_absolute())
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+fn truncate_bytes(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    truncate_str(&text)
+}
+
+fn truncate_str(text: &str) -> String {
+    const LIMIT: usize = 400;
+    if text.len() <= LIMIT {
+        return text.to_string();
+    }
+
+    let mut end = LIMIT;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}…", &text[..end])
+}
+
+fn resolve_script(app: &AppHandle) -> Result<PathBuf> {
+    app.path_resolver()
+        .resolve_resource("python/main.py")
+        .or_else(|| Some(PathBuf::from("python/main.py")))
+        .context("Impossible de trouver le script python/main.py")
+}
+
+async fn write_payload(payload: &Value) -> Result<PathBuf> {
+    let filename = format!("cvgen-{}.json", Uuid::new_v4());
+    let path = std::env::temp_dir().join(filename);
+    fs::write(&path, serde_json::to_vec(payload)?).await?;
+    Ok(path)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::resolve_python_cmd;
+    use super::{find_on_path, resolve_python_cmd};
     use std::path::{Path, PathBuf};
 
     fn project_venv_python() -> Option<PathBuf> {
@@ -201,41 +310,19 @@ mod tests {
         assert!(env_resolved.is_absolute());
         assert_eq!(env_resolved, expected);
     }
-}
 
-fn truncate_bytes(bytes: &[u8]) -> String {
-    let text = String::from_utf8_lossy(bytes);
-    truncate_str(&text)
-}
-
-fn truncate_payload(payload: &Value) -> String {
-    truncate_str(&payload.to_string())
-}
-
-fn truncate_str(text: &str) -> String {
-    const LIMIT: usize = 400;
-    if text.len() <= LIMIT {
-        return text.to_string();
-    }
-
-    let mut end = LIMIT;
-    while !text.is_char_boundary(end) {
-        end -= 1;
+    #[test]
+    fn bare_command_is_found_on_path() {
+        let bin = std::env::temp_dir().join(format!("cvgen-path-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("python3"), "").unwrap();
+        let path = std::env::join_paths([Path::new("/nonexistent/cvgen"), &bin]).unwrap();
+
+        assert_eq!(
+            find_on_path(Path::new("python3"), &path),
+            Some(bin.join("python3"))
+        );
+        assert_eq!(find_on_path(Path::new("python2"), &path), None);
+        std::fs::remove_dir_all(bin).unwrap();
     }
-
-    format!("{}…", &text[..end])
-}
-
-fn resolve_script(app: &AppHandle) -> Result<PathBuf> {
-    app.path_resolver()
-        .resolve_resource("python/main.py")
-        .or_else(|| Some(PathBuf::from("python/main.py")))
-        .context("Impossible de trouver le script python/main.py")
-}
-
-async fn write_payload(payload: &Value) -> Result<PathBuf> {
-    let filename = format!("cvgen-{}.json", Uuid::new_v4());
-    let path = std::env::temp_dir().join(filename);
-    fs::write(&path, serde_json::to_vec(payload)?).await?;
-    Ok(path)
 }
@@ -0,0 +1,439 @@
+//! Rendu texte du CV structuré (`ResumeDocument`) et relecture du texte des moteurs qui n'en
+//! produisent pas (Python, LLM) ou retouché par l'utilisateur.
+//!
+//! Format : le nom, puis les sections séparées par une ligne vide. « Intitulé : a, b » est une
+//! liste en ligne ; « Intitulé : » seul annonce des entrées (« - titre (période) »), suivies de
+//! leurs puces (« • ») et d'une ligne libellée indentée (« Technologies : … »).
+
+use crate::models::{
+    HighlightSpan, HighlightType, Inline, ResumeBlock, ResumeDocument, ResumeEntry, ResumeSection,
+    RichText, TagLine,
+};
+
+const BULLET: &str = "• ";
+const BULLETS: &[&str] = &["• ", "* "];
+const ENTRIES: &[&str] = &["- ", "– "];
+const ITEM_SEPARATOR: &str = ", ";
+/// Un intitulé de section ou de ligne libellée reste court.
+const MAX_LABEL_CHARS: usize = 40;
+
+/// Texte en cours de rendu ; les mises en valeur sont relevées en indices de caractères.
+#[derive(Default)]
+struct Writer {
+    text: String,
+    chars: usize,
+    emphasis: Vec<(usize, usize)>,
+}
+
+impl Writer {
+    fn push(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.chars += text.chars().count();
+    }
+
+    fn push_rich(&mut self, rich: &[Inline]) {
+        for inline in rich {
+            let start = self.chars;
+            self.push(&inline.text);
+            if inline.emphasis && self.chars > start {
+                self.emphasis.push((start, self.chars));
+            }
+        }
+    }
+
+    fn push_items(&mut self, items: &[RichText]) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                self.push(ITEM_SEPARATOR);
+            }
+            self.push_rich(item);
+        }
+    }
+
+    fn push_blocks(&mut self, blocks: &[ResumeBlock]) {
+        for (index, block) in blocks.iter().enumerate() {
+            if index > 0 {
+                // Les entrées sans détail (formations) se suivent, le reste est aéré.
+                let compact = matches!(
+                    &blocks[index - 1],
+                    ResumeBlock::Entry(entry) if entry.bullets.is_empty() && entry.tags.is_none()
+                );
+                self.push(if compact { "\n" } else { "\n\n" });
+            }
+            match block {
+                ResumeBlock::Paragraph { text } => self.push_rich(text),
+                ResumeBlock::Tags { items } => self.push_items(items),
+                ResumeBlock::List { items } => {
+                    for (index, item) in items.iter().enumerate() {
+                        if index > 0 {
+                            self.push("\n");
+                        }
+                        self.push(BULLET);
+                        self.push_rich(item);
+                    }
+                }
+                ResumeBlock::Entry(entry) => self.push_entry(entry),
+            }
+        }
+    }
+
+    fn push_entry(&mut self, entry: &ResumeEntry) {
+        self.push(ENTRIES[0]);
+        self.push_rich(&entry.title);
+        if let Some(period) = &entry.period {
+            self.push(&format!(" ({period})"));
+        }
+        for bullet in &entry.bullets {
+            self.push("\n  ");
+            self.push(BULLET);
+            self.push_rich(bullet);
+        }
+        if let Some(tags) = &entry.tags {
+            self.push(&format!("\n    {} : ", tags.label));
+            self.push_items(&tags.items);
+        }
+    }
+}
+
+impl ResumeDocument {
+    /// Texte du CV et surlignages `emphasis` des segments mis en valeur.
+    pub fn render(&self) -> (String, Vec<HighlightSpan>) {
+        let mut writer = Writer::default();
+        writer.push(&self.name);
+        for section in self.sections.iter().filter(|s| !s.blocks.is_empty()) {
+            writer.push("\n\n");
+            match (&section.heading, section.blocks.as_slice()) {
+                (Some(heading), [ResumeBlock::Tags { items }]) => {
+                    writer.push(&format!("{heading} : "));
+                    writer.push_items(items);
+                }
+                (heading, blocks) => {
+                    if let Some(heading) = heading {
+                        writer.push(&format!("{heading} :\n"));
+                    }
+                    writer.push_blocks(blocks);
+                }
+            }
+        }
+
+        let highlights = writer
+            .emphasis
+            .into_iter()
+            .enumerate()
+            .map(|(index, (start, end))| HighlightSpan {
+                id: format!("emphasis-{index}"),
+                highlight_type: HighlightType::Emphasis,
+                start,
+                end,
+                utf8: None,
+                utf16: None,
+                removed_text: None,
+            })
+            .collect();
+        (writer.text, highlights)
+    }
+
+    /// Relit un CV en texte ; les surlignages `emphasis` deviennent des segments mis en valeur.
+    pub fn from_text(text: &str, highlights: &[HighlightSpan]) -> Self {
+        let mut emphasis: Vec<(usize, usize)> = highlights
+            .iter()
+            .filter(|span| matches!(span.highlight_type, HighlightType::Emphasis))
+            .map(|span| (span.start, span.end))
+            .collect();
+        emphasis.sort_unstable();
+        let reader = Reader {
+            emphasis: &emphasis,
+        };
+
+        let mut document = Self::default();
+        let mut line_start = 0;
+        let mut after_blank = true;
+        for raw in text.split('\n') {
+            let start = line_start;
+            line_start += raw.chars().count() + 1;
+            let line = raw.trim_end();
+            if line.trim().is_empty() {
+                after_blank = true;
+                continue;
+            }
+            let content = line.trim_start();
+            let indented = content.len() < line.len();
+            let at = start + line[..line.len() - content.len()].chars().count();
+            if document.name.is_empty() {
+                document.name = content.to_string();
+            } else {
+                reader.read_line(&mut document, content, at, indented, after_blank);
+            }
+            after_blank = false;
+        }
+        document
+    }
+
+    /// Applique `transform` à chaque texte de l'arbre (masquage des données personnelles). Un
+    /// texte riche est transformé d'un seul tenant, segments mis en valeur compris ; un texte
+    /// simple l'est comme un texte riche d'un seul segment.
+    pub fn map_text(&self, transform: impl Fn(&RichText) -> RichText) -> Self {
+        let mut document = self.clone();
+        let apply = |text: &mut String| {
+            let plain = vec![Inline {
+                text: std::mem::take(text),
+                emphasis: false,
+            }];
+            *text = transform(&plain)
+                .into_iter()
+                .map(|inline| inline.text)
+                .collect();
+        };
+        apply(&mut document.name);
+        for section in &mut document.sections {
+            if let Some(heading) = &mut section.heading {
+                apply(heading);
+            }
+            for block in &mut section.blocks {
+                let rich_texts: Vec<&mut RichText> = match block {
+                    ResumeBlock::Paragraph { text } => vec![text],
+                    ResumeBlock::Tags { items } | ResumeBlock::List { items } => {
+                        items.iter_mut().collect()
+                    }
+                    ResumeBlock::Entry(entry) => {
+                        if let Some(period) = &mut entry.period {
+                            apply(period);
+                        }
+                        let tags = match &mut entry.tags {
+                            Some(tags) => {
+                                apply(&mut tags.label);
+                                tags.items.iter_mut().collect()
+                            }
+                            None => Vec::new(),
+                        };
+                        std::iter::once(&mut entry.title)
+                            .chain(entry.bullets.iter_mut())
+                            .chain(tags)
+                            .collect()
+                    }
+                };
+                for rich in rich_texts {
+                    *rich = transform(rich);
+                }
+            }
+        }
+        document
+    }
+}
+
+struct Reader<'a> {
+    /// Plages mises en valeur (indices de caractères), triées.
+    emphasis: &'a [(usize, usize)],
+}
+
+impl Reader<'_> {
+    /// Découpe `text`, situé au caractère `start` du CV, selon les mises en valeur.
+    fn rich(&self, text: &str, start: usize) -> RichText {
+        let chars: Vec<char> = text.chars().collect();
+        let end = start + chars.len();
+        let slice = |from: usize, to: usize| chars[from - start..to - start].iter().collect();
+        let mut inlines = Vec::new();
+        let mut cursor = start;
+        for &(from, to) in self.emphasis {
+            let (from, to) = (from.max(cursor), to.min(end));
+            if from >= to {
+                continue;
+            }
+            if from > cursor {
+                inlines.push(Inline {
+                    text: slice(cursor, from),
+                    emphasis: false,
+                });
+            }
+            inlines.push(Inline {
+                text: slice(from, to),
+                emphasis: true,
+            });
+            cursor = to;
+        }
+        if cursor < end || inlines.is_empty() {
+            inlines.push(Inline {
+                text: slice(cursor, end),
+                emphasis: false,
+            });
+        }
+        inlines
+    }
+
+    fn items(&self, text: &str, start: usize) -> Vec<RichText> {
+        let mut items = Vec::new();
+        let mut offset = 0;
+        for item in text.split(ITEM_SEPARATOR) {
+            let at = start + text[..offset].chars().count();
+            if !item.trim().is_empty() {
+                items.push(self.rich(item, at));
+            }
+            offset += item.len() + ITEM_SEPARATOR.len();
+        }
+        items
+    }
+
+    fn read_line(
+        &self,
+        document: &mut ResumeDocument,
+        content: &str,
+        at: usize,
+        indented: bool,
+        after_blank: bool,
+    ) {
+        let offset = |prefix: &str| at + prefix.chars().count();
+
+        if let Some(bullet) = strip_prefix(content, BULLETS) {
+            let item = self.rich(bullet, offset(&content[..content.len() - bullet.len()]));
+            let blocks = &mut current_section(document).blocks;
+            match blocks.last_mut() {
+                Some(ResumeBlock::Entry(entry)) => entry.bullets.push(item),
+                Some(ResumeBlock::List { items }) if !after_blank => items.push(item),
+                _ => blocks.push(ResumeBlock::List { items: vec![item] }),
+            }
+            return;
+        }
+
+        if let Some(title) = strip_prefix(content, ENTRIES) {
+            let title_start = offset(&content[..content.len() - title.len()]);
+            let (title, period) = match title.strip_suffix(')').and_then(|t| t.rsplit_once(" (")) {
+                Some((title, period)) => (title, Some(period.to_string())),
+                None => (title, None),
+            };
+            current_section(document)
+                .blocks
+                .push(ResumeBlock::Entry(ResumeEntry {
+                    title: self.rich(title, title_start),
+                    period,
+                    bullets: Vec::new(),
+                    tags: None,
+                }));
+            return;
+        }
+
+        if let Some((label, items)) = split_label(content) {
+            let items_start = offset(&content[..content.len() - items.len()]);
+            let items = self.items(items, items_start);
+            if indented {
+                if let Some(ResumeBlock::Entry(entry)) = current_section(document).blocks.last_mut()
+                {
+                    entry.tags = Some(TagLine {
+                        label: label.to_string(),
+                        items,
+                    });
+                    return;
+                }
+            }
+            let blocks = if items.is_empty() {
+                Vec::new()
+            } else {
+                vec![ResumeBlock::Tags { items }]
+            };
+            document.sections.push(ResumeSection {
+                heading: Some(label.to_string()),
+                blocks,
+            });
+            return;
+        }
+
+        let text = self.rich(content, at);
+        let section = match document.sections.last_mut() {
+            // Après une liste en ligne, un paragraphe ouvre une section sans intitulé.
+            Some(section)
+                if !(after_blank
+                    && section.heading.is_some()
+                    && matches!(section.blocks.as_slice(), [ResumeBlock::Tags { .. }])) =>
+            {
+                section
+            }
+            _ => {
+                document.sections.push(ResumeSection::default());
+                document.sections.last_mut().expect("section ajoutée")
+            }
+        };
+        match section.blocks.last_mut() {
+            Some(ResumeBlock::Paragraph { text: paragraph }) if !after_blank => {
+                paragraph.push(Inline {
+                    text: "\n".into(),
+                    emphasis: false,
+                });
+                paragraph.extend(text);
+            }
+            _ => section.blocks.push(ResumeBlock::Paragraph { text }),
+        }
+    }
+}
+
+fn current_section(document: &mut ResumeDocument) -> &mut ResumeSection {
+    if document.sections.is_empty() {
+        document.sections.push(ResumeSection::default());
+    }
+    document.sections.last_mut().expect("section présente")
+}
+
+fn strip_prefix<'a>(content: &'a str, prefixes: &[&str]) -> Option<&'a str> {
+    prefixes
+        .iter()
+        .find_map(|prefix| content.strip_prefix(prefix))
+}
+
+/// « Intitulé : a, b » ou « Intitulé : » ; une phrase contenant deux-points n'en est pas un.
+fn split_label(content: &str) -> Option<(&str, &str)> {
+    let (label, rest) = content.split_once(':')?;
+    let label = label.trim_end();
+    let short = !label.is_empty()
+        && label.chars().count() <= MAX_LABEL_CHARS
+        && !label.contains(['.', '!', '?']);
+    let items = rest.trim_start();
+    (short && (rest.is_empty() || rest.starts_with(' '))).then_some((label, items))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{HighlightType, ResumeBlock, ResumeDocument};
+
+    const RESUME: &str = "Élise Martin\n\nIngénieure logiciel\n\n\
+                          Compétences clés : Rust, Kubernetes\n\n\
+                          Expériences professionnelles :\n\
+                          - Développeuse chez Acme (2020 - Présent)\n  \
+                          • Migration vers Rust\n    \
+                          Technologies : Rust, PHP\n\n\
+                          Formation :\n\
+                          - Master - INSA (2015 - 2017)\n\
+                          - Licence - Lyon 1 (2012 - 2015)";
+
+    #[test]
+    fn text_round_trip_keeps_structure_and_emphasis() {
+        let rust = RESUME.find("Rust").unwrap();
+        let start = RESUME[..rust].chars().count();
+        let emphasis = crate::models::HighlightSpan {
+            id: "h".into(),
+            highlight_type: HighlightType::Emphasis,
+            start,
+            end: start + 4,
+            utf8: None,
+            utf16: None,
+            removed_text: None,
+        };
+        let document = ResumeDocument::from_text(RESUME, &[emphasis]);
+
+        assert_eq!(document.name, "Élise Martin");
+        assert_eq!(document.sections.len(), 4);
+        let ResumeBlock::Entry(job) = &document.sections[2].blocks[0] else {
+            panic!("entrée attendue");
+        };
+        assert_eq!(job.period.as_deref(), Some("2020 - Présent"));
+        assert_eq!(job.bullets.len(), 1);
+        assert_eq!(job.tags.as_ref().unwrap().items.len(), 2);
+        let ResumeBlock::Tags { items } = &document.sections[1].blocks[0] else {
+            panic!("liste en ligne attendue");
+        };
+        assert!(items[0][0].emphasis);
+        assert_eq!(items[0][0].text, "Rust");
+
+        let (text, highlights) = document.render();
+        assert_eq!(text, RESUME);
+        assert_eq!(highlights.len(), 1);
+        assert_eq!((highlights[0].start, highlights[0].end), (start, start + 4));
+    }
+}
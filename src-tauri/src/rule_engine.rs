@@ -0,0 +1,475 @@
+//! Moteur d'adaptation déterministe, sans Python : les compétences et réalisations sont
+//! triées par pertinence vis-à-vis des mots-clés de l'offre, et la lettre suit un canevas
+//! localisé (`coverLetter.*` dans les catalogues). Le CV est construit en arbre
+//! (`ResumeDocument`), mots-clés mis en valeur.
+
+use std::{cmp::Reverse, collections::HashMap};
+
+use chrono::Local;
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    ai_engine::{AdaptationEngine, EngineInput},
+    locale::{is_stopword, lookup, section_labels},
+    messages::AppError,
+    models::{
+        AdaptationResult, CoverLetter, Inline, ResumeBlock, ResumeDocument, ResumeEntry,
+        ResumeSection, RichText, TagLine,
+    },
+};
+
+/// Mots-clés retenus quand l'offre n'a pas été analysée.
+const MAX_KEYWORDS: usize = 15;
+const MAX_SKILLS: usize = 10;
+const MAX_EXPERIENCES: usize = 6;
+const MAX_ACHIEVEMENTS: usize = 4;
+const MAX_TECHNOLOGIES: usize = 6;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RuleBasedEngine;
+
+impl AdaptationEngine for RuleBasedEngine {
+    async fn adapt(&self, input: EngineInput<'_>) -> Result<AdaptationResult, AppError> {
+        Ok(adapt_with_rules(&input))
+    }
+}
+
+fn adapt_with_rules(input: &EngineInput<'_>) -> AdaptationResult {
+    let keywords = match &input.offer.keywords {
+        Some(keywords) if !keywords.is_empty() => keywords.clone(),
+        _ => extract_keywords(&input.offer.description),
+    };
+    let relevance = Relevance::new(&keywords);
+    let document = build_resume(input, &relevance, &Emphasis::new(&keywords));
+    let (adapted_resume, highlights) = document.render();
+    AdaptationResult {
+        adapted_cover_letter: build_cover_letter(input, &keywords),
+        adapted_resume,
+        resume_document: Some(document),
+        highlights,
+        cover_letter_highlights: Vec::new(),
+        language: Some(input.language),
+        offer_id: Some(input.offer.id.clone()),
+        user_edited: false,
+    }
+}
+
+/// Mots les plus fréquents de l'offre, hors mots-outils (ordre de première apparition à
+/// fréquence égale).
+fn extract_keywords(text: &str) -> Vec<String> {
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    let words = text
+        .split(|c: char| !(c.is_alphanumeric() || matches!(c, '+' | '#' | '.' | '-')))
+        .map(|word| word.trim_matches(|c: char| matches!(c, '.' | '-')))
+        .filter(|word| word.chars().count() >= 3 && word.chars().any(char::is_alphabetic));
+    for (position, word) in words.enumerate() {
+        let word = word.to_lowercase();
+        if is_stopword(&word) {
+            continue;
+        }
+        counts.entry(word).or_insert((0, position)).0 += 1;
+    }
+    let mut ranked: Vec<(String, (usize, usize))> = counts.into_iter().collect();
+    ranked.sort_by_key(|(_, (count, first))| (Reverse(*count), *first));
+    ranked
+        .into_iter()
+        .take(MAX_KEYWORDS)
+        .map(|(word, _)| word)
+        .collect()
+}
+
+/// Motif d'un mot-clé en mots entiers (« java » ne trouve pas « JavaScript », ni « go »
+/// « Google ») ; la limite n'est posée que du côté d'un caractère de mot, pour « C++ » ou
+/// « .NET ».
+fn keyword_pattern(keyword: &str) -> String {
+    let boundary = |c: Option<char>| match c {
+        Some(c) if c.is_alphanumeric() || c == '_' => r"\b",
+        _ => "",
+    };
+    format!(
+        "{}{}{}",
+        boundary(keyword.chars().next()),
+        regex::escape(keyword),
+        boundary(keyword.chars().last())
+    )
+}
+
+/// Score d'un texte : somme des poids des mots-clés qu'il contient, le premier pesant le plus.
+struct Relevance {
+    keywords: Vec<(Regex, usize)>,
+}
+
+impl Relevance {
+    fn new(keywords: &[String]) -> Self {
+        let count = keywords.len();
+        Self {
+            keywords: keywords
+                .iter()
+                .enumerate()
+                .filter(|(_, keyword)| !keyword.trim().is_empty())
+                .filter_map(|(index, keyword)| {
+                    let pattern = RegexBuilder::new(&keyword_pattern(keyword.trim()))
+                        .case_insensitive(true)
+                        .build()
+                        .ok()?;
+                    Some((pattern, count - index))
+                })
+                .collect(),
+        }
+    }
+
+    fn score(&self, text: &str) -> usize {
+        self.keywords
+            .iter()
+            .filter(|(pattern, _)| pattern.is_match(text))
+            .map(|(_, weight)| weight)
+            .sum()
+    }
+
+    /// Tri stable : à score égal, l'ordre saisi par le candidat est conservé.
+    fn sorted<'a>(&self, items: &'a [String]) -> Vec<&'a String> {
+        let mut sorted: Vec<&String> = items.iter().collect();
+        sorted.sort_by_key(|item| Reverse(self.score(item)));
+        sorted
+    }
+}
+
+fn build_resume(
+    input: &EngineInput<'_>,
+    relevance: &Relevance,
+    emphasis: &Emphasis,
+) -> ResumeDocument {
+    let profile = input.profile;
+    let labels = section_labels(input.language);
+    let label = |key: &str| {
+        labels
+            .get(key)
+            .map(String::as_str)
+            .unwrap_or(key)
+            .to_string()
+    };
+    let ranked = |items: &[String], limit: usize| -> Vec<RichText> {
+        relevance
+            .sorted(items)
+            .into_iter()
+            .take(limit)
+            .map(|item| emphasis.apply(item))
+            .collect()
+    };
+
+    let mut sections = Vec::new();
+    if let Some(summary) = profile.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        sections.push(ResumeSection {
+            heading: None,
+            blocks: vec![ResumeBlock::Paragraph {
+                text: emphasis.apply(summary.trim()),
+            }],
+        });
+    }
+    sections.push(ResumeSection {
+        heading: Some(label("skills")),
+        blocks: vec![ResumeBlock::Tags {
+            items: ranked(&profile.skills, MAX_SKILLS),
+        }],
+    });
+
+    let experiences = profile
+        .experiences
+        .iter()
+        .take(MAX_EXPERIENCES)
+        .map(|experience| {
+            let end = experience
+                .end_date
+                .clone()
+                .unwrap_or_else(|| label("present"));
+            let tags = (!experience.technologies.is_empty()).then(|| TagLine {
+                label: label("technologies"),
+                items: ranked(&experience.technologies, MAX_TECHNOLOGIES),
+            });
+            ResumeBlock::Entry(ResumeEntry {
+                title: emphasis.apply(&format!(
+                    "{} {} {}",
+                    experience.role,
+                    label("at"),
+                    experience.company
+                )),
+                period: Some(format!("{} - {end}", experience.start_date)),
+                bullets: ranked(&experience.achievements, MAX_ACHIEVEMENTS),
+                tags,
+            })
+        })
+        .collect();
+    sections.push(ResumeSection {
+        heading: Some(label("experiences")),
+        blocks: experiences,
+    });
+
+    let education = profile
+        .education
+        .iter()
+        .take(4)
+        .map(|education| {
+            let end = education
+                .end_date
+                .clone()
+                .unwrap_or_else(|| label("present"));
+            ResumeBlock::Entry(ResumeEntry {
+                title: emphasis.apply(&format!("{} - {}", education.degree, education.school)),
+                period: Some(format!("{} - {end}", education.start_date)),
+                ..Default::default()
+            })
+        })
+        .collect();
+    sections.push(ResumeSection {
+        heading: Some(label("education")),
+        blocks: education,
+    });
+
+    if !profile.languages.is_empty() {
+        let languages = profile
+            .languages
+            .iter()
+            .map(|language| emphasis.apply(&format!("{} ({})", language.label, language.level)))
+            .collect();
+        sections.push(ResumeSection {
+            heading: Some(label("languages")),
+            blocks: vec![ResumeBlock::Tags { items: languages }],
+        });
+    }
+    sections.retain(|section| !section.blocks.is_empty());
+
+    ResumeDocument {
+        name: profile.full_name.clone(),
+        sections,
+    }
+}
+
+fn build_cover_letter(input: &EngineInput<'_>, keywords: &[String]) -> CoverLetter {
+    let template = |key: &str| lookup(input.language, &format!("coverLetter.{key}")).unwrap_or("");
+    let profile = input.profile;
+    let offer = input.offer;
+    let strengths = if keywords.is_empty() {
+        profile.skills.iter().take(5).cloned().collect::<Vec<_>>()
+    } else {
+        keywords.iter().take(5).cloned().collect()
+    };
+
+    let mut paragraphs = vec![
+        template("intro").replace("{title}", &offer.title),
+        template("body").replace("{strengths}", &strengths.join(", ")),
+    ];
+    let achievements: Vec<&str> = profile
+        .experiences
+        .iter()
+        .take(2)
+        .filter_map(|experience| experience.achievements.first())
+        .map(String::as_str)
+        .collect();
+    if !achievements.is_empty() {
+        paragraphs.push(format!(
+            "{}{}",
+            template("achievements"),
+            achievements.join("; ")
+        ));
+    }
+    paragraphs.push(template("closing").to_string());
+
+    let contact = offer
+        .contact
+        .as_ref()
+        .and_then(|contact| contact.name.clone());
+    CoverLetter {
+        sender: [
+            Some(profile.full_name.clone()),
+            profile.email.clone(),
+            profile.phone.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+        recipient: [offer.company.clone(), contact, offer.location.clone()]
+            .into_iter()
+            .flatten()
+            .collect(),
+        date: Some(Local::now().format(template("dateFormat")).to_string()),
+        subject: Some(template("subject").replace("{title}", &offer.title)),
+        salutation: template("salutation").to_string(),
+        paragraphs,
+        closing: template("signoff").to_string(),
+        signature: profile.full_name.clone(),
+    }
+}
+
+/// Mise en valeur des mots-clés de l'offre en mots entiers, sans tenir compte de la casse.
+struct Emphasis(Option<Regex>);
+
+impl Emphasis {
+    fn new(keywords: &[String]) -> Self {
+        let mut keywords: Vec<&str> = keywords
+            .iter()
+            .map(|k| k.trim())
+            .filter(|k| !k.is_empty())
+            .collect();
+        // Le mot-clé le plus long l'emporte (« Rust async » avant « Rust »).
+        keywords.sort_by_key(|keyword| Reverse(keyword.len()));
+        let alternation = keywords
+            .into_iter()
+            .map(keyword_pattern)
+            .collect::<Vec<_>>()
+            .join("|");
+        let pattern = (!alternation.is_empty())
+            .then(|| {
+                RegexBuilder::new(&alternation)
+                    .case_insensitive(true)
+                    .build()
+                    .ok()
+            })
+            .flatten();
+        Self(pattern)
+    }
+
+    fn apply(&self, text: &str) -> RichText {
+        let mut inlines = Vec::new();
+        let mut cursor = 0;
+        let matches = self.0.iter().flat_map(|pattern| pattern.find_iter(text));
+        for found in matches.filter(|m| !m.is_empty()) {
+            if found.start() > cursor {
+                inlines.push(Inline {
+                    text: text[cursor..found.start()].to_string(),
+                    emphasis: false,
+                });
+            }
+            inlines.push(Inline {
+                text: found.as_str().to_string(),
+                emphasis: true,
+            });
+            cursor = found.end();
+        }
+        if cursor < text.len() || inlines.is_empty() {
+            inlines.push(Inline {
+                text: text[cursor..].to_string(),
+                emphasis: false,
+            });
+        }
+        inlines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adapt_with_rules, extract_keywords, Emphasis, Relevance};
+    use crate::{
+        ai_engine::EngineInput,
+        locale::Language,
+        models::{CandidateProfile, Experience, JobOffer},
+    };
+
+    fn profile() -> CandidateProfile {
+        serde_json::from_value(serde_json::json!({
+            "id": "p1",
+            "fullName": "Élise Martin",
+            "email": null,
+            "phone": null,
+            "summary": "Ingénieure logiciel",
+            "experiences": [],
+            "skills": ["Java", "Kubernetes", "Rust"],
+            "education": [],
+            "projects": [],
+            "languages": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn ranks_skills_and_achievements_by_keywords() {
+        let mut profile = profile();
+        profile.experiences.push(Experience {
+            id: "e1".into(),
+            company: "Acme".into(),
+            role: "Développeuse".into(),
+            start_date: "2020".into(),
+            end_date: None,
+            achievements: vec![
+                "Refonte du site vitrine".into(),
+                "Migration des services vers Rust et Kubernetes".into(),
+            ],
+            technologies: vec!["PHP".into(), "Rust".into()],
+            role_translations: Default::default(),
+            achievement_translations: Default::default(),
+        });
+        let offer = JobOffer {
+            id: "o1".into(),
+            title: "Ingénieur Rust".into(),
+            company: None,
+            description: String::new(),
+            location: None,
+            keywords: Some(vec!["rust".into(), "kubernetes".into()]),
+            language: None,
+            date_posted: None,
+            valid_through: None,
+            salary: None,
+            contact: None,
+        };
+        let input = EngineInput {
+            profile: &profile,
+            offer: &offer,
+            language: Language::Fr,
+        };
+
+        let result = adapt_with_rules(&input);
+        let resume = &result.adapted_resume;
+        assert!(resume.contains("Compétences clés : Rust, Kubernetes, Java"));
+        assert!(
+            resume.find("Migration des services").unwrap()
+                < resume.find("Refonte du site").unwrap()
+        );
+        assert!(resume.contains("Technologies : Rust, PHP"));
+        let letter = &result.adapted_cover_letter;
+        assert_eq!(
+            letter.paragraphs[0],
+            "Je vous propose ma candidature au poste Ingénieur Rust."
+        );
+        assert_eq!(
+            letter.subject.as_deref(),
+            Some("Candidature au poste Ingénieur Rust")
+        );
+        assert_eq!(letter.sender, ["Élise Martin"]);
+        assert!(letter.to_text().ends_with("Cordialement,\nÉlise Martin"));
+
+        // Offsets en caractères : « É » compte pour un.
+        let first = &result.highlights[0];
+        let highlighted: String = resume
+            .chars()
+            .skip(first.start)
+            .take(first.end - first.start)
+            .collect();
+        assert_eq!(highlighted.to_lowercase(), "rust");
+        assert_eq!(adapt_with_rules(&input).highlights[0].id, first.id);
+    }
+
+    #[test]
+    fn extracts_keywords_without_stopwords() {
+        let keywords =
+            extract_keywords("Nous recherchons un profil Rust. Rust et Tokio pour le backend.");
+        assert_eq!(keywords[0], "rust");
+        assert!(keywords.contains(&"tokio".to_string()));
+        assert!(!keywords.contains(&"pour".to_string()));
+    }
+
+    #[test]
+    fn keywords_match_whole_words_only() {
+        let keywords: Vec<String> = ["java", "go", "c++"].map(String::from).into();
+        let relevance = Relevance::new(&keywords);
+        assert_eq!(relevance.score("JavaScript chez Google"), 0);
+        assert!(relevance.score("Java et Go") > 0);
+        assert!(relevance.score("C++ moderne") > 0);
+
+        let emphasized: Vec<String> = Emphasis::new(&keywords)
+            .apply("JavaScript puis Java")
+            .into_iter()
+            .filter(|inline| inline.emphasis)
+            .map(|inline| inline.text)
+            .collect();
+        assert_eq!(emphasized, ["Java"]);
+    }
+}
@@ -0,0 +1,590 @@
+//! Index inversé en mémoire sur les profils, offres et adaptations, tenu à jour par les
+//! fonctions `store_*` de `state`.
+//!
+//! Les mots sont comparés sans casse ni accents (« Ingénieur » = « ingenieur »), l'élision
+//! française est retirée (« l'entreprise » → « entreprise »). Une requête combine des mots,
+//! des préfixes (`dev*`) et des expressions entre guillemets (`"chef de projet"`) ; les
+//! résultats sont classés par BM25, en favorisant ceux qui couvrent le plus de termes.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use tauri::{command, State};
+use tracing::instrument;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    locale::is_stopword,
+    messages::AppError,
+    models::{AdaptationResult, CandidateProfile, JobOffer, OffsetRange},
+    state::SharedState,
+    vault::VaultState,
+};
+
+const DEFAULT_LIMIT: usize = 20;
+/// Termes de l'index retenus au plus pour un préfixe.
+const MAX_PREFIX_TERMS: usize = 64;
+/// Largeur de l'extrait, en caractères.
+const SNIPPET_CHARS: usize = 160;
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DocumentKind {
+    Profile,
+    Offer,
+    /// Clé de cache de l'adaptation (`profil` ou `profil:variante`).
+    Adaptation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub kind: DocumentKind,
+    pub id: String,
+    pub title: String,
+    pub score: f64,
+    /// Champ d'où provient l'extrait (`description`, `skills`, `resume`…).
+    pub field: String,
+    pub snippet: String,
+    /// Passages correspondants dans l'extrait, en caractères.
+    pub matches: Vec<OffsetRange>,
+}
+
+/// Champ indexé : nom, poids dans le score et texte.
+struct Field {
+    name: &'static str,
+    weight: f64,
+    text: String,
+}
+
+impl Field {
+    fn new(name: &'static str, text: impl Into<String>) -> Self {
+        Self {
+            name,
+            weight: 1.0,
+            text: text.into(),
+        }
+    }
+
+    fn weighted(self, weight: f64) -> Self {
+        Self { weight, ..self }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IndexedDocument {
+    kind: DocumentKind,
+    id: String,
+    title: String,
+    fields: Vec<(&'static str, f64, String)>,
+    length: usize,
+    terms: Vec<String>,
+}
+
+/// Occurrence d'un terme : champ, rang du mot dans le champ et plage en octets.
+#[derive(Debug, Clone, Copy)]
+struct Occurrence {
+    field: usize,
+    position: usize,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    documents: Vec<Option<IndexedDocument>>,
+    slots: HashMap<(DocumentKind, String), usize>,
+    /// Emplacements libérés par `remove`, réutilisés à l'insertion suivante.
+    free: Vec<usize>,
+    /// Trié pour parcourir les termes d'un préfixe.
+    postings: BTreeMap<String, BTreeMap<usize, Vec<Occurrence>>>,
+    total_length: usize,
+}
+
+impl SearchIndex {
+    pub fn index_profile(&mut self, profile: &CandidateProfile) {
+        let experiences = profile.experiences.iter().map(|experience| {
+            let mut lines = vec![format!("{} {}", experience.role, experience.company)];
+            lines.extend(experience.achievements.iter().cloned());
+            lines.push(experience.technologies.join(", "));
+            lines.join("\n")
+        });
+        let education = profile
+            .education
+            .iter()
+            .map(|education| format!("{} {}", education.degree, education.school));
+        let projects = profile
+            .projects
+            .iter()
+            .map(|project| format!("{} {}", project.name, project.description));
+        let languages = profile.languages.iter().map(|language| &language.label);
+
+        let fields = vec![
+            Field::new("name", &profile.full_name).weighted(2.0),
+            Field::new("summary", profile.summary.clone().unwrap_or_default()),
+            Field::new("skills", profile.skills.join(", ")).weighted(1.5),
+            Field::new("experiences", join_lines(experiences)),
+            Field::new("education", join_lines(education)),
+            Field::new("projects", join_lines(projects)),
+            Field::new("languages", join_lines(languages)),
+        ];
+        self.insert(
+            DocumentKind::Profile,
+            &profile.id,
+            &profile.full_name,
+            fields,
+        );
+    }
+
+    pub fn index_offer(&mut self, offer: &JobOffer) {
+        let fields = vec![
+            Field::new("title", &offer.title).weighted(2.0),
+            Field::new("company", offer.company.clone().unwrap_or_default()).weighted(1.5),
+            Field::new("location", offer.location.clone().unwrap_or_default()).weighted(1.5),
+            Field::new(
+                "keywords",
+                offer.keywords.clone().unwrap_or_default().join(", "),
+            )
+            .weighted(1.5),
+            Field::new("description", &offer.description),
+        ];
+        self.insert(DocumentKind::Offer, &offer.id, &offer.title, fields);
+    }
+
+    /// `key` : clé de cache (`adaptation_key`) ; le titre est le nom du candidat si connu.
+    pub fn index_adaptation(&mut self, key: &str, title: &str, adaptation: &AdaptationResult) {
+        let fields = vec![
+            Field::new("resume", &adaptation.adapted_resume),
+            Field::new("coverLetter", adaptation.adapted_cover_letter.to_text()),
+        ];
+        self.insert(DocumentKind::Adaptation, key, title, fields);
+    }
+
+    pub fn remove(&mut self, kind: DocumentKind, id: &str) {
+        let Some(slot) = self.slots.remove(&(kind, id.to_string())) else {
+            return;
+        };
+        self.clear_slot(slot);
+        self.free.push(slot);
+    }
+
+    fn clear_slot(&mut self, slot: usize) {
+        let Some(document) = self.documents[slot].take() else {
+            return;
+        };
+        self.total_length -= document.length;
+        for term in &document.terms {
+            if let Some(postings) = self.postings.get_mut(term) {
+                postings.remove(&slot);
+                if postings.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+    }
+
+    fn insert(&mut self, kind: DocumentKind, id: &str, title: &str, fields: Vec<Field>) {
+        let slot = match self.slots.get(&(kind, id.to_string())) {
+            Some(&slot) => {
+                self.clear_slot(slot);
+                slot
+            }
+            None => {
+                let slot = self.free.pop().unwrap_or_else(|| {
+                    self.documents.push(None);
+                    self.documents.len() - 1
+                });
+                self.slots.insert((kind, id.to_string()), slot);
+                slot
+            }
+        };
+
+        let mut occurrences: HashMap<String, Vec<Occurrence>> = HashMap::new();
+        let mut length = 0;
+        for (field, Field { text, .. }) in fields.iter().enumerate() {
+            for (position, token) in tokenize(text).into_iter().enumerate() {
+                length += 1;
+                occurrences.entry(token.term).or_default().push(Occurrence {
+                    field,
+                    position,
+                    start: token.start,
+                    end: token.end,
+                });
+            }
+        }
+        let terms: Vec<String> = occurrences.keys().cloned().collect();
+        for (term, occurrences) in occurrences {
+            self.postings
+                .entry(term)
+                .or_default()
+                .insert(slot, occurrences);
+        }
+
+        self.total_length += length;
+        self.documents[slot] = Some(IndexedDocument {
+            kind,
+            id: id.to_string(),
+            title: title.to_string(),
+            fields: fields
+                .into_iter()
+                .map(|field| (field.name, field.weight, field.text))
+                .collect(),
+            length,
+            terms,
+        });
+    }
+
+    /// Résultats triés par pertinence, limités aux `kinds` demandés (tous si vide).
+    pub fn search(&self, query: &str, kinds: &[DocumentKind], limit: usize) -> Vec<SearchHit> {
+        let clauses = parse_query(query);
+        if clauses.is_empty() {
+            return Vec::new();
+        }
+        let count = self.slots.len().max(1) as f64;
+        let average_length = (self.total_length as f64 / count).max(1.0);
+
+        // Par document : score cumulé, clauses satisfaites et occurrences à surligner.
+        let mut matches: HashMap<usize, (f64, usize, Vec<Occurrence>)> = HashMap::new();
+        for clause in &clauses {
+            let found = self.clause_occurrences(clause);
+            let idf = (1.0 + (count - found.len() as f64 + 0.5) / (found.len() as f64 + 0.5)).ln();
+            for (slot, occurrences) in found {
+                let Some(document) = &self.documents[slot] else {
+                    continue;
+                };
+                let frequency: f64 = occurrences
+                    .iter()
+                    .map(|occurrence| document.fields[occurrence.field].1)
+                    .sum();
+                let norm = 1.0 - BM25_B + BM25_B * document.length as f64 / average_length;
+                let entry = matches.entry(slot).or_default();
+                entry.0 += idf * frequency * (BM25_K1 + 1.0) / (frequency + BM25_K1 * norm);
+                entry.1 += 1;
+                entry.2.extend(occurrences);
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = matches
+            .into_iter()
+            .filter_map(|(slot, (score, matched, occurrences))| {
+                let document = self.documents[slot].as_ref()?;
+                if !kinds.is_empty() && !kinds.contains(&document.kind) {
+                    return None;
+                }
+                let coverage = matched as f64 / clauses.len() as f64;
+                Some(hit(document, score * coverage, occurrences))
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.title.cmp(&b.title))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        hits.truncate(limit);
+        hits
+    }
+
+    /// Occurrences de la clause par document ; une expression compte pour une occurrence,
+    /// couvrant tous ses mots.
+    fn clause_occurrences(&self, clause: &Clause) -> HashMap<usize, Vec<Occurrence>> {
+        let mut found: HashMap<usize, Vec<Occurrence>> = HashMap::new();
+        match clause {
+            Clause::Term(term) => {
+                for (&slot, occurrences) in self.postings.get(term).into_iter().flatten() {
+                    found.entry(slot).or_default().extend(occurrences);
+                }
+            }
+            Clause::Prefix(prefix) => {
+                let terms = self
+                    .postings
+                    .range(prefix.clone()..)
+                    .take_while(|(term, _)| term.starts_with(prefix.as_str()))
+                    .take(MAX_PREFIX_TERMS);
+                for (_, postings) in terms {
+                    for (&slot, occurrences) in postings {
+                        found.entry(slot).or_default().extend(occurrences);
+                    }
+                }
+            }
+            Clause::Phrase(terms) => {
+                let Some(first) = self.postings.get(&terms[0]) else {
+                    return found;
+                };
+                for (&slot, starts) in first {
+                    let following: Option<Vec<HashMap<(usize, usize), Occurrence>>> = terms[1..]
+                        .iter()
+                        .map(|term| {
+                            let occurrences = self.postings.get(term)?.get(&slot)?;
+                            Some(
+                                occurrences
+                                    .iter()
+                                    .map(|o| ((o.field, o.position), *o))
+                                    .collect(),
+                            )
+                        })
+                        .collect();
+                    let Some(following) = following else {
+                        continue;
+                    };
+                    for start in starts {
+                        let last = following.iter().enumerate().try_fold(
+                            *start,
+                            |_, (offset, occurrences)| {
+                                occurrences
+                                    .get(&(start.field, start.position + offset + 1))
+                                    .copied()
+                            },
+                        );
+                        if let Some(last) = last {
+                            found.entry(slot).or_default().push(Occurrence {
+                                end: last.end,
+                                ..*start
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+}
+
+fn join_lines<S: AsRef<str>>(lines: impl Iterator<Item = S>) -> String {
+    lines
+        .map(|line| line.as_ref().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Extrait du champ le plus représenté parmi les occurrences, centré sur la première.
+fn hit(document: &IndexedDocument, score: f64, mut occurrences: Vec<Occurrence>) -> SearchHit {
+    let mut per_field: HashMap<usize, usize> = HashMap::new();
+    for occurrence in &occurrences {
+        *per_field.entry(occurrence.field).or_default() += 1;
+    }
+    let field = per_field
+        .into_iter()
+        .max_by_key(|&(field, count)| (count, std::cmp::Reverse(field)))
+        .map(|(field, _)| field)
+        .unwrap_or_default();
+    occurrences.retain(|occurrence| occurrence.field == field);
+    occurrences.sort_by_key(|occurrence| occurrence.start);
+    let (name, _, text) = &document.fields[field];
+
+    let char_at = |byte: usize| text[..byte].chars().count();
+    let total = text.chars().count();
+    let first = occurrences.first().map_or(0, |o| char_at(o.start));
+    let start = first.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (start + SNIPPET_CHARS).min(total);
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < total { "…" } else { "" };
+    let excerpt: String = text
+        .chars()
+        .skip(start)
+        .take(end - start)
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+    let shift = prefix.chars().count();
+    let matches = occurrences
+        .iter()
+        .map(|o| (char_at(o.start), char_at(o.end)))
+        .filter(|&(from, to)| from >= start && to <= end)
+        .map(|(from, to)| OffsetRange {
+            start: from - start + shift,
+            end: to - start + shift,
+        })
+        .collect();
+
+    SearchHit {
+        kind: document.kind,
+        id: document.id.clone(),
+        title: document.title.clone(),
+        score,
+        field: name.to_string(),
+        snippet: format!("{prefix}{excerpt}{suffix}"),
+        matches,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Clause {
+    Term(String),
+    Prefix(String),
+    Phrase(Vec<String>),
+}
+
+/// Découpe la requête : les guillemets délimitent les expressions, `*` termine un préfixe.
+/// Les mots-outils isolés sont ignorés, sauf si la requête n'a rien d'autre.
+fn parse_query(query: &str) -> Vec<Clause> {
+    let mut clauses = Vec::new();
+    let mut stopwords = Vec::new();
+    for (index, part) in query.split('"').enumerate() {
+        if index % 2 == 1 {
+            let terms: Vec<String> = tokenize(part).into_iter().map(|t| t.term).collect();
+            match terms.len() {
+                0 => {}
+                1 => clauses.extend(terms.into_iter().map(Clause::Term)),
+                _ => clauses.push(Clause::Phrase(terms)),
+            }
+            continue;
+        }
+        for word in part.split_whitespace() {
+            let prefix = word.ends_with('*');
+            let tokens = tokenize(word);
+            let last = tokens.len().saturating_sub(1);
+            for (position, token) in tokens.into_iter().enumerate() {
+                if prefix && position == last {
+                    clauses.push(Clause::Prefix(token.term));
+                } else if is_stopword(&token.term) {
+                    stopwords.push(Clause::Term(token.term));
+                } else {
+                    clauses.push(Clause::Term(token.term));
+                }
+            }
+        }
+    }
+    if clauses.is_empty() {
+        clauses = stopwords;
+    }
+    clauses.dedup();
+    clauses
+}
+
+/// Mot normalisé et sa plage en octets dans le texte d'origine.
+struct Token {
+    term: String,
+    start: usize,
+    end: usize,
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    text.unicode_word_indices()
+        .filter_map(|(start, word)| {
+            // Élision : « l'entreprise », « qu'il » ; « aujourd'hui » reste entier.
+            let (offset, word) = match word.rfind(['\'', '’']) {
+                Some(index) if is_elision(&word[..index]) => {
+                    let next = index + word[index..].chars().next()?.len_utf8();
+                    (next, &word[next..])
+                }
+                _ => (0, word),
+            };
+            let term = normalize(word);
+            (!term.is_empty()).then(|| Token {
+                term,
+                start: start + offset,
+                end: start + offset + word.len(),
+            })
+        })
+        .collect()
+}
+
+fn is_elision(prefix: &str) -> bool {
+    matches!(
+        normalize(prefix).as_str(),
+        "c" | "d" | "j" | "l" | "m" | "n" | "s" | "t" | "qu" | "jusqu" | "lorsqu" | "puisqu"
+    )
+}
+
+/// Minuscules sans accents ; ligatures développées (« œ » → « oe »).
+fn normalize(word: &str) -> String {
+    let mut normalized = String::with_capacity(word.len());
+    for c in word.nfd().filter(|c| !is_combining_mark(*c)) {
+        match c {
+            'œ' | 'Œ' => normalized.push_str("oe"),
+            'æ' | 'Æ' => normalized.push_str("ae"),
+            'ß' => normalized.push_str("ss"),
+            '\'' | '’' => {}
+            c => normalized.extend(c.to_lowercase()),
+        }
+    }
+    normalized
+}
+
+#[command]
+#[instrument(skip_all, fields(kinds = ?kinds, limit = ?limit))]
+pub fn search(
+    query: String,
+    kinds: Option<Vec<DocumentKind>>,
+    limit: Option<usize>,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+) -> Result<Vec<SearchHit>, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let memory = state.0.lock();
+    Ok(memory.search.search(
+        &query,
+        &kinds.unwrap_or_default(),
+        limit.unwrap_or(DEFAULT_LIMIT),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DocumentKind, SearchIndex};
+    use crate::models::JobOffer;
+
+    fn offer(id: &str, title: &str, location: &str, description: &str) -> JobOffer {
+        JobOffer {
+            id: id.into(),
+            title: title.into(),
+            company: None,
+            description: description.into(),
+            location: Some(location.into()),
+            keywords: None,
+            language: None,
+            date_posted: None,
+            valid_through: None,
+            salary: None,
+            contact: None,
+        }
+    }
+
+    #[test]
+    fn finds_offers_by_accentless_terms_phrases_and_prefixes() {
+        let mut index = SearchIndex::default();
+        index.index_offer(&offer(
+            "o1",
+            "Ingénieur Rust",
+            "Lyon",
+            "Rejoignez l'équipe plateforme : développement de services en Rust.",
+        ));
+        index.index_offer(&offer(
+            "o2",
+            "Développeur Java",
+            "Paris",
+            "Chef de projet technique, Java et Kubernetes.",
+        ));
+
+        let hits = index.search("that Rust job in lyon", &[], 10);
+        assert_eq!(hits[0].id, "o1");
+        assert_eq!(hits.len(), 1);
+
+        let hits = index.search("equipe", &[DocumentKind::Offer], 10);
+        assert_eq!(hits[0].id, "o1");
+        assert_eq!(hits[0].field, "description");
+        let snippet: String = hits[0]
+            .snippet
+            .chars()
+            .skip(hits[0].matches[0].start)
+            .take(hits[0].matches[0].end - hits[0].matches[0].start)
+            .collect();
+        assert_eq!(snippet, "équipe");
+
+        assert_eq!(index.search("\"chef de projet\"", &[], 10)[0].id, "o2");
+        assert!(index.search("\"projet de chef\"", &[], 10).is_empty());
+        assert_eq!(index.search("dével*", &[], 10).len(), 2);
+        assert!(index
+            .search("rust", &[DocumentKind::Profile], 10)
+            .is_empty());
+
+        index.index_offer(&offer("o1", "Ingénieure data", "Nantes", "Python"));
+        assert!(index.search("rust", &[], 10).is_empty());
+        index.remove(DocumentKind::Offer, "o2");
+        assert!(index.search("java", &[], 10).is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+use std::{fs, path::PathBuf};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chacha20poly1305::aead::{rand_core::RngCore, OsRng};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Manager, State};
+
+use crate::{
+    ai_engine::EngineKind,
+    http_api::apply_http_api,
+    inbox::apply_inbox,
+    llm_engine::{DEFAULT_SYSTEM_PROMPT, DEFAULT_USER_PROMPT},
+    locale::Language,
+    logging::{apply_log_level, LogLevel},
+    messages::{apply_ui_language, AppError},
+    models::ExportFormat,
+    paths::AppPaths,
+    vault::VaultState,
+};
+
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+/// Longueur minimale du jeton de l'API HTTP ; ceux générés en font 43.
+pub const MIN_API_TOKEN_LEN: usize = 32;
+
+/// Préférences utilisateur, persistées dans `settings.json` du répertoire de configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// Interpréteur Python ; à défaut `PYTHON_CMD` puis la détection d'un `.venv`.
+    pub python_command: Option<String>,
+    /// Dossier de la bibliothèque de CV ; à défaut `CVGen` dans le dossier Documents du système.
+    pub library_dir: Option<PathBuf>,
+    /// Historise la bibliothèque dans un dépôt git local : chaque enregistrement ou
+    /// suppression de CV devient un commit.
+    pub library_git: bool,
+    /// Dossier des fichiers exportés ; à défaut `~/.cvgen/exports`.
+    pub export_dir: Option<PathBuf>,
+    /// Dossier d'arrivée surveillé : CV et offres qui y sont déposés sont importés.
+    pub inbox_dir: Option<PathBuf>,
+    pub export_format: ExportFormat,
+    pub ui_language: Language,
+    /// Langue d'adaptation quand l'offre ne permet pas de la détecter.
+    pub default_target_language: Language,
+    /// Moteur d'adaptation : pipeline Python ou règles en Rust (sans Python).
+    pub adaptation_engine: EngineKind,
+    /// Niveau des journaux ; la variable `CVGEN_LOG` reste prioritaire.
+    pub log_level: LogLevel,
+    pub http_api: HttpApiSettings,
+    /// Serveur LLM local utilisé par le moteur `llm`.
+    pub llm: LlmSettings,
+}
+
+/// API HTTP locale (127.0.0.1 uniquement) pour les intégrations externes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Jeton attendu dans `Authorization: Bearer …` ; généré à la première activation.
+    /// Enregistré en clair, même avec le chiffrement : l'API l'exige avant le déverrouillage.
+    pub token: Option<String>,
+}
+
+impl Default for HttpApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47321,
+            token: None,
+        }
+    }
+}
+
+/// Serveur compatible OpenAI (`/chat/completions`) : llama.cpp, Ollama, LM Studio…
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LlmSettings {
+    /// URL de base de l'API, sans `/chat/completions`.
+    pub endpoint: String,
+    pub model: String,
+    /// Enregistrée en clair dans `settings.json`, même avec le chiffrement.
+    pub api_key: Option<String>,
+    pub temperature: f32,
+    pub timeout_secs: u64,
+    /// Gabarits éditables (`{{language}}`, `{{offer_title}}`, `{{offer_company}}`,
+    /// `{{keywords}}`, `{{offer}}`, `{{profile}}`) ; `None` reprend ceux de `prompts/`.
+    pub system_prompt: Option<String>,
+    pub user_prompt: Option<String>,
+}
+
+impl Default for LlmSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://127.0.0.1:8080/v1".into(),
+            model: "local".into(),
+            api_key: None,
+            temperature: 0.3,
+            timeout_secs: 300,
+            system_prompt: None,
+            user_prompt: None,
+        }
+    }
+}
+
+impl LlmSettings {
+    pub fn system_prompt(&self) -> &str {
+        self.system_prompt
+            .as_deref()
+            .unwrap_or(DEFAULT_SYSTEM_PROMPT)
+    }
+
+    pub fn user_prompt(&self) -> &str {
+        self.user_prompt.as_deref().unwrap_or(DEFAULT_USER_PROMPT)
+    }
+}
+
+impl HttpApiSettings {
+    /// Jeton vide ou blanc : traité comme absent, donc régénéré à l'activation.
+    fn ensure_token(&mut self) {
+        self.trim_token();
+        if self.enabled && self.token.is_none() {
+            self.token = Some(new_api_token());
+        }
+    }
+
+    /// Le jeton est enregistré et comparé sans les espaces qui l'entourent.
+    fn trim_token(&mut self) {
+        self.token = self
+            .token
+            .take()
+            .map(|token| token.trim().to_owned())
+            .filter(|token| !token.is_empty());
+    }
+}
+
+fn new_api_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<(), AppError> {
+        // Seuls le français et l'anglais ont un catalogue d'erreurs complet.
+        if !matches!(self.ui_language, Language::Fr | Language::En) {
+            return Err(
+                AppError::new("errors.settings.uiLanguage").arg("language", self.ui_language)
+            );
+        }
+        if let Some(command) = &self.python_command {
+            if command.trim().is_empty() {
+                return Err(AppError::new("errors.settings.pythonCommandEmpty"));
+            }
+        }
+        for (field, dir) in [
+            ("libraryDir", &self.library_dir),
+            ("exportDir", &self.export_dir),
+            ("inboxDir", &self.inbox_dir),
+        ] {
+            if let Some(dir) = dir {
+                if !dir.is_absolute() {
+                    return Err(AppError::new("errors.settings.relativePath")
+                        .arg("field", field)
+                        .arg("path", dir.display()));
+                }
+            }
+        }
+        if self.http_api.enabled && self.http_api.port == 0 {
+            return Err(AppError::new("errors.settings.httpApiPort"));
+        }
+        if let Some(token) = &self.http_api.token {
+            if token.len() < MIN_API_TOKEN_LEN {
+                return Err(
+                    AppError::new("errors.settings.httpApiToken").arg("min", MIN_API_TOKEN_LEN)
+                );
+            }
+        }
+        if self.adaptation_engine == EngineKind::Llm {
+            // Serveur local : pas de client TLS embarqué.
+            let endpoint = self.llm.endpoint.trim();
+            if !endpoint.starts_with("http://") {
+                return Err(AppError::new("errors.settings.llmEndpoint").arg("url", endpoint));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct SettingsState(pub Mutex<Settings>);
+
+pub fn current_settings(app: &AppHandle) -> Settings {
+    app.state::<SettingsState>().0.lock().clone()
+}
+
+/// Lit `settings.json` ; `None` si aucun fichier n'existe encore.
+pub fn read_settings(paths: &AppPaths) -> anyhow::Result<Option<Settings>> {
+    let path = paths.settings_file();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut settings: Settings = serde_json::from_str(&fs::read_to_string(path)?)?;
+    settings.http_api.trim_token();
+    Ok(Some(settings))
+}
+
+/// Charge les préférences ; sans fichier, celles par défaut restent en place.
+pub fn load_settings(app: &AppHandle) -> anyhow::Result<()> {
+    let Some(settings) = read_settings(&AppPaths::from_app(app)?)? else {
+        return Ok(());
+    };
+    apply_ui_language(settings.ui_language);
+    apply_log_level(settings.log_level);
+    *app.state::<SettingsState>().0.lock() = settings;
+    Ok(())
+}
+
+pub fn save_settings(app: &AppHandle, settings: &Settings) -> anyhow::Result<()> {
+    let path = AppPaths::from_app(app)?.settings_file();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Écriture atomique : un crash en cours d'écriture ne doit pas corrompre les préférences.
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_string_pretty(settings)?)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+#[command]
+pub fn get_settings(settings: State<'_, SettingsState>) -> Settings {
+    settings.0.lock().clone()
+}
+
+/// Valide, persiste et publie de nouvelles préférences.
+pub fn apply_settings(app: &AppHandle, mut settings: Settings) -> Result<Settings, AppError> {
+    settings.http_api.ensure_token();
+    settings.validate()?;
+    // L'historique git garderait les CV en clair à côté de la bibliothèque chiffrée.
+    let enabling_git = settings.library_git && !app.state::<SettingsState>().0.lock().library_git;
+    if enabling_git && app.state::<VaultState>().0.lock().status().enabled {
+        return Err(AppError::new("errors.settings.libraryGitEncrypted"));
+    }
+    save_settings(app, &settings)
+        .map_err(|error| AppError::new("errors.settings.save").arg("details", error))?;
+
+    apply_ui_language(settings.ui_language);
+    apply_log_level(settings.log_level);
+    *app.state::<SettingsState>().0.lock() = settings.clone();
+    apply_http_api(app, &settings.http_api);
+    apply_inbox(app, settings.inbox_dir.as_deref());
+    if let Err(error) = app.emit_all(SETTINGS_CHANGED_EVENT, &settings) {
+        tracing::warn!(?error, "Impossible d'émettre {SETTINGS_CHANGED_EVENT}");
+    }
+    Ok(settings)
+}
+
+#[command]
+pub fn update_settings(settings: Settings, app: AppHandle) -> Result<Settings, AppError> {
+    apply_settings(&app, settings)
+}
+
+/// Invalide le jeton de l'API HTTP et en génère un nouveau.
+#[command]
+pub fn regenerate_api_token(
+    settings: State<'_, SettingsState>,
+    app: AppHandle,
+) -> Result<Settings, AppError> {
+    let mut next = settings.0.lock().clone();
+    next.http_api.token = Some(new_api_token());
+    apply_settings(&app, next)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{HttpApiSettings, Settings};
+    use crate::locale::Language;
+
+    #[test]
+    fn missing_fields_fall_back_to_defaults() {
+        let settings: Settings = serde_json::from_str(r#"{ "uiLanguage": "en" }"#).unwrap();
+        assert_eq!(settings.ui_language, Language::En);
+        assert_eq!(settings.export_format, crate::models::ExportFormat::Pdf);
+        assert!(settings.python_command.is_none());
+    }
+
+    #[test]
+    fn validate_rejects_relative_dirs_and_blank_python() {
+        assert!(Settings::default().validate().is_ok());
+
+        let relative = Settings {
+            library_dir: Some(PathBuf::from("cvs")),
+            ..Settings::default()
+        };
+        assert_eq!(
+            relative.validate().unwrap_err().key,
+            "errors.settings.relativePath"
+        );
+
+        let blank = Settings {
+            python_command: Some("  ".into()),
+            ..Settings::default()
+        };
+        assert_eq!(
+            blank.validate().unwrap_err().key,
+            "errors.settings.pythonCommandEmpty"
+        );
+
+        let german = Settings {
+            ui_language: Language::De,
+            ..Settings::default()
+        };
+        assert_eq!(
+            german.validate().unwrap_err().key,
+            "errors.settings.uiLanguage"
+        );
+    }
+
+    #[test]
+    fn enabling_http_api_generates_a_token_once() {
+        let mut api = HttpApiSettings::default();
+        api.ensure_token();
+        assert!(api.token.is_none());
+
+        api.enabled = true;
+        api.ensure_token();
+        let token = api.token.clone().expect("jeton généré");
+        assert!(token.len() >= 43);
+        api.ensure_token();
+        assert_eq!(api.token, Some(token));
+
+        api.token = Some("  ".into());
+        api.ensure_token();
+        assert!(api.token.as_deref().is_some_and(|token| token.len() >= 43));
+
+        let typed = "a".repeat(32);
+        api.token = Some(format!("  {typed}\n"));
+        api.ensure_token();
+        assert_eq!(api.token.as_deref(), Some(typed.as_str()));
+
+        let short = Settings {
+            http_api: HttpApiSettings {
+                token: Some("abc".into()),
+                ..HttpApiSettings::default()
+            },
+            ..Settings::default()
+        };
+        assert_eq!(
+            short.validate().unwrap_err().key,
+            "errors.settings.httpApiToken"
+        );
+    }
+}
@@ -1,25 +1,65 @@
-use std::collections::HashMap;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs::{self, File},
+    path::Path,
+    sync::Arc,
+};
 
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use tauri::State;
 
-use crate::models::{AdaptationResult, CandidateProfile, JobOffer};
+use crate::{
+    journal::{Journal, JournalKind},
+    logging::remember_name,
+    models::{AdaptationResult, CandidateProfile, JobOffer, ProfileVariant},
+    search::{DocumentKind, SearchIndex},
+    vault::Vault,
+};
 
 #[derive(Default, Clone)]
 pub struct AppMemory {
     pub profiles: HashMap<String, CandidateProfile>,
     pub offers: HashMap<String, JobOffer>,
     pub adaptations: HashMap<String, AdaptationResult>,
+    pub variants: HashMap<String, ProfileVariant>,
+    /// Recherche plein texte ; reconstruite au chargement, jamais persistée.
+    pub search: SearchIndex,
+    pub journal: Journal,
+    /// `storage.json` tel que lu ou écrit en dernier par ce processus : base de la fusion
+    /// avec les écritures d'un autre processus.
+    pub saved: Arc<PersistedData>,
+}
+
+impl AppMemory {
+    fn index_adaptation(&mut self, key: &str) {
+        let Some(adaptation) = self.adaptations.get(key) else {
+            return;
+        };
+        let profile_id = key.split(':').next().unwrap_or(key);
+        let title = self
+            .profiles
+            .get(profile_id)
+            .map_or(key, |profile| profile.full_name.as_str());
+        self.search.index_adaptation(key, title, adaptation);
+    }
 }
 
 pub struct SharedState(pub Mutex<AppMemory>);
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PersistedData {
     pub profiles: HashMap<String, CandidateProfile>,
     pub offers: HashMap<String, JobOffer>,
     pub adaptations: HashMap<String, AdaptationResult>,
+    #[serde(default)]
+    pub variants: HashMap<String, ProfileVariant>,
+    /// Historique d'annulation (`undo` / `redo`).
+    #[serde(default)]
+    pub journal: Journal,
+    /// Incrémentée à chaque écriture : un processus qui trouve une autre génération que celle
+    /// qu'il a lue fusionne ses changements avec ceux du fichier.
+    #[serde(default)]
+    pub generation: u64,
 }
 
 impl From<AppMemory> for PersistedData {
@@ -28,50 +68,289 @@ impl From<AppMemory> for PersistedData {
             profiles: value.profiles,
             offers: value.offers,
             adaptations: value.adaptations,
+            variants: value.variants,
+            journal: value.journal,
+            generation: value.saved.generation,
         }
     }
 }
 
 impl From<PersistedData> for AppMemory {
     fn from(value: PersistedData) -> Self {
-        Self {
+        // Profils relus du disque : leurs noms doivent aussi être masqués dans les journaux.
+        for profile in value.profiles.values() {
+            remember_name(&profile.full_name);
+        }
+        let saved = Arc::new(value.clone());
+        let mut memory = Self {
             profiles: value.profiles,
             offers: value.offers,
             adaptations: value.adaptations,
+            variants: value.variants,
+            search: SearchIndex::default(),
+            journal: value.journal,
+            saved,
+        };
+        for profile in memory.profiles.values() {
+            memory.search.index_profile(profile);
         }
+        for offer in memory.offers.values() {
+            memory.search.index_offer(offer);
+        }
+        let keys: Vec<String> = memory.adaptations.keys().cloned().collect();
+        for key in keys {
+            memory.index_adaptation(&key);
+        }
+        memory
     }
 }
 
-pub fn try_get_profile<'a>(state: &'a State<SharedState>, id: &str) -> Option<CandidateProfile> {
+/// Lit `storage.json`, déchiffré par le coffre si le chiffrement est actif.
+pub fn read_persisted(path: &Path, vault: &Vault) -> anyhow::Result<Option<PersistedData>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(decode_persisted(vault, fs::read(path)?)?))
+}
+
+/// Écrit à côté puis renomme : un arrêt brutal ne laisse jamais de fichier tronqué.
+fn write_atomically(path: &Path, content: &[u8]) -> anyhow::Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Reporte sur `disk` les changements de `ours` par rapport à `base` : ajouts et
+/// modifications (qui l'emportent à identifiant égal), puis suppressions.
+fn merge_by_id<V: Serialize>(
+    disk: &mut HashMap<String, V>,
+    base: &HashMap<String, V>,
+    ours: HashMap<String, V>,
+) {
+    for id in base.keys() {
+        if !ours.contains_key(id) {
+            disk.remove(id);
+        }
+    }
+    for (id, value) in ours {
+        if changed(base.get(&id), &value) {
+            disk.insert(id, value);
+        }
+    }
+}
+
+pub(crate) fn changed<V: Serialize>(base: Option<&V>, value: &V) -> bool {
+    base.and_then(|base| serde_json::to_value(base).ok()) != serde_json::to_value(value).ok()
+}
+
+/// Enregistre `memory` et renvoie le contenu écrit. Si un autre processus (l'application,
+/// `cvgen-cli`) a écrit le fichier depuis la dernière lecture, ses changements sont conservés
+/// et ceux de `memory` fusionnés par identifiant. Le verrou `storage.lock` sérialise la
+/// lecture et l'écriture entre processus.
+pub fn write_persisted(
+    path: &Path,
+    vault: &Vault,
+    memory: AppMemory,
+) -> anyhow::Result<PersistedData> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let lock = File::create(path.with_file_name("storage.lock"))?;
+    lock.lock()?;
+
+    let base = memory.saved.clone();
+    let ours = PersistedData::from(memory);
+    let stored = match path.exists() {
+        // Un fichier encore en clair à l'activation du chiffrement est lu aussi.
+        true => serde_json::from_slice::<PersistedData>(
+            &vault.decode_for_migration(fs::read(path)?, STORAGE_CONTEXT)?,
+        )?,
+        false => PersistedData::default(),
+    };
+    let mut data = if stored.generation == base.generation {
+        ours
+    } else {
+        tracing::info!(
+            generation = stored.generation,
+            "storage.json modifié par un autre processus : fusion"
+        );
+        merge(stored.clone(), &base, ours)
+    };
+    data.generation = stored.generation + 1;
+    let content = vault.encode(
+        serde_json::to_string_pretty(&data)?.as_bytes(),
+        STORAGE_CONTEXT,
+    )?;
+    write_atomically(path, &content)?;
+    Ok(data)
+}
+
+fn merge(mut disk: PersistedData, base: &PersistedData, ours: PersistedData) -> PersistedData {
+    merge_by_id(&mut disk.profiles, &base.profiles, ours.profiles);
+    merge_by_id(&mut disk.offers, &base.offers, ours.offers);
+    merge_by_id(&mut disk.adaptations, &base.adaptations, ours.adaptations);
+    merge_by_id(&mut disk.variants, &base.variants, ours.variants);
+    disk.journal.merge(&base.journal, ours.journal);
+    disk
+}
+
+/// Données associées liant le `storage.json` chiffré à son nom.
+pub const STORAGE_CONTEXT: &str = "storage.json";
+
+/// Contenu de `storage.json` pour `memory`, chiffré si le chiffrement est actif.
+pub fn encode_persisted(vault: &Vault, memory: AppMemory) -> anyhow::Result<Vec<u8>> {
+    let data: PersistedData = memory.into();
+    Ok(vault.encode(
+        serde_json::to_string_pretty(&data)?.as_bytes(),
+        STORAGE_CONTEXT,
+    )?)
+}
+
+pub fn decode_persisted(vault: &Vault, content: Vec<u8>) -> anyhow::Result<PersistedData> {
+    Ok(serde_json::from_slice(
+        &vault.decode(content, STORAGE_CONTEXT)?,
+    )?)
+}
+
+pub fn try_get_profile(state: &SharedState, id: &str) -> Option<CandidateProfile> {
     let guard = state.0.lock();
     guard.profiles.get(id).cloned()
 }
 
-pub fn try_get_offer<'a>(state: &'a State<SharedState>, id: &str) -> Option<JobOffer> {
+pub fn try_get_offer(state: &SharedState, id: &str) -> Option<JobOffer> {
     let guard = state.0.lock();
     guard.offers.get(id).cloned()
 }
 
-pub fn store_profile(state: &State<SharedState>, profile: CandidateProfile) {
+pub fn store_profile(state: &SharedState, profile: CandidateProfile) {
+    remember_name(&profile.full_name);
     let mut guard = state.0.lock();
-    guard.profiles.insert(profile.id.clone(), profile);
+    guard.search.index_profile(&profile);
+    let id = profile.id.clone();
+    let after = serde_json::to_value(&profile).ok();
+    let before = guard
+        .profiles
+        .insert(id.clone(), profile)
+        .and_then(|before| serde_json::to_value(before).ok());
+    guard
+        .journal
+        .record(JournalKind::Profile, &id, before, after);
 }
 
-pub fn store_offer(state: &State<SharedState>, offer: JobOffer) {
+pub fn store_offer(state: &SharedState, offer: JobOffer) {
     let mut guard = state.0.lock();
+    guard.search.index_offer(&offer);
     guard.offers.insert(offer.id.clone(), offer);
 }
 
-pub fn store_adaptation(
-    state: &State<SharedState>,
-    profile_id: &str,
-    adaptation: AdaptationResult,
-) {
+pub fn try_get_variant(state: &SharedState, id: &str) -> Option<ProfileVariant> {
+    let guard = state.0.lock();
+    guard.variants.get(id).cloned()
+}
+
+pub fn store_variant(state: &SharedState, variant: ProfileVariant) {
+    let mut guard = state.0.lock();
+    guard.variants.insert(variant.id.clone(), variant);
+}
+
+/// Enregistre une nouvelle variante ; renvoie `false` sans rien changer si l'id est déjà pris.
+pub fn insert_variant(state: &SharedState, variant: ProfileVariant) -> bool {
+    let mut guard = state.0.lock();
+    match guard.variants.entry(variant.id.clone()) {
+        Entry::Occupied(_) => false,
+        Entry::Vacant(entry) => {
+            entry.insert(variant);
+            true
+        }
+    }
+}
+
+pub fn remove_variant(state: &SharedState, id: &str) -> Option<ProfileVariant> {
+    let mut guard = state.0.lock();
+    let removed = guard.variants.remove(id)?;
+    remove_adaptation_locked(&mut guard, &adaptation_key(&removed.profile_id, Some(id)));
+    Some(removed)
+}
+
+pub fn list_profile_variants(state: &SharedState, profile_id: &str) -> Vec<ProfileVariant> {
+    let guard = state.0.lock();
+    let mut variants: Vec<ProfileVariant> = guard
+        .variants
+        .values()
+        .filter(|variant| variant.profile_id == profile_id)
+        .cloned()
+        .collect();
+    variants.sort_by(|a, b| a.name.cmp(&b.name));
+    variants
+}
+
+/// Clé de cache des adaptations : le profil seul, ou `profil:variante`.
+pub fn adaptation_key(profile_id: &str, variant_id: Option<&str>) -> String {
+    match variant_id {
+        Some(variant_id) => format!("{profile_id}:{variant_id}"),
+        None => profile_id.to_string(),
+    }
+}
+
+pub fn store_adaptation(state: &SharedState, profile_id: &str, adaptation: AdaptationResult) {
     let mut guard = state.0.lock();
     guard.adaptations.insert(profile_id.to_string(), adaptation);
+    guard.index_adaptation(profile_id);
+}
+
+/// Retire une adaptation du cache et de l'index, verrou déjà pris.
+pub fn remove_adaptation_locked(memory: &mut AppMemory, key: &str) -> Option<AdaptationResult> {
+    memory.search.remove(DocumentKind::Adaptation, key);
+    memory.adaptations.remove(key)
 }
 
-pub fn get_adaptation(state: &State<SharedState>, profile_id: &str) -> Option<AdaptationResult> {
+pub fn get_adaptation(state: &SharedState, profile_id: &str) -> Option<AdaptationResult> {
     let guard = state.0.lock();
     guard.adaptations.get(profile_id).cloned()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::json;
+
+    use super::{read_persisted, write_persisted, AppMemory};
+    use crate::{models::JobOffer, vault::Vault};
+
+    fn offer(id: &str, title: &str) -> JobOffer {
+        serde_json::from_value(json!({ "id": id, "title": title, "description": "" })).unwrap()
+    }
+
+    #[test]
+    fn concurrent_writers_are_merged_by_id() {
+        let dir = std::env::temp_dir().join(format!("cvgen-state-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("storage.json");
+        let vault = Vault::default();
+        let mut first = AppMemory::default();
+        first.offers.insert("o1".into(), offer("o1", "Rust"));
+        first.offers.insert("o2".into(), offer("o2", "Go"));
+        assert_eq!(write_persisted(&path, &vault, first).unwrap().generation, 1);
+
+        // L'application et le CLI partent du même fichier.
+        let mut gui: AppMemory = read_persisted(&path, &vault).unwrap().unwrap().into();
+        let mut cli = gui.clone();
+        cli.offers.insert("o3".into(), offer("o3", "Python"));
+        assert_eq!(write_persisted(&path, &vault, cli).unwrap().generation, 2);
+
+        gui.offers.insert("o1".into(), offer("o1", "Rust senior"));
+        gui.offers.remove("o2");
+        let merged = write_persisted(&path, &vault, gui).unwrap();
+        assert_eq!(merged.generation, 3);
+        let mut ids: Vec<&String> = merged.offers.keys().collect();
+        ids.sort();
+        assert_eq!(ids, ["o1", "o3"]);
+        assert_eq!(merged.offers["o1"].title, "Rust senior");
+
+        let reread = read_persisted(&path, &vault).unwrap().unwrap();
+        assert_eq!((reread.generation, reread.offers.len()), (3, 2));
+        assert!(!path.with_extension("json.tmp").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
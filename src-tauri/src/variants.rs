@@ -0,0 +1,308 @@
+use tauri::{command, State};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    messages::AppError,
+    models::{CandidateProfile, ProfileVariant},
+    state::{
+        adaptation_key, insert_variant, list_profile_variants, remove_adaptation_locked,
+        remove_variant, store_variant, try_get_profile, try_get_variant, SharedState,
+    },
+    vault::VaultState,
+};
+
+impl ProfileVariant {
+    /// Produit le profil concret obtenu en appliquant la variante sur `base`.
+    pub fn apply(&self, base: &CandidateProfile) -> CandidateProfile {
+        let mut resolved = base.clone();
+
+        if let Some(summary) = &self.summary {
+            resolved.summary = Some(summary.clone());
+            // Le résumé de la variante prime sur les traductions du profil de base.
+            resolved.summary_translations.clear();
+        }
+        if let Some(skills) = &self.skills {
+            resolved.skills = skills.clone();
+        }
+
+        resolved
+            .experiences
+            .retain(|experience| !self.hidden_experience_ids.contains(&experience.id));
+
+        if !self.experience_order.is_empty() {
+            let rank = |id: &str| {
+                self.experience_order
+                    .iter()
+                    .position(|ordered| ordered == id)
+                    .unwrap_or(usize::MAX)
+            };
+            // Tri stable : les expériences non listées gardent leur ordre d'origine, en fin de liste.
+            resolved
+                .experiences
+                .sort_by_key(|experience| rank(&experience.id));
+        }
+
+        resolved
+    }
+}
+
+/// Résout le profil à adapter : le profil de base, ou sa variante si `variant_id` est fourni.
+pub fn resolve_profile(
+    state: &SharedState,
+    profile_id: &str,
+    variant_id: Option<&str>,
+) -> Result<CandidateProfile, AppError> {
+    let base = try_get_profile(state, profile_id)
+        .ok_or_else(|| AppError::new("errors.profileNotFound").arg("id", profile_id))?;
+
+    let Some(variant_id) = variant_id else {
+        return Ok(base);
+    };
+    let variant = try_get_variant(state, variant_id)
+        .ok_or_else(|| AppError::new("errors.variantNotFound").arg("id", variant_id))?;
+    if variant.profile_id != profile_id {
+        return Err(AppError::new("errors.variantWrongProfile")
+            .arg("variant", variant_id)
+            .arg("profile", profile_id));
+    }
+
+    Ok(variant.apply(&base))
+}
+
+#[command]
+#[instrument(skip_all, fields(profile_id = %variant.profile_id))]
+pub fn create_variant(
+    variant: ProfileVariant,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+) -> Result<ProfileVariant, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    add_variant(&state, variant)
+}
+
+/// Crée la variante ; un id fourni par l'appelant ne doit pas déjà exister, quel qu'en soit le
+/// profil.
+fn add_variant(
+    state: &SharedState,
+    mut variant: ProfileVariant,
+) -> Result<ProfileVariant, AppError> {
+    if try_get_profile(state, &variant.profile_id).is_none() {
+        return Err(AppError::new("errors.profileNotFound").arg("id", &variant.profile_id));
+    }
+    if variant.name.trim().is_empty() {
+        return Err(AppError::new("errors.variantNameRequired"));
+    }
+    if variant.id.is_empty() {
+        variant.id = Uuid::new_v4().to_string();
+    }
+
+    if !insert_variant(state, variant.clone()) {
+        return Err(AppError::new("errors.variantExists").arg("id", &variant.id));
+    }
+    Ok(variant)
+}
+
+#[command]
+#[instrument(skip_all, fields(id = %variant.id))]
+pub fn update_variant(
+    variant: ProfileVariant,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+) -> Result<ProfileVariant, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    let existing = try_get_variant(&state, &variant.id)
+        .ok_or_else(|| AppError::new("errors.variantNotFound").arg("id", &variant.id))?;
+    if existing.profile_id != variant.profile_id {
+        return Err(AppError::new("errors.variantProfileImmutable"));
+    }
+    if variant.name.trim().is_empty() {
+        return Err(AppError::new("errors.variantNameRequired"));
+    }
+
+    store_variant(&state, variant.clone());
+    // L'adaptation en cache a été calculée sur l'ancienne surcouche, sauf si l'utilisateur
+    // l'a retouchée.
+    let key = adaptation_key(&variant.profile_id, Some(&variant.id));
+    let mut memory = state.0.lock();
+    if memory
+        .adaptations
+        .get(&key)
+        .is_some_and(|adaptation| !adaptation.has_user_edits())
+    {
+        remove_adaptation_locked(&mut memory, &key);
+    }
+    drop(memory);
+    Ok(variant)
+}
+
+#[command]
+#[instrument(skip_all, fields(id = %id))]
+pub fn get_variant(
+    id: String,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+) -> Result<ProfileVariant, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    try_get_variant(&state, &id)
+        .ok_or_else(|| AppError::new("errors.variantNotFound").arg("id", &id))
+}
+
+#[command]
+#[instrument(skip_all, fields(profile_id = %profile_id))]
+pub fn list_variants(
+    profile_id: String,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+) -> Result<Vec<ProfileVariant>, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    Ok(list_profile_variants(&state, &profile_id))
+}
+
+#[command]
+#[instrument(skip_all, fields(id = %id))]
+pub fn delete_variant(
+    id: String,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+) -> Result<(), AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    remove_variant(&state, &id)
+        .map(|_| ())
+        .ok_or_else(|| AppError::new("errors.variantNotFound").arg("id", &id))
+}
+
+#[command]
+#[instrument(skip_all, fields(profile_id = %profile_id, variant_id = ?variant_id))]
+pub fn resolve_variant(
+    profile_id: String,
+    variant_id: Option<String>,
+    state: State<'_, SharedState>,
+    vault: State<'_, VaultState>,
+) -> Result<CandidateProfile, AppError> {
+    vault.0.lock().ensure_unlocked()?;
+    resolve_profile(&state, &profile_id, variant_id.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::Mutex;
+
+    use super::add_variant;
+    use crate::{
+        models::{CandidateProfile, Experience, ProfileVariant},
+        state::{store_profile, try_get_variant, AppMemory, SharedState},
+    };
+
+    fn experience(id: &str) -> Experience {
+        Experience {
+            id: id.into(),
+            company: format!("Company {id}"),
+            role: "Engineer".into(),
+            start_date: "2020".into(),
+            end_date: None,
+            achievements: vec![],
+            technologies: vec![],
+            role_translations: Default::default(),
+            achievement_translations: Default::default(),
+        }
+    }
+
+    fn profile() -> CandidateProfile {
+        CandidateProfile {
+            id: "p1".into(),
+            full_name: "Jane Doe".into(),
+            email: None,
+            phone: None,
+            summary: Some("Base summary".into()),
+            experiences: vec![experience("a"), experience("b"), experience("c")],
+            skills: vec!["Rust".into(), "SQL".into()],
+            education: vec![],
+            projects: vec![],
+            languages: vec![],
+            language: None,
+            summary_translations: Default::default(),
+        }
+    }
+
+    #[test]
+    fn apply_hides_reorders_and_overrides() {
+        let variant = ProfileVariant {
+            id: "v1".into(),
+            profile_id: "p1".into(),
+            name: "data".into(),
+            summary: Some("Data summary".into()),
+            hidden_experience_ids: vec!["b".into()],
+            experience_order: vec!["c".into()],
+            skills: Some(vec!["Python".into()]),
+        };
+
+        let resolved = variant.apply(&profile());
+        let ids: Vec<&str> = resolved.experiences.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["c", "a"]);
+        assert_eq!(resolved.summary.as_deref(), Some("Data summary"));
+        assert_eq!(resolved.skills, ["Python"]);
+        assert_eq!(resolved.id, "p1");
+    }
+
+    #[test]
+    fn empty_variant_keeps_base_profile() {
+        let variant = ProfileVariant {
+            id: "v2".into(),
+            profile_id: "p1".into(),
+            name: "backend".into(),
+            summary: None,
+            hidden_experience_ids: vec![],
+            experience_order: vec![],
+            skills: None,
+        };
+
+        let resolved = variant.apply(&profile());
+        assert_eq!(resolved.experiences.len(), 3);
+        assert_eq!(resolved.summary.as_deref(), Some("Base summary"));
+        assert_eq!(resolved.skills, ["Rust", "SQL"]);
+    }
+
+    #[test]
+    fn create_rejects_an_existing_id() {
+        let state = SharedState(Mutex::new(AppMemory::default()));
+        store_profile(&state, profile());
+        store_profile(
+            &state,
+            CandidateProfile {
+                id: "p2".into(),
+                ..profile()
+            },
+        );
+        let variant = |profile_id: &str, name: &str| ProfileVariant {
+            id: "v1".into(),
+            profile_id: profile_id.into(),
+            name: name.into(),
+            summary: None,
+            hidden_experience_ids: vec![],
+            experience_order: vec![],
+            skills: None,
+        };
+
+        add_variant(&state, variant("p1", "data")).unwrap();
+        for (profile_id, name) in [("p1", "backend"), ("p2", "autre profil")] {
+            let error = add_variant(&state, variant(profile_id, name)).unwrap_err();
+            assert_eq!(error.key, "errors.variantExists");
+        }
+        let stored = try_get_variant(&state, "v1").unwrap();
+        assert_eq!(
+            (stored.profile_id.as_str(), stored.name.as_str()),
+            ("p1", "data")
+        );
+
+        let generated = add_variant(
+            &state,
+            ProfileVariant {
+                id: String::new(),
+                ..variant("p2", "autre profil")
+            },
+        )
+        .unwrap();
+        assert_ne!(generated.id, "v1");
+    }
+}
@@ -0,0 +1,654 @@
+//! Chiffrement optionnel des données persistées (`storage.json` et bibliothèque de CV).
+//!
+//! Une clé de données aléatoire chiffre les fichiers (XChaCha20-Poly1305). Elle est elle-même
+//! scellée par une clé dérivée de la phrase secrète (Argon2id) et stockée dans `vault.json` :
+//! changer de phrase secrète ne réécrit donc que ce fichier.
+//!
+//! Chaque fichier chiffré est lié à son nom (données associées) : on ne peut pas substituer un
+//! fichier à un autre. Une fois le chiffrement actif, un fichier en clair est refusé
+//! (`errors.vault.corrupted`) ; les fichiers en clair ou au format `CVGENENC1` (sans données
+//! associées) ne sont acceptés qu'à l'activation ou à la mise à niveau d'un coffre ancien.
+
+use std::{fs, path::Path};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng, Payload},
+    Key, XChaCha20Poly1305, XNonce,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Manager, State};
+use tracing::instrument;
+use zeroize::Zeroizing;
+
+use crate::{
+    commands::fs::{library_root, seal_library},
+    inbox::scan_inbox,
+    messages::AppError,
+    paths::AppPaths,
+    settings::{Settings, SettingsState},
+    state::{SharedState, STORAGE_CONTEXT},
+};
+
+const MAGIC: &[u8] = b"CVGENENC2";
+/// Ancien format, sans données associées.
+const LEGACY_MAGIC: &[u8] = b"CVGENENC1";
+/// Données associées de la clé de données scellée dans `vault.json`.
+const DATA_KEY_CONTEXT: &str = "vault.json";
+/// Version 1 : fichiers au format `CVGENENC1`, réécrits au déverrouillage suivant.
+const VAULT_VERSION: u32 = 2;
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+type SecretKey = Zeroizing<[u8; 32]>;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KdfParams {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    salt: String,
+    wrapped_key: String,
+}
+
+#[derive(Default)]
+pub struct Vault {
+    config: Option<VaultFile>,
+    key: Option<SecretKey>,
+}
+
+pub struct VaultState(pub Mutex<Vault>);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub locked: bool,
+}
+
+impl Vault {
+    pub fn is_locked(&self) -> bool {
+        self.config.is_some() && self.key.is_none()
+    }
+
+    pub fn status(&self) -> EncryptionStatus {
+        EncryptionStatus {
+            enabled: self.config.is_some(),
+            locked: self.is_locked(),
+        }
+    }
+
+    /// Déverrouille avec la phrase secrète, vérifiée même si la clé est déjà en mémoire ;
+    /// renvoie `false` dans ce cas.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<bool, AppError> {
+        let config = self
+            .config
+            .as_ref()
+            .ok_or_else(|| AppError::new("errors.vault.notEnabled"))?;
+        let key = unwrap_data_key(passphrase, config)?;
+        if self.key.is_some() {
+            return Ok(false);
+        }
+        self.key = Some(key);
+        Ok(true)
+    }
+
+    pub fn ensure_unlocked(&self) -> Result<(), AppError> {
+        if self.is_locked() {
+            return Err(AppError::new("errors.vault.locked"));
+        }
+        Ok(())
+    }
+
+    /// Coffre dont les fichiers sont encore au format `CVGENENC1`.
+    fn is_legacy(&self) -> bool {
+        self.config
+            .as_ref()
+            .is_some_and(|config| config.version < VAULT_VERSION)
+    }
+
+    fn key(&self) -> Result<&SecretKey, AppError> {
+        self.key
+            .as_ref()
+            .ok_or_else(|| AppError::new("errors.vault.locked"))
+    }
+
+    /// Chiffre `plaintext` pour le fichier `context` si le chiffrement est actif, le renvoie
+    /// tel quel sinon.
+    pub fn encode(&self, plaintext: &[u8], context: &str) -> Result<Vec<u8>, AppError> {
+        match self.config {
+            None => Ok(plaintext.to_vec()),
+            Some(_) => seal(self.key()?, plaintext, context),
+        }
+    }
+
+    /// Déchiffre le fichier `context`. Chiffrement actif, tout fichier qui n'a pas été scellé
+    /// pour ce nom avec la clé de données est refusé, y compris un fichier en clair.
+    pub fn decode(&self, data: Vec<u8>, context: &str) -> Result<Vec<u8>, AppError> {
+        match self.config {
+            None if is_sealed(&data) => Err(AppError::new("errors.vault.notEnabled")),
+            None => Ok(data),
+            Some(_) => open(self.key()?, &data, context),
+        }
+    }
+
+    /// Comme `decode`, en acceptant aussi les fichiers en clair et au format `CVGENENC1` :
+    /// réservé à l'activation du chiffrement et à la mise à niveau d'un coffre ancien.
+    pub(crate) fn decode_for_migration(
+        &self,
+        data: Vec<u8>,
+        context: &str,
+    ) -> Result<Vec<u8>, AppError> {
+        if data.starts_with(LEGACY_MAGIC) {
+            return open_legacy(self.key()?, &data);
+        }
+        if !is_sealed(&data) {
+            return Ok(data);
+        }
+        self.decode(data, context)
+    }
+}
+
+/// Fichier chiffré, quel que soit son format.
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(MAGIC) || data.starts_with(LEGACY_MAGIC)
+}
+
+/// Fichier chiffré au format courant.
+pub(crate) fn is_current(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+fn seal(key: &[u8; 32], plaintext: &[u8], context: &str) -> Result<Vec<u8>, AppError> {
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let payload = Payload {
+        msg: plaintext,
+        aad: context.as_bytes(),
+    };
+    let ciphertext = cipher
+        .encrypt(&nonce, payload)
+        .map_err(|error| AppError::new("errors.vault.crypto").arg("details", error))?;
+
+    let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+fn open(key: &[u8; 32], data: &[u8], context: &str) -> Result<Vec<u8>, AppError> {
+    open_with(key, data, MAGIC, context.as_bytes())
+}
+
+fn open_legacy(key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>, AppError> {
+    open_with(key, data, LEGACY_MAGIC, &[])
+}
+
+fn open_with(key: &[u8; 32], data: &[u8], magic: &[u8], aad: &[u8]) -> Result<Vec<u8>, AppError> {
+    let body = data
+        .strip_prefix(magic)
+        .filter(|body| body.len() > NONCE_LEN)
+        .ok_or_else(|| AppError::new("errors.vault.corrupted"))?;
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    let payload = Payload {
+        msg: ciphertext,
+        aad,
+    };
+    XChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(XNonce::from_slice(nonce), payload)
+        .map_err(|_| AppError::new("errors.vault.corrupted"))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], kdf: KdfParams) -> Result<SecretKey, AppError> {
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|error| AppError::new("errors.vault.crypto").arg("details", error))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|error| AppError::new("errors.vault.crypto").arg("details", error))?;
+    Ok(key)
+}
+
+fn wrap_data_key(
+    passphrase: &str,
+    data_key: &[u8; 32],
+    kdf: KdfParams,
+) -> Result<VaultFile, AppError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(AppError::new("errors.vault.weakPassphrase").arg("min", MIN_PASSPHRASE_LEN));
+    }
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let wrapping_key = derive_key(passphrase, &salt, kdf)?;
+
+    Ok(VaultFile {
+        version: VAULT_VERSION,
+        kdf,
+        salt: STANDARD.encode(salt),
+        wrapped_key: STANDARD.encode(seal(&wrapping_key, data_key, DATA_KEY_CONTEXT)?),
+    })
+}
+
+fn unwrap_data_key(passphrase: &str, config: &VaultFile) -> Result<SecretKey, AppError> {
+    let decode = |value: &str| {
+        STANDARD
+            .decode(value)
+            .map_err(|_| AppError::new("errors.vault.corrupted"))
+    };
+    let wrapping_key = derive_key(passphrase, &decode(&config.salt)?, config.kdf)?;
+    let wrapped = decode(&config.wrapped_key)?;
+    let data_key = if wrapped.starts_with(LEGACY_MAGIC) {
+        open_legacy(&wrapping_key, &wrapped)
+    } else {
+        open(&wrapping_key, &wrapped, DATA_KEY_CONTEXT)
+    };
+    let data_key =
+        Zeroizing::new(data_key.map_err(|_| AppError::new("errors.vault.wrongPassphrase"))?);
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    if data_key.len() != key.len() {
+        return Err(AppError::new("errors.vault.corrupted"));
+    }
+    key.copy_from_slice(&data_key);
+    Ok(key)
+}
+
+/// Sel et paramètres d'une clé dérivée d'une phrase secrète, indépendante du coffre : une
+/// archive d'espace de travail chiffrée ainsi se relit sur une autre installation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PassphraseKdf {
+    kdf: KdfParams,
+    salt: String,
+}
+
+/// Clé dérivée d'une phrase secrète par `PassphraseKdf`.
+pub struct PassphraseKey(SecretKey);
+
+impl PassphraseKdf {
+    /// Nouveau sel ; la phrase secrète doit avoir la même longueur minimale que celle du coffre.
+    pub fn create(passphrase: &str) -> Result<(Self, PassphraseKey), AppError> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(AppError::new("errors.vault.weakPassphrase").arg("min", MIN_PASSPHRASE_LEN));
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let kdf = KdfParams::default();
+        let key = derive_key(passphrase, &salt, kdf)?;
+        let params = Self {
+            kdf,
+            salt: STANDARD.encode(salt),
+        };
+        Ok((params, PassphraseKey(key)))
+    }
+
+    pub fn derive(&self, passphrase: &str) -> Result<PassphraseKey, AppError> {
+        let salt = STANDARD
+            .decode(&self.salt)
+            .map_err(|_| AppError::new("errors.vault.corrupted"))?;
+        derive_key(passphrase, &salt, self.kdf).map(PassphraseKey)
+    }
+}
+
+impl PassphraseKey {
+    pub fn seal(&self, plaintext: &[u8], context: &str) -> Result<Vec<u8>, AppError> {
+        seal(&self.0, plaintext, context)
+    }
+
+    /// `errors.vault.corrupted` si la phrase secrète est fausse ou le contenu altéré.
+    pub fn open(&self, data: &[u8], context: &str) -> Result<Vec<u8>, AppError> {
+        open(&self.0, data, context)
+    }
+}
+
+/// Lit `vault.json` ; le coffre renvoyé est verrouillé si le chiffrement est actif.
+pub fn read_vault(paths: &AppPaths) -> anyhow::Result<Vault> {
+    let path = paths.vault_file();
+    if !path.exists() {
+        return Ok(Vault::default());
+    }
+    let config: VaultFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+    Ok(Vault {
+        config: Some(config),
+        key: None,
+    })
+}
+
+/// Lit `vault.json` au démarrage ; si le chiffrement est actif, l'application démarre verrouillée.
+pub fn load_vault(app: &AppHandle) -> anyhow::Result<()> {
+    *app.state::<VaultState>().0.lock() = read_vault(&AppPaths::from_app(app)?)?;
+    Ok(())
+}
+
+fn save_vault(app: &AppHandle, config: &VaultFile) -> Result<(), AppError> {
+    let paths = AppPaths::from_app(app)
+        .map_err(|error| AppError::new("errors.vault.save").arg("details", error))?;
+    write_vault(&paths, config)
+}
+
+fn write_vault(paths: &AppPaths, config: &VaultFile) -> Result<(), AppError> {
+    let write = || -> anyhow::Result<()> {
+        let path = paths.vault_file();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(config)?)?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    };
+    write().map_err(|error| AppError::new("errors.vault.save").arg("details", error))
+}
+
+/// Réécrit chiffrés `storage.json` et les fichiers de la bibliothèque encore en clair.
+fn migrate_plaintext(app: &AppHandle) -> Result<(), AppError> {
+    crate::save_state(app)
+        .map_err(|error| AppError::new("errors.vault.migrate").arg("details", error))?;
+    let settings = app.state::<SettingsState>().0.lock().clone();
+    let vault = app.state::<VaultState>();
+    let vault = vault.0.lock();
+    seal_library(&settings, &vault)
+}
+
+/// Activation interrompue : remet en clair ce qui a déjà été chiffré avec la nouvelle clé,
+/// qui n'a pas encore été enregistrée dans `vault.json` et serait sinon perdue.
+fn revert_migration(app: &AppHandle) {
+    let settings = app.state::<SettingsState>().0.lock().clone();
+    let sealed = std::mem::take(&mut *app.state::<VaultState>().0.lock());
+    let revert = || -> Result<(), AppError> {
+        let paths = AppPaths::from_app(app)
+            .map_err(|error| AppError::new("errors.vault.migrate").arg("details", error))?;
+        let plain = Vault::default();
+        rewrite(&paths.storage_file(), STORAGE_CONTEXT, &sealed, &plain)?;
+        crate::commands::fs::unseal_library(&settings, &sealed)
+    };
+    if let Err(error) = revert() {
+        tracing::error!(key = error.key, "Chiffrement: retour en clair incomplet");
+    }
+}
+
+/// Réécrit `path` chiffré par `to`, après l'avoir lu avec `from` (formats anciens acceptés).
+fn rewrite(path: &Path, context: &str, from: &Vault, to: &Vault) -> Result<(), AppError> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let migrate =
+        |error: std::io::Error| AppError::new("errors.vault.migrate").arg("details", error);
+    let content = from.decode_for_migration(fs::read(path).map_err(migrate)?, context)?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, to.encode(&content, context)?).map_err(migrate)?;
+    fs::rename(&tmp_path, path).map_err(migrate)
+}
+
+/// Coffre de version 1 : rechiffre `storage.json` et la bibliothèque au format courant, lié
+/// au nom de chaque fichier, puis seulement enregistre la nouvelle version de `vault.json`.
+pub fn upgrade_legacy_vault(
+    paths: &AppPaths,
+    settings: &Settings,
+    vault: &mut Vault,
+) -> Result<(), AppError> {
+    if !vault.is_legacy() {
+        return Ok(());
+    }
+    rewrite(&paths.storage_file(), STORAGE_CONTEXT, vault, vault)?;
+    seal_library(settings, vault)?;
+    let Some(config) = vault.config.as_mut() else {
+        return Ok(());
+    };
+    config.version = VAULT_VERSION;
+    write_vault(paths, config)
+}
+
+#[command]
+#[instrument(skip_all)]
+pub fn encryption_status(vault: State<'_, VaultState>) -> EncryptionStatus {
+    vault.0.lock().status()
+}
+
+#[command]
+#[instrument(skip_all)]
+pub fn enable_encryption(
+    passphrase: String,
+    vault: State<'_, VaultState>,
+    app: AppHandle,
+) -> Result<EncryptionStatus, AppError> {
+    let passphrase = Zeroizing::new(passphrase);
+    if vault.0.lock().config.is_some() {
+        return Err(AppError::new("errors.vault.alreadyEnabled"));
+    }
+    // Un dépôt git de la bibliothèque garde les versions en clair dans ses objets.
+    let settings = app.state::<SettingsState>().0.lock().clone();
+    if settings.library_git || library_root(&settings)?.join(".git").exists() {
+        return Err(AppError::new("errors.vault.libraryGit"));
+    }
+
+    let data_key: SecretKey = Zeroizing::new(XChaCha20Poly1305::generate_key(&mut OsRng).into());
+    let config = wrap_data_key(&passphrase, &data_key, KdfParams::default())?;
+    {
+        let mut guard = vault.0.lock();
+        guard.config = Some(config.clone());
+        guard.key = Some(data_key);
+    }
+
+    // `vault.json` n'est écrit qu'une fois les données chiffrées : un échec ne laisse pas un
+    // coffre actif devant des fichiers en clair, désormais refusés.
+    if let Err(error) = migrate_plaintext(&app).and_then(|_| save_vault(&app, &config)) {
+        revert_migration(&app);
+        return Err(error);
+    }
+    Ok(vault.0.lock().status())
+}
+
+#[command]
+#[instrument(skip_all)]
+pub fn unlock(
+    passphrase: String,
+    vault: State<'_, VaultState>,
+    app: AppHandle,
+) -> Result<EncryptionStatus, AppError> {
+    let passphrase = Zeroizing::new(passphrase);
+    {
+        let mut guard = vault.0.lock();
+        // Déjà déverrouillé : l'état en mémoire, pas encore enregistré, ne doit pas être
+        // remplacé par celui du disque.
+        if !guard.unlock(&passphrase)? {
+            return Ok(guard.status());
+        }
+        if guard.is_legacy() {
+            let paths = AppPaths::from_app(&app)
+                .map_err(|error| AppError::new("errors.vault.migrate").arg("details", error))?;
+            let settings = app.state::<SettingsState>().0.lock().clone();
+            upgrade_legacy_vault(&paths, &settings, &mut guard)?;
+        }
+    }
+
+    crate::load_state(&app)
+        .map_err(|error| AppError::new("errors.vault.load").arg("details", error))?;
+    // Fichiers déposés pendant le verrouillage.
+    scan_inbox(&app);
+    Ok(vault.0.lock().status())
+}
+
+#[command]
+#[instrument(skip_all)]
+pub fn lock(
+    vault: State<'_, VaultState>,
+    state: State<'_, SharedState>,
+    app: AppHandle,
+) -> Result<EncryptionStatus, AppError> {
+    if vault.0.lock().config.is_none() {
+        return Err(AppError::new("errors.vault.notEnabled"));
+    }
+    if !vault.0.lock().is_locked() {
+        crate::save_state(&app)
+            .map_err(|error| AppError::new("errors.vault.migrate").arg("details", error))?;
+    }
+
+    *state.0.lock() = Default::default();
+    vault.0.lock().key = None;
+    Ok(vault.0.lock().status())
+}
+
+#[command]
+#[instrument(skip_all)]
+pub fn change_passphrase(
+    current: String,
+    new_passphrase: String,
+    vault: State<'_, VaultState>,
+    app: AppHandle,
+) -> Result<EncryptionStatus, AppError> {
+    let current = Zeroizing::new(current);
+    let new_passphrase = Zeroizing::new(new_passphrase);
+    let mut guard = vault.0.lock();
+    let config = guard
+        .config
+        .as_ref()
+        .ok_or_else(|| AppError::new("errors.vault.notEnabled"))?;
+
+    let data_key = unwrap_data_key(&current, config)?;
+    let mut rewrapped = wrap_data_key(&new_passphrase, &data_key, KdfParams::default())?;
+    // Les fichiers ne changent pas : un coffre ancien reste à mettre à niveau.
+    rewrapped.version = config.version;
+    save_vault(&app, &rewrapped)?;
+    guard.config = Some(rewrapped);
+    Ok(guard.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use zeroize::Zeroizing;
+
+    use super::{
+        is_sealed, open, seal, unwrap_data_key, wrap_data_key, KdfParams, Vault, LEGACY_MAGIC,
+    };
+
+    // Paramètres Argon2 réduits pour garder les tests rapides.
+    const FAST_KDF: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn unlocked_vault() -> Vault {
+        let data_key = Zeroizing::new([5u8; 32]);
+        Vault {
+            config: Some(wrap_data_key("correct horse", &data_key, FAST_KDF).unwrap()),
+            key: Some(data_key),
+        }
+    }
+
+    #[test]
+    fn seal_round_trips_and_detects_tampering() {
+        let key = [7u8; 32];
+        let mut sealed = seal(&key, br#"{"fullName":"Jane"}"#, "storage.json").unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(
+            open(&key, &sealed, "storage.json").unwrap(),
+            br#"{"fullName":"Jane"}"#
+        );
+        // Un fichier chiffré ne peut pas être substitué à un autre.
+        assert_eq!(
+            open(&key, &sealed, "library/cv1.json").unwrap_err().key,
+            "errors.vault.corrupted"
+        );
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert_eq!(
+            open(&key, &sealed, "storage.json").unwrap_err().key,
+            "errors.vault.corrupted"
+        );
+    }
+
+    #[test]
+    fn wrapped_key_requires_the_right_passphrase() {
+        let data_key = Zeroizing::new([3u8; 32]);
+        let config = wrap_data_key("correct horse", &data_key, FAST_KDF).unwrap();
+
+        assert_eq!(
+            *unwrap_data_key("correct horse", &config).unwrap(),
+            *data_key
+        );
+        assert_eq!(
+            unwrap_data_key("wrong horse!", &config).unwrap_err().key,
+            "errors.vault.wrongPassphrase"
+        );
+        assert_eq!(
+            wrap_data_key("short", &data_key, FAST_KDF).unwrap_err().key,
+            "errors.vault.weakPassphrase"
+        );
+    }
+
+    #[test]
+    fn plaintext_passes_through_decode() {
+        let vault = Vault::default();
+        assert_eq!(vault.decode(b"{}".to_vec(), "storage.json").unwrap(), b"{}");
+        assert_eq!(vault.encode(b"{}", "storage.json").unwrap(), b"{}");
+    }
+
+    #[test]
+    fn enabled_vault_accepts_plaintext_only_for_migration() {
+        let vault = unlocked_vault();
+        assert_eq!(
+            vault
+                .decode(b"{}".to_vec(), "storage.json")
+                .unwrap_err()
+                .key,
+            "errors.vault.corrupted"
+        );
+        assert_eq!(
+            vault
+                .decode_for_migration(b"{}".to_vec(), "storage.json")
+                .unwrap(),
+            b"{}"
+        );
+
+        // Ancien format sans données associées : lisible seulement à la mise à niveau.
+        let mut legacy = seal(&[5u8; 32], b"{}", "").unwrap();
+        legacy[..LEGACY_MAGIC.len()].copy_from_slice(LEGACY_MAGIC);
+        assert!(vault.decode(legacy.clone(), "storage.json").is_err());
+        assert_eq!(
+            vault.decode_for_migration(legacy, "storage.json").unwrap(),
+            b"{}"
+        );
+
+        let sealed = vault.encode(b"{}", "storage.json").unwrap();
+        assert_eq!(vault.decode(sealed, "storage.json").unwrap(), b"{}");
+    }
+
+    #[test]
+    fn unlocking_again_checks_the_passphrase() {
+        let mut vault = unlocked_vault();
+        assert_eq!(
+            vault.unlock("wrong horse!").unwrap_err().key,
+            "errors.vault.wrongPassphrase"
+        );
+        assert!(!vault.unlock("correct horse").unwrap());
+
+        vault.key = None;
+        assert!(vault.unlock("correct horse").unwrap());
+        assert!(!vault.is_locked());
+    }
+}
@@ -0,0 +1,848 @@
+//! Sauvegarde et restauration de l'espace de travail complet dans une archive zip.
+//!
+//! L'archive contient `workspace.json` (version du format, date, et pour chaque fichier sa
+//! taille et son empreinte SHA-256), `storage.json` (état courant), `settings.json`, les
+//! fichiers de la bibliothèque (`library/`, `manifest.json` compris) et les documents générés
+//! (`exports/`, fichiers `cvgen_*` du dossier d'export). Les fichiers y sont déchiffrés, puis,
+//! si une phrase secrète est donnée (obligatoire quand le chiffrement est actif), chacun est
+//! rechiffré par une clé dérivée de celle-ci : l'archive se relit sur une autre installation.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{command, AppHandle, State};
+use tracing::instrument;
+use zeroize::Zeroizing;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    commands::fs::{
+        cv_file_path, ensure_library_dir, library_context, library_files, load_manifest,
+        save_manifest, Manifest,
+    },
+    library_history::record_change,
+    messages::AppError,
+    settings::{apply_settings, Settings, SettingsState},
+    state::{decode_persisted, encode_persisted, AppMemory, PersistedData, SharedState},
+    vault::{PassphraseKdf, Vault, VaultState},
+};
+
+/// Version du format d'archive ; une archive plus récente est refusée.
+pub const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "workspace.json";
+const STORAGE_FILE: &str = "storage.json";
+const SETTINGS_FILE: &str = "settings.json";
+const LIBRARY_DIR: &str = "library";
+const EXPORTS_DIR: &str = "exports";
+/// Préfixe des documents écrits par l'exporteur Python ; seuls ceux-là sont archivés.
+const EXPORT_PREFIX: &str = "cvgen_";
+const LIBRARY_MANIFEST: &str = "manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceManifest {
+    pub format: u32,
+    pub created_at: String,
+    pub app_version: String,
+    /// Fichiers chiffrés par la phrase secrète de l'archive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<PassphraseKdf>,
+    /// Tailles et empreintes du contenu stocké, chiffré le cas échéant.
+    pub files: Vec<ArchivedFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedFile {
+    /// Chemin dans l'archive, séparé par `/`.
+    pub path: String,
+    pub size: u64,
+    /// SHA-256 du contenu, en hexadécimal.
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+    /// Ajoute les éléments absents ; en cas de conflit, la version locale est conservée.
+    Merge,
+    /// Remplace l'état, la bibliothèque et les préférences par ceux de l'archive.
+    Replace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkspaceItem {
+    Profile,
+    Offer,
+    Adaptation,
+    Variant,
+    Cv,
+    Export,
+}
+
+/// Élément présent des deux côtés avec un contenu différent.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportConflict {
+    pub kind: WorkspaceItem,
+    /// Identifiant (clé `profil[:variante]` pour une adaptation, nom de fichier pour un export).
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCounts {
+    pub profiles: usize,
+    pub offers: usize,
+    pub adaptations: usize,
+    pub variants: usize,
+    pub cvs: usize,
+    pub exports: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub mode: ImportMode,
+    pub imported: ImportCounts,
+    /// Fusion uniquement : éléments laissés dans leur version locale.
+    pub conflicts: Vec<ImportConflict>,
+    pub settings_replaced: bool,
+}
+
+/// Fichiers à archiver : `(chemin dans l'archive, contenu)`.
+type ArchiveFiles = Vec<(String, Vec<u8>)>;
+
+/// Contenu vérifié d'une archive, clé : chemin dans l'archive.
+struct ArchiveContent {
+    manifest: WorkspaceManifest,
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+fn write_error(error: impl std::fmt::Display) -> AppError {
+    AppError::new("errors.workspace.write").arg("details", error)
+}
+
+fn read_error(error: impl std::fmt::Display) -> AppError {
+    AppError::new("errors.workspace.read").arg("details", error)
+}
+
+/// Dossier des documents générés ; même valeur par défaut que l'exporteur Python.
+fn exports_root(settings: &Settings) -> Option<PathBuf> {
+    settings
+        .export_dir
+        .clone()
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cvgen").join("exports")))
+}
+
+/// Nom de fichier simple (sans dossier) pour un chemin `dossier/nom` de l'archive.
+fn archived_name<'a>(path: &'a str, dir: &str) -> Option<&'a str> {
+    let name = path.strip_prefix(dir)?.strip_prefix('/')?;
+    let valid = !name.is_empty()
+        && Path::new(name).file_name().and_then(|file| file.to_str()) == Some(name);
+    valid.then_some(name)
+}
+
+/// Fichiers de l'espace de travail, déchiffrés.
+fn collect_files(
+    settings: &Settings,
+    vault: &Vault,
+    memory: AppMemory,
+) -> Result<ArchiveFiles, AppError> {
+    let mut files = vec![(
+        STORAGE_FILE.to_string(),
+        encode_persisted(&Vault::default(), memory).map_err(write_error)?,
+    )];
+    files.push((
+        SETTINGS_FILE.to_string(),
+        serde_json::to_vec_pretty(&exported_settings(settings)).map_err(write_error)?,
+    ));
+    for path in library_files(settings)? {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let content = vault.decode(
+            fs::read(&path).map_err(write_error)?,
+            &library_context(name),
+        )?;
+        files.push((format!("{LIBRARY_DIR}/{name}"), content));
+    }
+    if let Some(dir) = exports_root(settings).filter(|dir| dir.is_dir()) {
+        let mut exports: Vec<(String, Vec<u8>)> = Vec::new();
+        for entry in fs::read_dir(&dir).map_err(write_error)?.flatten() {
+            let path = entry.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if path.is_file() && name.starts_with(EXPORT_PREFIX) {
+                let content = fs::read(&path).map_err(write_error)?;
+                exports.push((format!("{EXPORTS_DIR}/{name}"), content));
+            }
+        }
+        exports.sort_by(|a, b| a.0.cmp(&b.0));
+        files.extend(exports);
+    }
+    Ok(files)
+}
+
+/// Chiffre chaque fichier par une clé dérivée de `passphrase`, lié à son chemin dans l'archive.
+fn seal_files(
+    files: ArchiveFiles,
+    passphrase: &str,
+) -> Result<(PassphraseKdf, ArchiveFiles), AppError> {
+    let (kdf, key) = PassphraseKdf::create(passphrase)?;
+    let mut sealed = Vec::with_capacity(files.len());
+    for (path, content) in files {
+        let content = key.seal(&content, &path)?;
+        sealed.push((path, content));
+    }
+    Ok((kdf, sealed))
+}
+
+fn build_manifest(
+    files: &[(String, Vec<u8>)],
+    encryption: Option<PassphraseKdf>,
+) -> WorkspaceManifest {
+    WorkspaceManifest {
+        format: FORMAT_VERSION,
+        created_at: Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        encryption,
+        files: files
+            .iter()
+            .map(|(path, content)| ArchivedFile {
+                path: path.clone(),
+                size: content.len() as u64,
+                sha256: sha256_hex(content),
+            })
+            .collect(),
+    }
+}
+
+/// Écrit l'archive à côté de `path` puis la met en place : pas d'archive tronquée en cas d'échec.
+fn write_zip(
+    path: &Path,
+    manifest: &WorkspaceManifest,
+    files: &[(String, Vec<u8>)],
+) -> Result<(), AppError> {
+    let tmp_path = path.with_extension("zip.tmp");
+    let write = || -> Result<(), AppError> {
+        let mut zip = ZipWriter::new(fs::File::create(&tmp_path).map_err(write_error)?);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.start_file(MANIFEST_FILE, options)
+            .map_err(write_error)?;
+        zip.write_all(&serde_json::to_vec_pretty(manifest).map_err(write_error)?)
+            .map_err(write_error)?;
+        for (name, content) in files {
+            zip.start_file(name.as_str(), options)
+                .map_err(write_error)?;
+            zip.write_all(content).map_err(write_error)?;
+        }
+        zip.finish().map_err(write_error)?;
+        fs::rename(&tmp_path, path).map_err(write_error)
+    };
+    write().inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+/// Lit l'archive et vérifie la taille et l'empreinte de chaque fichier annoncé.
+fn read_archive(path: &Path) -> Result<ArchiveContent, AppError> {
+    let mut zip = ZipArchive::new(fs::File::open(path).map_err(read_error)?).map_err(read_error)?;
+    let mut read_entry = |name: &str| -> Result<Option<Vec<u8>>, AppError> {
+        let mut entry = match zip.by_name(name) {
+            Ok(entry) => entry,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(error) => return Err(read_error(error)),
+        };
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).map_err(read_error)?;
+        Ok(Some(content))
+    };
+
+    let manifest = read_entry(MANIFEST_FILE)?
+        .ok_or_else(|| AppError::new("errors.workspace.missingFile").arg("path", MANIFEST_FILE))?;
+    let manifest: WorkspaceManifest = serde_json::from_slice(&manifest)
+        .map_err(|error| AppError::new("errors.workspace.invalid").arg("details", error))?;
+    if manifest.format > FORMAT_VERSION {
+        return Err(AppError::new("errors.workspace.unsupportedFormat")
+            .arg("format", manifest.format)
+            .arg("supported", FORMAT_VERSION));
+    }
+
+    let mut files = BTreeMap::new();
+    for file in &manifest.files {
+        let content = read_entry(&file.path)?
+            .ok_or_else(|| AppError::new("errors.workspace.missingFile").arg("path", &file.path))?;
+        if content.len() as u64 != file.size || sha256_hex(&content) != file.sha256 {
+            return Err(AppError::new("errors.workspace.checksum").arg("path", &file.path));
+        }
+        files.insert(file.path.clone(), content);
+    }
+    Ok(ArchiveContent { manifest, files })
+}
+
+/// Contenu déchiffré de l'archive par sa phrase secrète, ou tel quel si elle n'est pas chiffrée.
+fn open_archive(
+    archive: ArchiveContent,
+    passphrase: Option<&str>,
+) -> Result<BTreeMap<String, Vec<u8>>, AppError> {
+    if let Some(encryption) = &archive.manifest.encryption {
+        let passphrase =
+            passphrase.ok_or_else(|| AppError::new("errors.workspace.passphraseRequired"))?;
+        let key = encryption.derive(passphrase)?;
+        return archive
+            .files
+            .into_iter()
+            .map(|(path, content)| {
+                let content = key
+                    .open(&content, &path)
+                    .map_err(|_| AppError::new("errors.workspace.wrongPassphrase"))?;
+                Ok((path, content))
+            })
+            .collect();
+    }
+    Ok(archive.files)
+}
+
+fn same_content<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Ajoute à `local` les éléments absents ; renvoie leur nombre.
+fn merge_items<T: Serialize>(
+    kind: WorkspaceItem,
+    local: &mut HashMap<String, T>,
+    incoming: HashMap<String, T>,
+    conflicts: &mut Vec<ImportConflict>,
+) -> usize {
+    let mut added = 0;
+    for (id, item) in incoming {
+        match local.get(&id) {
+            None => {
+                local.insert(id, item);
+                added += 1;
+            }
+            Some(existing) if same_content(existing, &item) => {}
+            Some(_) => conflicts.push(ImportConflict { kind, id }),
+        }
+    }
+    added
+}
+
+/// Fusionne l'état de l'archive ; le journal d'annulation local est conservé tel quel.
+fn merge_state(
+    local: &mut PersistedData,
+    incoming: PersistedData,
+    counts: &mut ImportCounts,
+    conflicts: &mut Vec<ImportConflict>,
+) {
+    counts.profiles = merge_items(
+        WorkspaceItem::Profile,
+        &mut local.profiles,
+        incoming.profiles,
+        conflicts,
+    );
+    counts.offers = merge_items(
+        WorkspaceItem::Offer,
+        &mut local.offers,
+        incoming.offers,
+        conflicts,
+    );
+    counts.adaptations = merge_items(
+        WorkspaceItem::Adaptation,
+        &mut local.adaptations,
+        incoming.adaptations,
+        conflicts,
+    );
+    counts.variants = merge_items(
+        WorkspaceItem::Variant,
+        &mut local.variants,
+        incoming.variants,
+        conflicts,
+    );
+}
+
+/// Préférences archivées : sans le jeton de l'API HTTP ni la clé du LLM.
+fn exported_settings(settings: &Settings) -> Settings {
+    let mut exported = settings.clone();
+    exported.http_api.token = None;
+    exported.llm.api_key = None;
+    exported
+}
+
+/// Préférences de l'archive, sans les emplacements, l'interpréteur ni les secrets propres à
+/// cette machine.
+fn imported_settings(current: &Settings, mut incoming: Settings) -> Settings {
+    incoming.http_api.token = current.http_api.token.clone();
+    incoming.llm.api_key = current.llm.api_key.clone();
+    Settings {
+        python_command: current.python_command.clone(),
+        library_dir: current.library_dir.clone(),
+        library_git: current.library_git,
+        export_dir: current.export_dir.clone(),
+        inbox_dir: current.inbox_dir.clone(),
+        ..incoming
+    }
+}
+
+/// Documents de la bibliothèque de l'archive : `(nom de fichier, contenu)`.
+fn library_documents(files: &BTreeMap<String, Vec<u8>>) -> Vec<(String, Vec<u8>)> {
+    files
+        .iter()
+        .filter_map(|(path, content)| {
+            archived_name(path, LIBRARY_DIR).map(|name| (name.to_string(), content.clone()))
+        })
+        .collect()
+}
+
+/// Fusion : ajoute les CV absents et leurs entrées de manifest ; les CV locaux sont conservés.
+fn merge_library(
+    documents: Vec<(String, Vec<u8>)>,
+    settings: &Settings,
+    vault: &Vault,
+    counts: &mut ImportCounts,
+    conflicts: &mut Vec<ImportConflict>,
+) -> Result<(), AppError> {
+    ensure_library_dir(settings)?;
+    let mut archived_manifest = None;
+    let mut imported = Vec::new();
+    for (name, content) in documents {
+        if name == LIBRARY_MANIFEST {
+            archived_manifest = Some(content);
+            continue;
+        }
+        let Some(id) = name.strip_suffix(".json") else {
+            continue;
+        };
+        let path = cv_file_path(settings, id)?;
+        if path.exists() {
+            let local = vault.decode(
+                fs::read(&path).map_err(read_error)?,
+                &library_context(&name),
+            )?;
+            if local != content {
+                conflicts.push(ImportConflict {
+                    kind: WorkspaceItem::Cv,
+                    id: id.to_string(),
+                });
+            }
+            continue;
+        }
+        fs::write(&path, vault.encode(&content, &library_context(&name))?).map_err(write_error)?;
+        imported.push(id.to_string());
+    }
+    counts.cvs = imported.len();
+
+    let Some(archived_manifest) = archived_manifest else {
+        return Ok(());
+    };
+    let archived: Manifest = serde_json::from_slice(&archived_manifest)
+        .map_err(|error| AppError::new("errors.workspace.invalid").arg("details", error))?;
+    let mut manifest = load_manifest(settings, vault)?;
+    for id in imported {
+        if let Some(entry) = archived.items.get(&id) {
+            manifest.items.insert(id, entry.clone());
+        }
+    }
+    save_manifest(settings, vault, &manifest)
+}
+
+/// Dossier temporaire à côté de la bibliothèque, sur le même volume.
+fn sibling_dir(root: &Path, label: &str) -> PathBuf {
+    let name = root
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("library");
+    root.with_file_name(format!(".{name}.{label}-{}", uuid::Uuid::new_v4()))
+}
+
+/// Remplacement : la nouvelle bibliothèque est écrite dans un dossier voisin, puis échangée
+/// fichier par fichier avec l'ancienne, mise de côté jusqu'au bout. Un échec remet l'ancienne
+/// en place ; le reste du dossier (historique git) n'est pas touché.
+fn replace_library(
+    documents: Vec<(String, Vec<u8>)>,
+    settings: &Settings,
+    vault: &Vault,
+    counts: &mut ImportCounts,
+) -> Result<(), AppError> {
+    let root = ensure_library_dir(settings)?;
+    let staging = sibling_dir(&root, "import");
+    let previous = sibling_dir(&root, "previous");
+    let result = stage_library(documents, settings, vault, &staging, counts)
+        .and_then(|_| swap_library(settings, &root, &staging, &previous));
+    let _ = fs::remove_dir_all(&staging);
+    if result.is_ok() {
+        let _ = fs::remove_dir_all(&previous);
+    }
+    result
+}
+
+fn stage_library(
+    documents: Vec<(String, Vec<u8>)>,
+    settings: &Settings,
+    vault: &Vault,
+    staging: &Path,
+    counts: &mut ImportCounts,
+) -> Result<(), AppError> {
+    fs::create_dir_all(staging).map_err(write_error)?;
+    for (name, content) in documents {
+        let target = if name == LIBRARY_MANIFEST {
+            staging.join(LIBRARY_MANIFEST)
+        } else if let Some(id) = name.strip_suffix(".json") {
+            let path = cv_file_path(settings, id)?;
+            counts.cvs += 1;
+            staging.join(path.file_name().unwrap_or_default())
+        } else {
+            continue;
+        };
+        let context = library_context(&target.file_name().unwrap_or_default().to_string_lossy());
+        fs::write(&target, vault.encode(&content, &context)?).map_err(write_error)?;
+    }
+    Ok(())
+}
+
+fn swap_library(
+    settings: &Settings,
+    root: &Path,
+    staging: &Path,
+    previous: &Path,
+) -> Result<(), AppError> {
+    let current = library_files(settings)?;
+    let mut moved = Vec::new();
+    let swap = || -> std::io::Result<()> {
+        fs::create_dir_all(previous)?;
+        for path in current {
+            let aside = previous.join(path.file_name().unwrap_or_default());
+            fs::rename(&path, &aside)?;
+            moved.push((aside, path));
+        }
+        for entry in fs::read_dir(staging)? {
+            let staged = entry?.path();
+            let target = root.join(staged.file_name().unwrap_or_default());
+            fs::rename(&staged, &target)?;
+            moved.push((staged, target));
+        }
+        Ok(())
+    };
+    swap().map_err(|error| {
+        // Défait les déplacements dans l'ordre inverse : l'ancienne bibliothèque revient.
+        for (from, to) in moved.iter().rev() {
+            let _ = fs::rename(to, from);
+        }
+        write_error(error)
+    })
+}
+
+fn import_exports(
+    files: &BTreeMap<String, Vec<u8>>,
+    mode: ImportMode,
+    settings: &Settings,
+    counts: &mut ImportCounts,
+    conflicts: &mut Vec<ImportConflict>,
+) -> Result<(), AppError> {
+    let Some(dir) = exports_root(settings) else {
+        return Ok(());
+    };
+    for (path, content) in files {
+        let Some(name) = archived_name(path, EXPORTS_DIR) else {
+            continue;
+        };
+        let target = dir.join(name);
+        if mode == ImportMode::Merge && target.exists() {
+            if fs::read(&target).map_err(read_error)? != *content {
+                conflicts.push(ImportConflict {
+                    kind: WorkspaceItem::Export,
+                    id: name.to_string(),
+                });
+            }
+            continue;
+        }
+        fs::create_dir_all(&dir).map_err(write_error)?;
+        fs::write(&target, content).map_err(write_error)?;
+        counts.exports += 1;
+    }
+    Ok(())
+}
+
+/// Archive l'état courant (même non encore sauvegardé), la bibliothèque, les préférences et
+/// les documents générés dans `path`, chiffrés par `passphrase` si elle est donnée. Avec le
+/// chiffrement actif, la phrase secrète est obligatoire : l'archive ne sort pas en clair.
+#[command]
+#[instrument(skip_all, fields(encrypted = passphrase.is_some()))]
+pub fn export_workspace(
+    path: String,
+    passphrase: Option<String>,
+    state: State<'_, SharedState>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, VaultState>,
+) -> Result<WorkspaceManifest, AppError> {
+    let passphrase = passphrase.map(Zeroizing::new);
+    let vault = vault.0.lock();
+    vault.ensure_unlocked()?;
+    if vault.status().enabled && passphrase.is_none() {
+        return Err(AppError::new("errors.workspace.passphraseRequired"));
+    }
+    let memory = state.0.lock().clone();
+    let settings = settings.0.lock().clone();
+    let files = collect_files(&settings, &vault, memory)?;
+    let (encryption, files) = match &passphrase {
+        Some(passphrase) => {
+            let (kdf, files) = seal_files(files, passphrase)?;
+            (Some(kdf), files)
+        }
+        None => (None, files),
+    };
+    let manifest = build_manifest(&files, encryption);
+    write_zip(Path::new(&path), &manifest, &files)?;
+    Ok(manifest)
+}
+
+/// Restaure une archive de `export_workspace`. Toute l'archive est vérifiée et déchiffrée
+/// avant la première écriture ; l'état est sauvegardé aussitôt après.
+#[command]
+#[instrument(skip_all, fields(mode = ?mode))]
+pub fn import_workspace(
+    path: String,
+    mode: ImportMode,
+    passphrase: Option<String>,
+    state: State<'_, SharedState>,
+    settings: State<'_, SettingsState>,
+    vault: State<'_, VaultState>,
+    app: AppHandle,
+) -> Result<ImportReport, AppError> {
+    let mut report = ImportReport {
+        mode,
+        imported: ImportCounts::default(),
+        conflicts: Vec::new(),
+        settings_replaced: false,
+    };
+    let passphrase = passphrase.map(Zeroizing::new);
+    let current_settings = settings.0.lock().clone();
+    let next_settings = {
+        let vault = vault.0.lock();
+        vault.ensure_unlocked()?;
+        let archive = read_archive(Path::new(&path))?;
+        let files = open_archive(archive, passphrase.as_deref().map(String::as_str))?;
+        let storage = files.get(STORAGE_FILE).cloned().ok_or_else(|| {
+            AppError::new("errors.workspace.missingFile").arg("path", STORAGE_FILE)
+        })?;
+        let incoming = decode_persisted(&Vault::default(), storage)
+            .map_err(|error| AppError::new("errors.workspace.invalid").arg("details", error))?;
+        let documents = library_documents(&files);
+        let incoming_settings = match files.get(SETTINGS_FILE) {
+            Some(content) if mode == ImportMode::Replace => Some(
+                serde_json::from_slice::<Settings>(content).map_err(|error| {
+                    AppError::new("errors.workspace.invalid").arg("details", error)
+                })?,
+            ),
+            _ => None,
+        };
+
+        // Le nouvel état n'est mis en place qu'une fois la bibliothèque et les exports écrits.
+        let mut next_memory: AppMemory = match mode {
+            ImportMode::Replace => {
+                let counts = &mut report.imported;
+                counts.profiles = incoming.profiles.len();
+                counts.offers = incoming.offers.len();
+                counts.adaptations = incoming.adaptations.len();
+                counts.variants = incoming.variants.len();
+                replace_library(documents, &current_settings, &vault, counts)?;
+                incoming.into()
+            }
+            ImportMode::Merge => {
+                let mut local = PersistedData::from(state.0.lock().clone());
+                merge_state(
+                    &mut local,
+                    incoming,
+                    &mut report.imported,
+                    &mut report.conflicts,
+                );
+                merge_library(
+                    documents,
+                    &current_settings,
+                    &vault,
+                    &mut report.imported,
+                    &mut report.conflicts,
+                )?;
+                local.into()
+            }
+        };
+        import_exports(
+            &files,
+            mode,
+            &current_settings,
+            &mut report.imported,
+            &mut report.conflicts,
+        )?;
+        let mut memory = state.0.lock();
+        // La base de fusion est le `storage.json` local, pas l'archive.
+        next_memory.saved = memory.saved.clone();
+        *memory = next_memory;
+        drop(memory);
+        record_change(&current_settings, "Import d'un espace de travail");
+        incoming_settings.map(|incoming| imported_settings(&current_settings, incoming))
+    };
+
+    crate::save_state(&app).map_err(write_error)?;
+    if let Some(next) = next_settings {
+        apply_settings(&app, next)?;
+        report.settings_replaced = true;
+    }
+    report.conflicts.sort();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use super::{
+        archived_name, build_manifest, exported_settings, imported_settings, merge_items,
+        open_archive, read_archive, replace_library, seal_files, write_zip, ArchiveContent,
+        ImportConflict, ImportCounts, WorkspaceItem,
+    };
+    use crate::{settings::Settings, vault::Vault};
+
+    #[test]
+    fn rejects_tampered_archives() {
+        let dir = std::env::temp_dir().join(format!("cvgen-workspace-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("workspace.zip");
+        let files = vec![
+            ("storage.json".to_string(), b"{}".to_vec()),
+            ("library/cv1.json".to_string(), b"{\"id\":\"cv1\"}".to_vec()),
+        ];
+        let manifest = build_manifest(&files, None);
+        write_zip(&path, &manifest, &files).unwrap();
+        let archive = read_archive(&path).unwrap();
+        assert_eq!(archive.files["library/cv1.json"], files[1].1);
+
+        let tampered = vec![
+            files[0].clone(),
+            ("library/cv1.json".into(), b"{}".to_vec()),
+        ];
+        write_zip(&path, &manifest, &tampered).unwrap();
+        let error = read_archive(&path).err().unwrap();
+        assert_eq!(error.key, "errors.workspace.checksum");
+        assert_eq!(error.args["path"], "library/cv1.json");
+
+        assert_eq!(
+            archived_name("library/cv1.json", "library"),
+            Some("cv1.json")
+        );
+        assert_eq!(archived_name("library/../cv1.json", "library"), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn sealed_archives_need_their_passphrase() {
+        let files = vec![
+            ("storage.json".to_string(), b"{}".to_vec()),
+            ("library/cv1.json".to_string(), b"{\"id\":\"cv1\"}".to_vec()),
+        ];
+        let (kdf, sealed) = seal_files(files.clone(), "correct horse").unwrap();
+        assert_ne!(sealed[1].1, files[1].1);
+        let archive = || ArchiveContent {
+            manifest: build_manifest(&sealed, Some(kdf.clone())),
+            files: sealed.iter().cloned().collect(),
+        };
+
+        let opened = open_archive(archive(), Some("correct horse")).unwrap();
+        assert_eq!(opened["library/cv1.json"], files[1].1);
+        assert_eq!(
+            open_archive(archive(), None).unwrap_err().key,
+            "errors.workspace.passphraseRequired"
+        );
+        assert_eq!(
+            open_archive(archive(), Some("wrong horse!"))
+                .unwrap_err()
+                .key,
+            "errors.workspace.wrongPassphrase"
+        );
+    }
+
+    #[test]
+    fn replace_swaps_the_library_and_keeps_its_history() {
+        let root = std::env::temp_dir().join(format!("cvgen-replace-{}", uuid::Uuid::new_v4()));
+        let library = root.join("library");
+        fs::create_dir_all(library.join(".git")).unwrap();
+        fs::write(library.join("old.json"), b"{}").unwrap();
+        let settings = Settings {
+            library_dir: Some(library.clone()),
+            ..Settings::default()
+        };
+        let documents = vec![
+            ("cv1.json".to_string(), b"{\"id\":\"cv1\"}".to_vec()),
+            ("manifest.json".to_string(), b"{\"items\":{}}".to_vec()),
+        ];
+        let mut counts = ImportCounts::default();
+        replace_library(documents, &settings, &Vault::default(), &mut counts).unwrap();
+
+        assert_eq!(counts.cvs, 1);
+        assert!(!library.join("old.json").exists());
+        assert_eq!(
+            fs::read(library.join("cv1.json")).unwrap(),
+            b"{\"id\":\"cv1\"}"
+        );
+        assert!(library.join("manifest.json").exists() && library.join(".git").is_dir());
+        // Les dossiers de préparation et de mise de côté ne restent pas.
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn settings_secrets_stay_on_their_machine() {
+        let mut local = Settings::default();
+        local.http_api.token = Some("local-token".into());
+        local.llm.api_key = Some("local-key".into());
+        let mut remote = Settings::default();
+        remote.http_api.token = Some("remote-token".into());
+        remote.llm.api_key = Some("remote-key".into());
+        remote.http_api.port = 4321;
+
+        let archived = exported_settings(&remote);
+        assert_eq!(
+            (archived.http_api.token, archived.llm.api_key),
+            (None, None)
+        );
+        let imported = imported_settings(&local, remote);
+        assert_eq!(imported.http_api.port, 4321);
+        assert_eq!(imported.http_api.token.as_deref(), Some("local-token"));
+        assert_eq!(imported.llm.api_key.as_deref(), Some("local-key"));
+    }
+
+    #[test]
+    fn merge_keeps_local_items_and_reports_conflicts() {
+        let mut local = HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]);
+        let incoming = HashMap::from([
+            ("a".to_string(), 1),
+            ("b".to_string(), 3),
+            ("c".to_string(), 4),
+        ]);
+        let mut conflicts = Vec::new();
+        let added = merge_items(WorkspaceItem::Offer, &mut local, incoming, &mut conflicts);
+        assert_eq!(added, 1);
+        assert_eq!((local["b"], local["c"]), (2, 4));
+        assert_eq!(
+            conflicts,
+            [ImportConflict {
+                kind: WorkspaceItem::Offer,
+                id: "b".into()
+            }]
+        );
+    }
+}
@@ -10,11 +10,17 @@ export function HighlightText({ text, highlights = [] }: HighlightTextProps) {
     return <p className="whitespace-pre-wrap leading-relaxed text-slate-700">{text}</p>;
   }
 
-  const sorted = [...highlights].sort((a, b) => a.start - b.start);
+  // Les indices UTF-16 correspondent à ceux des chaînes JavaScript (émojis compris).
+  const sorted = highlights
+    .map((span) => ({ ...span, start: span.utf16?.start ?? span.start, end: span.utf16?.end ?? span.end }))
+    .sort((a, b) => a.start - b.start);
   const output: Array<{ key: string; value: string; className?: string }> = [];
   let index = 0;
 
   for (const span of sorted) {
+    if (span.start < index) {
+      continue;
+    }
     if (span.start > index) {
       output.push({
         key: `${index}-${span.start}`,
@@ -22,12 +28,22 @@ export function HighlightText({ text, highlights = [] }: HighlightTextProps) {
       });
     }
 
+    if (span.type === 'removal' && span.start === span.end) {
+      if (span.removedText) {
+        output.push({ key: span.id, value: span.removedText, className: 'highlight-remove rounded line-through' });
+      }
+      index = span.start;
+      continue;
+    }
+
     const className =
       span.type === 'addition'
         ? 'highlight-add rounded'
         : span.type === 'removal'
           ? 'highlight-remove rounded'
-          : 'underline decoration-emerald-400 decoration-2 underline-offset-4';
+          : span.type === 'userEdit'
+            ? 'rounded bg-sky-100'
+            : 'underline decoration-emerald-400 decoration-2 underline-offset-4';
 
     output.push({
       key: span.id,
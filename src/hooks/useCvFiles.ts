@@ -15,7 +15,49 @@ export interface CvData {
   meta?: Record<string, any>;
 }
 
-export interface CvMeta { id: string; title: string; updatedAt: string }
+export interface CvMeta {
+  id: string;
+  title: string;
+  updatedAt: string;
+  createdAt?: string;
+  tags: string[];
+  folder: string; // Dossier virtuel ("" = racine)
+  favorite: boolean;
+  offerIds: string[];
+}
+
+export interface CvListFilter {
+  folder?: string;
+  recursive?: boolean;
+  tags?: string[];
+  favorite?: boolean;
+  offerId?: string;
+  sortBy?: 'updatedAt' | 'createdAt' | 'title' | 'folder' | 'favorite';
+  descending?: boolean;
+}
+
+export interface CvRevision {
+  revision: string;
+  at: string;
+  message: string;
+  deleted: boolean;
+}
+
+export interface CvDiff {
+  from: string;
+  to?: string | null; // Absent : fichier actuel
+  patch: string; // Différence unifiée
+  additions: number;
+  deletions: number;
+}
+
+export interface LibraryReport {
+  orphans: string[]; // Fichiers sans entrée dans manifest.json
+  missing: string[]; // Entrées sans fichier
+  unparseable: { id: string; details: string }[];
+  stale: string[]; // Titre ou date de l'entrée périmés
+  manifestError?: string;
+}
 
 export function useCvFiles() {
   async function listCvs(): Promise<string[]> {
@@ -24,18 +66,69 @@ export function useCvFiles() {
     return [];
   }
 
-  async function listCvsMeta(): Promise<CvMeta[]> {
-    const res = await invoke<any>('list_cvs_meta');
+  async function listCvsMeta(filter?: CvListFilter): Promise<CvMeta[]> {
+    const res = await invoke<any>('list_cvs_meta', { filter });
     if (Array.isArray(res)) {
       return res.filter(r => r && typeof r === 'object' && r.id).map(r => ({
         id: r.id,
         title: r.title || 'CV',
         updatedAt: r.updatedAt || '',
+        createdAt: r.createdAt,
+        tags: r.tags || [],
+        folder: r.folder || '',
+        favorite: Boolean(r.favorite),
+        offerIds: r.offerIds || [],
       }));
     }
     return [];
   }
 
+  async function updateCvMeta(command: string, args: Record<string, unknown>): Promise<CvMeta | null> {
+    try {
+      return await invoke<CvMeta>(command, args);
+    } catch (e) {
+      console.error(`${command} error`, e);
+      return null;
+    }
+  }
+
+  const tagCv = (id: string, tags: string[]) => updateCvMeta('tag_cv', { id, tags });
+  const moveCv = (id: string, folder: string) => updateCvMeta('move_cv', { id, folder });
+  const favoriteCv = (id: string, favorite: boolean) => updateCvMeta('favorite_cv', { id, favorite });
+  const linkCvOffers = (id: string, offerIds: string[]) => updateCvMeta('link_cv_offers', { id, offerIds });
+
+  const checkLibrary = () => invoke<LibraryReport>('check_library');
+  // Reconstruit manifest.json depuis les fichiers ; renvoie ce qui reste à corriger.
+  const repairLibrary = () => invoke<LibraryReport>('repair_library');
+
+  // Historique git de la bibliothèque (préférence `libraryGit`).
+  async function cvHistory(id: string): Promise<CvRevision[]> {
+    try {
+      return await invoke<CvRevision[]>('cv_history', { id });
+    } catch (e) {
+      console.error('cvHistory error', e);
+      return [];
+    }
+  }
+
+  async function cvDiff(id: string, from: string, to?: string): Promise<CvDiff | null> {
+    try {
+      return await invoke<CvDiff>('cv_diff', { id, from, to });
+    } catch (e) {
+      console.error('cvDiff error', e);
+      return null;
+    }
+  }
+
+  async function restoreCvRevision(id: string, revision: string): Promise<CvData | null> {
+    try {
+      return await invoke<CvData>('restore_cv_revision', { id, revision });
+    } catch (e) {
+      console.error('restoreCvRevision error', e);
+      return null;
+    }
+  }
+
   async function loadCv(id: string): Promise<CvData | null> {
     try {
       const data = await invoke<any>('load_cv', { id });
@@ -67,5 +160,8 @@ export function useCvFiles() {
     }
   }
 
-  return { listCvs, listCvsMeta, loadCv, saveCv, deleteCv };
+  return {
+    listCvs, listCvsMeta, loadCv, saveCv, deleteCv, tagCv, moveCv, favoriteCv, linkCvOffers,
+    cvHistory, cvDiff, restoreCvRevision, checkLibrary, repairLibrary,
+  };
 }
@@ -1,5 +1,25 @@
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import { invoke } from '@tauri-apps/api/tauri';
-import { AdaptationResult, ExtractionPayload, ExportPayload, JobOffer } from '../types';
+import {
+  AdaptationResult,
+  CoverLetter,
+  ExtractionPayload,
+  ExportPayload,
+  JobOffer,
+  SearchHit,
+  SearchKind,
+} from '../types';
+
+/** Erreur structurée renvoyée par les commandes Tauri (clé de catalogue + texte rendu). */
+export interface BackendError {
+  key: string;
+  args: Record<string, string>;
+  message: string;
+}
+
+function isBackendError(error: unknown): error is BackendError {
+  return typeof error === 'object' && error !== null && 'key' in error && 'message' in error;
+}
 
 async function callBackend<T>(command: string, args: Record<string, unknown>): Promise<T> {
   try {
@@ -14,6 +34,9 @@ async function callBackend<T>(command: string, args: Record<string, unknown>): P
     if (typeof error === 'string') {
       throw new Error(error);
     }
+    if (isBackendError(error)) {
+      throw Object.assign(new Error(error.message), { key: error.key, args: error.args });
+    }
     throw new Error(JSON.stringify(error));
   }
 }
@@ -26,10 +49,130 @@ export function analyzeOffer(offer: JobOffer): Promise<JobOffer> {
   return callBackend<JobOffer>('analyze_offer', { offer });
 }
 
-export function adaptDocuments(profileId: string, offerId: string): Promise<AdaptationResult> {
-  return callBackend<AdaptationResult>('adapt_documents', { profileId, offerId });
+/** Importe une offre depuis une page d'annonce enregistrée (fichier) ou son code HTML. */
+export function importOfferHtml(source: { html?: string; filePath?: string }): Promise<JobOffer> {
+  return callBackend<JobOffer>('import_offer_html', source);
+}
+
+/** Offre importée d'un e-mail ; `warnings` liste les pièces jointes illisibles, ignorées. */
+export interface EmailOffer {
+  offer: JobOffer;
+  warnings: string[];
+}
+
+/** Importe une offre reçue par e-mail (`.eml`), pièces jointes PDF/DOCX comprises. */
+export function importOfferEml(filePath: string): Promise<EmailOffer> {
+  return callBackend<EmailOffer>('import_offer_eml', { filePath });
+}
+
+export function adaptDocuments(
+  profileId: string,
+  offerId: string,
+  options: { regenerate?: boolean; discardEdits?: boolean } = {},
+): Promise<AdaptationResult> {
+  return callBackend<AdaptationResult>('adapt_documents', { profileId, offerId, ...options });
+}
+
+/** Enregistre le CV et/ou la lettre retouchés ; les passages saisis sont marqués `userEdit`. */
+export function updateAdaptation(edit: {
+  profileId: string;
+  variantId?: string;
+  adaptedResume?: string;
+  adaptedCoverLetter?: CoverLetter;
+}): Promise<AdaptationResult> {
+  return callBackend<AdaptationResult>('update_adaptation', edit);
+}
+
+/** Recherche plein texte dans les profils, offres et adaptations (mots, `préfixe*`, "expression"). */
+export function search(
+  query: string,
+  options: { kinds?: SearchKind[]; limit?: number } = {},
+): Promise<SearchHit[]> {
+  return callBackend<SearchHit[]>('search', { query, ...options });
+}
+
+/** Document suivi par le journal d'annulation : profil ou CV de la bibliothèque. */
+export type JournalKind = 'profile' | 'cv';
+
+export interface JournalEntry {
+  kind: JournalKind;
+  id: string;
+  at: string;
+  before: unknown | null; // État complet avant la modification (null : document absent) ; CV : { document, entry }
+  after: unknown | null;
+}
+
+/** Annule la dernière modification du document ; renvoie l'entrée annulée. */
+export function undo(kind: JournalKind, id: string): Promise<JournalEntry> {
+  return callBackend<JournalEntry>('undo', { kind, id });
+}
+
+export function redo(kind: JournalKind, id: string): Promise<JournalEntry> {
+  return callBackend<JournalEntry>('redo', { kind, id });
+}
+
+export function journalHistory(
+  kind: JournalKind,
+  id: string,
+): Promise<{ done: JournalEntry[]; undone: JournalEntry[] }> {
+  return callBackend('journal_history', { kind, id });
+}
+
+/** Fichier d'une archive d'espace de travail, avec sa taille et son empreinte SHA-256. */
+export interface WorkspaceManifest {
+  format: number;
+  createdAt: string;
+  appVersion: string;
+  encryption?: { kdf: { memoryKib: number; iterations: number; parallelism: number }; salt: string }; // Chiffrée par une phrase secrète
+  files: { path: string; size: number; sha256: string }[];
+}
+
+export type ImportMode = 'merge' | 'replace';
+
+export interface ImportReport {
+  mode: ImportMode;
+  imported: { profiles: number; offers: number; adaptations: number; variants: number; cvs: number; exports: number };
+  conflicts: { kind: 'profile' | 'offer' | 'adaptation' | 'variant' | 'cv' | 'export'; id: string }[];
+  settingsReplaced: boolean;
+}
+
+/** Archive zip de l'état, de la bibliothèque, des préférences et des exports ; `passphrase` est obligatoire si le chiffrement est actif. */
+export function exportWorkspace(path: string, passphrase?: string): Promise<WorkspaceManifest> {
+  return callBackend<WorkspaceManifest>('export_workspace', { path, passphrase });
+}
+
+export function importWorkspace(path: string, mode: ImportMode, passphrase?: string): Promise<ImportReport> {
+  return callBackend<ImportReport>('import_workspace', { path, mode, passphrase });
 }
 
 export function exportDocuments(profileId: string, format: 'pdf' | 'docx'): Promise<ExportPayload> {
   return callBackend<ExportPayload>('export_documents', { profileId, format });
 }
+
+/** Donnée ajoutée par un client de l'API HTTP locale (offre, adaptation, export). */
+export interface ExternalChange {
+  kind: 'offer' | 'adaptation' | 'export';
+  [field: string]: unknown;
+}
+
+export function onExternalChange(callback: (change: ExternalChange) => void): Promise<UnlistenFn> {
+  return listen<ExternalChange>('external-data-changed', (event) => callback(event.payload));
+}
+
+/** Fichier traité depuis le dossier d'arrivée (`inboxDir`). */
+export interface InboxResult {
+  file: string;
+  kind: 'cv' | 'offer';
+  id?: string | null;
+  archivedTo?: string | null;
+  error?: { key: string; args: Record<string, string>; message: string } | null;
+}
+
+export function onInboxResult(callback: (result: InboxResult) => void): Promise<UnlistenFn> {
+  return listen<InboxResult>('inbox-file-processed', (event) => callback(event.payload));
+}
+
+/** Fragment de texte produit par le moteur `llm` pendant une adaptation. */
+export function onAdaptationProgress(callback: (delta: string) => void): Promise<UnlistenFn> {
+  return listen<{ delta: string }>('adaptation-progress', (event) => callback(event.payload.delta));
+}
@@ -25,5 +25,50 @@ export interface CandidateProfile {
 }
 
 export interface JobOffer { id?: string; title?: string; company?: string; description?: string }
+export interface OffsetRange { start: number; end: number }
+export interface HighlightSpan {
+  id: string;
+  type: 'addition' | 'removal' | 'emphasis' | 'userEdit';
+  start: number;
+  end: number;
+  utf8?: OffsetRange;
+  utf16?: OffsetRange; // Indices des chaînes JavaScript (à préférer à start/end, en caractères)
+  removedText?: string;
+}
+export interface CoverLetter {
+  sender: string[];
+  recipient: string[];
+  date?: string | null;
+  subject?: string | null;
+  salutation: string;
+  paragraphs: string[];
+  closing: string;
+  signature: string;
+}
+export interface Inline { text: string; emphasis?: boolean }
+export type RichText = Inline[];
+export interface ResumeEntry {
+  title: RichText;
+  period?: string | null;
+  bullets: RichText[];
+  tags?: { label: string; items: RichText[] } | null;
+}
+export type ResumeBlock =
+  | { type: 'paragraph'; text: RichText }
+  | { type: 'tags'; items: RichText[] }
+  | { type: 'list'; items: RichText[] }
+  | ({ type: 'entry' } & ResumeEntry);
+export interface ResumeSection { heading?: string | null; blocks: ResumeBlock[] }
+export interface ResumeDocument { name: string; sections: ResumeSection[] }
 export interface AdaptationResult { id?: string; summary?: string; highlights?: string[] }
+export type SearchKind = 'profile' | 'offer' | 'adaptation';
+export interface SearchHit {
+  kind: SearchKind;
+  id: string; // Pour une adaptation : clé `profil` ou `profil:variante`
+  title: string;
+  score: number;
+  field: string;
+  snippet: string;
+  matches: OffsetRange[]; // En caractères dans `snippet`
+}
 export interface ExtractionPayload { rawText?: string; tokens?: string[] }